@@ -0,0 +1,109 @@
+//! Fixed-point monetary amount for capital-tier and fee math
+//!
+//! `CapitalTier`/`TierParameters`/`FeeTier` used to take and return bare `f64` dollar
+//! values, and chaining `round_trip_percent()` into `min_profitable_tp()` into
+//! `min_position_for_profit()` let rounding drift accumulate across the chain the same
+//! way `money` warns `f64` can for order sizing. `Amount` stores an integer cent count
+//! instead, with checked arithmetic so overflow surfaces as `None` rather than a wrapped
+//! or silently wrong total; `from_dollars`/`to_dollars` are the only places a plain
+//! `f64` crosses the boundary, same as `money::decimal_from_f64` for order sizing.
+
+use serde::{Serialize, Serializer};
+
+/// A dollar-denominated amount stored as integer cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Amount {
+    cents: i64,
+}
+
+impl Serialize for Amount {
+    /// Serializes as a fixed two-decimal-place string (e.g. `"19.99"`, `"-5.00"`)
+    /// rather than `to_dollars()`'s bare `f64`, so dashboards/snapshot tests get a
+    /// deterministic, lossless representation that can never render in scientific
+    /// notation the way a very small or very large `f64` could.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let abs_cents = self.cents.unsigned_abs();
+        serializer.serialize_str(&format!("{sign}{}.{:02}", abs_cents / 100, abs_cents % 100))
+    }
+}
+
+impl Amount {
+    pub const ZERO: Amount = Amount { cents: 0 };
+
+    /// Build an `Amount` from a dollar-denominated `f64`, rounding to the nearest cent.
+    pub fn from_dollars(dollars: f64) -> Self {
+        Amount { cents: (dollars * 100.0).round() as i64 }
+    }
+
+    /// Back to a dollar-denominated `f64` - only for display/serialization at the API
+    /// boundary, not for further arithmetic.
+    pub fn to_dollars(self) -> f64 {
+        self.cents as f64 / 100.0
+    }
+
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.cents.checked_add(other.cents).map(|cents| Amount { cents })
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.cents.checked_sub(other.cents).map(|cents| Amount { cents })
+    }
+
+    /// Scale by a plain ratio (e.g. a tier's `max_position_percent / 100.0`), rounding
+    /// to the nearest cent. Returns `None` if the result doesn't fit in `i64` cents.
+    pub fn checked_mul(self, factor: f64) -> Option<Amount> {
+        let scaled = self.cents as f64 * factor;
+        if scaled.is_finite() && scaled.abs() < i64::MAX as f64 {
+            Some(Amount { cents: scaled.round() as i64 })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_dollars_rounds_to_nearest_cent() {
+        assert_eq!(Amount::from_dollars(19.999).to_dollars(), 20.0);
+        // -5.005 * 100.0 is exactly -500.5 in f64, and `round` takes a half away from zero.
+        assert_eq!(Amount::from_dollars(-5.005).to_dollars(), -5.01);
+    }
+
+    #[test]
+    fn test_checked_add_and_sub() {
+        let a = Amount::from_dollars(10.0);
+        let b = Amount::from_dollars(2.5);
+        assert_eq!(a.checked_add(b).unwrap().to_dollars(), 12.5);
+        assert_eq!(a.checked_sub(b).unwrap().to_dollars(), 7.5);
+    }
+
+    #[test]
+    fn test_checked_add_overflow_returns_none() {
+        let max = Amount { cents: i64::MAX };
+        assert_eq!(max.checked_add(Amount::from_dollars(1.0)), None);
+    }
+
+    #[test]
+    fn test_checked_mul_scales_by_ratio() {
+        let amount = Amount::from_dollars(200.0);
+        assert_eq!(amount.checked_mul(0.25).unwrap().to_dollars(), 50.0);
+    }
+
+    #[test]
+    fn test_checked_mul_non_finite_returns_none() {
+        let amount = Amount::from_dollars(100.0);
+        assert_eq!(amount.checked_mul(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_serialize_renders_fixed_two_decimals() {
+        assert_eq!(serde_json::to_string(&Amount::from_dollars(19.99)).unwrap(), "\"19.99\"");
+        assert_eq!(serde_json::to_string(&Amount::from_dollars(5.0)).unwrap(), "\"5.00\"");
+        assert_eq!(serde_json::to_string(&Amount::from_dollars(-5.5)).unwrap(), "\"-5.50\"");
+        assert_eq!(serde_json::to_string(&Amount::ZERO).unwrap(), "\"0.00\"");
+    }
+}
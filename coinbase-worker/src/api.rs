@@ -0,0 +1,215 @@
+//! Unified typed API response envelope
+//!
+//! Route handlers used to be inconsistent: `/health` returned a raw HTTP 500 on a
+//! config error, while `/api/portfolio`, `/api/balance`, and `/api/trigger` all
+//! returned HTTP 200 with an ad-hoc `{"error": ...}` body, and `/api/trigger`
+//! additionally hand-parsed an `error_type` out of `{e:?}`. `ApiResponse<T>` gives
+//! every route a single discriminated shape (`ok` tells the client which of
+//! `data`/`error` is populated), and `ApiError` mirrors `TradingError` with a
+//! stable, serializable shape mapped to the right HTTP status.
+
+use crate::error::TradingError;
+use serde::Serialize;
+use worker::{Response, Result as WResult};
+
+/// Discriminated response envelope returned by every JSON route.
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    /// Build and serialize a successful `200 OK` response.
+    pub fn ok(data: T) -> WResult<Response> {
+        Response::from_json(&ApiResponse { ok: true, data: Some(data), error: None })
+    }
+}
+
+impl ApiResponse<()> {
+    /// Build and serialize an error response, with the HTTP status taken from
+    /// `ApiError::status_code`.
+    pub fn err(error: &TradingError) -> WResult<Response> {
+        let api_error = ApiError::from(error);
+        let status = api_error.status_code();
+        Response::from_json(&ApiResponse::<()> { ok: false, data: None, error: Some(api_error) })
+            .map(|r| r.with_status(status))
+    }
+}
+
+/// Serializable, stable-shape mirror of `TradingError`, so the public JSON
+/// contract can't silently drift if a variant's `Display` message wording changes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum ApiError {
+    Config(String),
+    Auth(String),
+    CoinbaseApi(String),
+    Http(String),
+    Json(String),
+    Trading(String),
+    OrderValidation(String),
+    RateLimit { retry_after_seconds: u64 },
+    InsufficientFunds { required: f64, available: f64 },
+    PositionNotFound(String),
+    Worker(String),
+    Storage(String),
+}
+
+impl From<&TradingError> for ApiError {
+    fn from(err: &TradingError) -> Self {
+        match err {
+            TradingError::Config(m) => ApiError::Config(m.clone()),
+            TradingError::Auth(m) => ApiError::Auth(m.clone()),
+            TradingError::CoinbaseApi(m) => ApiError::CoinbaseApi(m.clone()),
+            TradingError::Http(m) => ApiError::Http(m.clone()),
+            TradingError::Json(e) => ApiError::Json(e.to_string()),
+            TradingError::Trading(m) => ApiError::Trading(m.clone()),
+            TradingError::OrderValidation(m) => ApiError::OrderValidation(m.clone()),
+            TradingError::RateLimit(seconds) => ApiError::RateLimit { retry_after_seconds: *seconds },
+            TradingError::InsufficientFunds { required, available } => {
+                ApiError::InsufficientFunds { required: *required, available: *available }
+            }
+            TradingError::PositionNotFound(m) => ApiError::PositionNotFound(m.clone()),
+            TradingError::Worker(m) => ApiError::Worker(m.clone()),
+            TradingError::Storage(m) => ApiError::Storage(m.clone()),
+        }
+    }
+}
+
+impl ApiError {
+    /// Maps each `TradingError` variant to the HTTP status a client should see:
+    /// auth failures are 401, config/internal/storage failures are 500, rate
+    /// limiting is 429, upstream market-data failures are 502, and malformed
+    /// requests or missing resources are 400/404.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            ApiError::Auth(_) => 401,
+            ApiError::Config(_) | ApiError::Worker(_) | ApiError::Storage(_) | ApiError::Trading(_) => 500,
+            ApiError::RateLimit { .. } => 429,
+            ApiError::CoinbaseApi(_) | ApiError::Http(_) => 502,
+            ApiError::OrderValidation(_) | ApiError::Json(_) | ApiError::InsufficientFunds { .. } => 400,
+            ApiError::PositionNotFound(_) => 404,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_code_mapping() {
+        assert_eq!(ApiError::Auth(String::new()).status_code(), 401);
+        assert_eq!(ApiError::Config(String::new()).status_code(), 500);
+        assert_eq!(ApiError::Worker(String::new()).status_code(), 500);
+        assert_eq!(ApiError::Storage(String::new()).status_code(), 500);
+        assert_eq!(ApiError::Trading(String::new()).status_code(), 500);
+        assert_eq!(ApiError::RateLimit { retry_after_seconds: 5 }.status_code(), 429);
+        assert_eq!(ApiError::CoinbaseApi(String::new()).status_code(), 502);
+        assert_eq!(ApiError::Http(String::new()).status_code(), 502);
+        assert_eq!(ApiError::OrderValidation(String::new()).status_code(), 400);
+        assert_eq!(ApiError::InsufficientFunds { required: 1.0, available: 0.0 }.status_code(), 400);
+        assert_eq!(ApiError::PositionNotFound(String::new()).status_code(), 404);
+    }
+
+    #[test]
+    fn test_api_error_json_shape_config() {
+        let err = ApiError::from(&TradingError::Config("missing key".to_string()));
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(json, r#"{"kind":"Config","message":"missing key"}"#);
+    }
+
+    #[test]
+    fn test_api_error_json_shape_rate_limit() {
+        let err = ApiError::from(&TradingError::RateLimit(30));
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(json, r#"{"kind":"RateLimit","message":{"retry_after_seconds":30}}"#);
+    }
+
+    #[test]
+    fn test_api_error_json_shape_insufficient_funds() {
+        let err = ApiError::from(&TradingError::InsufficientFunds { required: 100.0, available: 50.0 });
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(json, r#"{"kind":"InsufficientFunds","message":{"required":100.0,"available":50.0}}"#);
+    }
+
+    #[test]
+    fn test_api_error_json_shape_position_not_found() {
+        let err = ApiError::from(&TradingError::PositionNotFound("BTC-USD".to_string()));
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(json, r#"{"kind":"PositionNotFound","message":"BTC-USD"}"#);
+    }
+
+    #[test]
+    fn test_api_response_success_envelope_shape() {
+        let response = ApiResponse { ok: true, data: Some(serde_json::json!({"price": 42})), error: None::<ApiError> };
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(json, r#"{"ok":true,"data":{"price":42}}"#);
+    }
+
+    #[test]
+    fn test_api_response_error_envelope_shape() {
+        let response = ApiResponse::<()> {
+            ok: false,
+            data: None,
+            error: Some(ApiError::from(&TradingError::Auth("bad key".to_string()))),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(json, r#"{"ok":false,"error":{"kind":"Auth","message":"bad key"}}"#);
+    }
+
+    /// Pins the `/api/portfolio` success shape (see `get_portfolio_with_pnl` in
+    /// `lib.rs`) wrapped in `ApiResponse`, so its field names can't silently drift.
+    #[test]
+    fn test_portfolio_success_payload_shape() {
+        let payload = serde_json::json!({
+            "summary": {
+                "base_currency": "USD",
+                "cash_balance": "$500.00",
+                "positions_value": "$120.00",
+                "total_portfolio": "$620.00",
+                "total_invested": "$100.00",
+                "unrealized_pnl": "+$20.00",
+                "pnl_percent": "+20.00%",
+                "realized_pnl": "$10.00",
+                "total_trades": 3,
+                "liquidation_value": "$115.00",
+                "total_portfolio_liquidation": "$615.00",
+            },
+            "positions": [],
+        });
+        let response = ApiResponse { ok: true, data: Some(payload), error: None::<ApiError> };
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ok":true,"data":{"positions":[],"summary":{"base_currency":"USD","cash_balance":"$500.00","liquidation_value":"$115.00","pnl_percent":"+20.00%","positions_value":"$120.00","realized_pnl":"$10.00","total_invested":"$100.00","total_portfolio":"$620.00","total_portfolio_liquidation":"$615.00","total_trades":3,"unrealized_pnl":"+$20.00"}}}"#
+        );
+    }
+
+    /// Pins the `/api/scan` success payload shape (see `scan_all_symbols` in
+    /// `lib.rs`), so its field names can't silently drift. `/api/scan` isn't
+    /// migrated to `ApiResponse` here - it already returns `200` on success with
+    /// no ad-hoc error body on that path, so only its payload shape is pinned.
+    #[test]
+    fn test_scan_success_payload_shape() {
+        let payload = serde_json::json!({
+            "market_regime": {
+                "status": "BULLISH",
+                "btc_24h_change": "1.50%",
+                "can_open_new": true,
+            },
+            "positions": 2,
+            "max_positions": 5,
+            "symbols": [],
+        });
+        let json = serde_json::to_string(&payload).unwrap();
+        assert_eq!(
+            json,
+            r#"{"market_regime":{"btc_24h_change":"1.50%","can_open_new":true,"status":"BULLISH"},"max_positions":5,"positions":2,"symbols":[]}"#
+        );
+    }
+}
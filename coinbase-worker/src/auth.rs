@@ -8,17 +8,46 @@ use p256::ecdsa::{SigningKey, Signature, signature::Signer};
 use p256::pkcs8::DecodePrivateKey;
 use p256::SecretKey;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use worker::Env;
 
 use crate::error::{Result, TradingError};
 
+/// Default host baked into a REST JWT's `uri` claim; overridable via `with_host` for
+/// the sandbox REST host. The WebSocket feed uses `generate_ws_jwt`, which has no
+/// `uri` claim at all, so it never needs this.
+const DEFAULT_HOST: &str = "api.coinbase.com";
+
+/// How many seconds before `exp` a cached token is treated as too stale to reuse and
+/// regenerated instead. Gives callers a safety margin so a token doesn't expire
+/// mid-flight on a slow request.
+const TOKEN_REFRESH_WINDOW_SECONDS: i64 = 15;
+
+/// A previously signed token, cached so repeated calls for the same `(method, path)`
+/// (or the same websocket call) don't mint a fresh one every time.
+struct CachedToken {
+    /// `Some((method, path))` for a REST token, `None` for a websocket token.
+    key: Option<(String, String)>,
+    token: String,
+    exp: i64,
+}
+
 /// Coinbase API authentication handler
 pub struct CoinbaseAuth {
     /// API Key Name (e.g., "organizations/.../apiKeys/...")
     api_key_name: String,
-    
+
     /// ECDSA signing key
     signing_key: SigningKey,
+
+    /// Host baked into a REST JWT's `uri` claim (see `DEFAULT_HOST`/`with_host`).
+    host: String,
+
+    /// Last signed token, reused by `generate_jwt`/`generate_ws_jwt` until it falls
+    /// within `TOKEN_REFRESH_WINDOW_SECONDS` of expiring. `RefCell` since both
+    /// methods take `&self` - the same interior-mutability trick `CoinbaseClient`
+    /// uses for its rate limiter's `TokenBucket`.
+    cache: RefCell<Option<CachedToken>>,
 }
 
 /// JWT claims for Coinbase API
@@ -83,24 +112,81 @@ impl CoinbaseAuth {
         Ok(Self {
             api_key_name,
             signing_key,
+            host: DEFAULT_HOST.to_string(),
+            cache: RefCell::new(None),
         })
     }
-    
-    /// Generate JWT token for API request
+
+    /// Use a non-default host (e.g. a sandbox REST host) in the `uri` claim of
+    /// future REST tokens. Builder-style so `CoinbaseAuth::new(...)?.with_host(...)`
+    /// reads as one expression at the construction site.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Generate JWT token for API request, reusing the cached token for this exact
+    /// `(method, path)` until it's within `TOKEN_REFRESH_WINDOW_SECONDS` of expiring.
     pub fn generate_jwt(&self, method: &str, path: &str) -> Result<String> {
+        let key = (method.to_string(), path.to_string());
+        if let Some(token) = self.reuse_cached(Some(&key)) {
+            return Ok(token);
+        }
+
+        // URI format: "METHOD host/path"
+        let uri = format!("{method} {}{path}", self.host);
+        let (token, exp) = self.sign_claims(Some(uri))?;
+        *self.cache.borrow_mut() = Some(CachedToken { key: Some(key), token: token.clone(), exp });
+        Ok(token)
+    }
+
+    /// Generate a JWT for the WebSocket feed's `subscribe` message. Unlike REST
+    /// requests, channel auth isn't tied to a single method+path, so `uri` is omitted
+    /// (see `JwtClaims::uri`). Reuses the cached websocket token the same way
+    /// `generate_jwt` reuses a REST one.
+    pub fn generate_ws_jwt(&self) -> Result<String> {
+        if let Some(token) = self.reuse_cached(None) {
+            return Ok(token);
+        }
+
+        let (token, exp) = self.sign_claims(None)?;
+        *self.cache.borrow_mut() = Some(CachedToken { key: None, token: token.clone(), exp });
+        Ok(token)
+    }
+
+    /// The currently cached token and its remaining lifetime in seconds, if one is
+    /// cached and still outside the refresh window - i.e. what the next matching
+    /// `generate_jwt`/`generate_ws_jwt` call would reuse rather than regenerate.
+    pub fn cached_jwt(&self) -> Option<(String, i64)> {
+        let cache = self.cache.borrow();
+        let cached = cache.as_ref()?;
+        let remaining = cached.exp - chrono::Utc::now().timestamp();
+        (remaining > TOKEN_REFRESH_WINDOW_SECONDS).then(|| (cached.token.clone(), remaining))
+    }
+
+    /// Returns the cached token for `key` if it's still outside the refresh window.
+    fn reuse_cached(&self, key: Option<&(String, String)>) -> Option<String> {
+        let cache = self.cache.borrow();
+        let cached = cache.as_ref()?;
+        if cached.key.as_ref() != key {
+            return None;
+        }
+        let remaining = cached.exp - chrono::Utc::now().timestamp();
+        (remaining > TOKEN_REFRESH_WINDOW_SECONDS).then(|| cached.token.clone())
+    }
+
+    fn sign_claims(&self, uri: Option<String>) -> Result<(String, i64)> {
         let now = chrono::Utc::now().timestamp();
-        
-        // URI format: "METHOD api.coinbase.com/path"
-        let uri = format!("{method} api.coinbase.com{path}");
-        
+        let exp = now + 120; // 2 minute expiration
+
         let claims = JwtClaims {
             sub: self.api_key_name.clone(),
             iss: "cdp".to_string(),
             nbf: now,
-            exp: now + 120, // 2 minute expiration
-            uri: Some(uri),
+            exp,
+            uri,
         };
-        
+
         // Create JWT header (kid = api key name, nonce = random hex)
         let nonce = format!("{:032x}", uuid::Uuid::new_v4().as_u128());
         let header = serde_json::json!({
@@ -109,18 +195,18 @@ impl CoinbaseAuth {
             "kid": self.api_key_name,
             "nonce": nonce,
         });
-        
+
         // Encode header and payload
         let header_b64 = Self::base64url_encode(&serde_json::to_vec(&header)?);
         let payload_b64 = Self::base64url_encode(&serde_json::to_vec(&claims)?);
-        
+
         let message = format!("{header_b64}.{payload_b64}");
-        
+
         // Sign the message
         let signature: Signature = self.signing_key.sign(message.as_bytes());
         let signature_b64 = Self::base64url_encode(&signature.to_bytes());
-        
-        Ok(format!("{message}.{signature_b64}"))
+
+        Ok((format!("{message}.{signature_b64}"), exp))
     }
     
     /// `Base64URL` encode (no padding, URL-safe characters)
@@ -0,0 +1,194 @@
+//! Period-breakdown backtest reporting
+//!
+//! Buckets `Backtester::run`'s closed trades by calendar day or week and reports,
+//! per bucket, the trade count, win/loss split, profit %, cumulative profit %, and
+//! running drawdown - the equivalent of freqtrade's "days breakdown" backtest
+//! output table, for spotting whether profits are concentrated in a few periods
+//! and where the worst drawdowns land.
+
+use crate::backtest::TradeRecord;
+use chrono::{DateTime, Datelike, Duration};
+use std::fmt::Write as _;
+
+/// Bucket granularity for `build_period_breakdown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Daily,
+    Weekly,
+}
+
+/// Aggregated stats for one calendar bucket, oldest-to-newest order in
+/// `PeriodBreakdown::periods`.
+#[derive(Debug, Clone)]
+pub struct PeriodStats {
+    /// `YYYY-MM-DD` - the day itself for `Bucket::Daily`, the Monday of the ISO
+    /// week for `Bucket::Weekly`.
+    pub period_start: String,
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    /// Sum of this bucket's trade P&L as a percent of `starting_capital`.
+    pub profit_percent: f64,
+    /// Running sum of `profit_percent` through this bucket.
+    pub cumulative_profit_percent: f64,
+    /// Decline from the running cumulative-profit peak as of this bucket; 0 at or
+    /// above the peak.
+    pub drawdown_percent: f64,
+}
+
+/// Period-by-period breakdown produced by `build_period_breakdown`.
+#[derive(Debug, Clone, Default)]
+pub struct PeriodBreakdown {
+    pub periods: Vec<PeriodStats>,
+}
+
+/// Bucket `trades` by calendar day/week and compute per-bucket trade counts,
+/// profit, cumulative profit, and drawdown. `starting_capital` expresses each
+/// trade's P&L as a percent, matching `BacktestReport::total_profit_percent`'s
+/// convention. Empty (no periods) if `trades` is empty or `starting_capital` isn't
+/// positive.
+pub fn build_period_breakdown(
+    trades: &[TradeRecord],
+    bucket: Bucket,
+    starting_capital: f64,
+) -> PeriodBreakdown {
+    if trades.is_empty() || starting_capital <= 0.0 {
+        return PeriodBreakdown::default();
+    }
+
+    let mut sorted: Vec<TradeRecord> = trades.to_vec();
+    sorted.sort_by_key(|t| t.closed_at);
+
+    let mut buckets: Vec<(String, Vec<f64>)> = Vec::new();
+    for t in &sorted {
+        let key = period_key(t.closed_at, bucket);
+        match buckets.last_mut() {
+            Some((k, pnls)) if *k == key => pnls.push(t.pnl),
+            _ => buckets.push((key, vec![t.pnl])),
+        }
+    }
+
+    let mut cumulative_profit_percent = 0.0_f64;
+    let mut peak_percent = 0.0_f64;
+    let periods = buckets
+        .into_iter()
+        .map(|(period_start, pnls)| {
+            let trade_count = pnls.len();
+            let wins = pnls.iter().filter(|p| **p > 0.0).count();
+            let profit_percent = pnls.iter().sum::<f64>() / starting_capital * 100.0;
+            cumulative_profit_percent += profit_percent;
+            peak_percent = peak_percent.max(cumulative_profit_percent);
+
+            PeriodStats {
+                period_start,
+                trades: trade_count,
+                wins,
+                losses: trade_count - wins,
+                profit_percent,
+                cumulative_profit_percent,
+                drawdown_percent: peak_percent - cumulative_profit_percent,
+            }
+        })
+        .collect();
+
+    PeriodBreakdown { periods }
+}
+
+/// `YYYY-MM-DD` for `timestamp` - the day itself for `Bucket::Daily`, the Monday of
+/// its ISO week for `Bucket::Weekly`.
+fn period_key(timestamp: i64, bucket: Bucket) -> String {
+    let dt = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+    match bucket {
+        Bucket::Daily => dt.format("%Y-%m-%d").to_string(),
+        Bucket::Weekly => {
+            let monday = dt.date_naive() - Duration::days(i64::from(dt.weekday().num_days_from_monday()));
+            monday.format("%Y-%m-%d").to_string()
+        }
+    }
+}
+
+/// Render a `PeriodBreakdown` as a plaintext table, in the style of freqtrade's
+/// "days breakdown" backtest summary.
+pub fn render_table(breakdown: &PeriodBreakdown) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<12} {:>7} {:>5} {:>7} {:>10} {:>10} {:>10}",
+        "Period", "Trades", "Wins", "Losses", "Profit %", "Cum %", "Drawdown %"
+    );
+    for p in &breakdown.periods {
+        let _ = writeln!(
+            out,
+            "{:<12} {:>7} {:>5} {:>7} {:>10.2} {:>10.2} {:>10.2}",
+            p.period_start,
+            p.trades,
+            p.wins,
+            p.losses,
+            p.profit_percent,
+            p.cumulative_profit_percent,
+            p.drawdown_percent,
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(pnl: f64, closed_at: i64) -> TradeRecord {
+        TradeRecord { pnl, closed_at }
+    }
+
+    #[test]
+    fn test_empty_trades_yields_empty_breakdown() {
+        let breakdown = build_period_breakdown(&[], Bucket::Daily, 10_000.0);
+        assert!(breakdown.periods.is_empty());
+    }
+
+    #[test]
+    fn test_daily_bucket_groups_same_day_trades() {
+        let day1 = 1_700_000_000; // 2023-11-14 ~22:13 UTC
+        let trades = vec![trade(100.0, day1), trade(-40.0, day1 + 3600), trade(60.0, day1 + 86_400)];
+
+        let breakdown = build_period_breakdown(&trades, Bucket::Daily, 10_000.0);
+        assert_eq!(breakdown.periods.len(), 2);
+        assert_eq!(breakdown.periods[0].trades, 2);
+        assert_eq!(breakdown.periods[0].wins, 1);
+        assert_eq!(breakdown.periods[0].losses, 1);
+        assert_eq!(breakdown.periods[1].trades, 1);
+    }
+
+    #[test]
+    fn test_cumulative_profit_and_drawdown_track_across_periods() {
+        let day1 = 1_700_000_000;
+        let trades = vec![trade(500.0, day1), trade(-200.0, day1 + 86_400)];
+
+        let breakdown = build_period_breakdown(&trades, Bucket::Daily, 10_000.0);
+        assert_eq!(breakdown.periods.len(), 2);
+        assert!((breakdown.periods[0].cumulative_profit_percent - 5.0).abs() < 1e-9);
+        assert_eq!(breakdown.periods[0].drawdown_percent, 0.0);
+        assert!((breakdown.periods[1].cumulative_profit_percent - 3.0).abs() < 1e-9);
+        assert!((breakdown.periods[1].drawdown_percent - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weekly_bucket_groups_whole_week_together() {
+        let monday = 1_700_006_400; // 2023-11-15 00:00 UTC (a Wednesday, picked for the offset below)
+        let trades = vec![trade(10.0, monday), trade(20.0, monday + 2 * 86_400)];
+
+        let breakdown = build_period_breakdown(&trades, Bucket::Weekly, 10_000.0);
+        assert_eq!(breakdown.periods.len(), 1);
+        assert_eq!(breakdown.periods[0].trades, 2);
+    }
+
+    #[test]
+    fn test_render_table_includes_header_and_each_period() {
+        let trades = vec![trade(100.0, 1_700_000_000), trade(-50.0, 1_700_086_400)];
+        let breakdown = build_period_breakdown(&trades, Bucket::Daily, 10_000.0);
+        let table = render_table(&breakdown);
+
+        assert!(table.contains("Period"));
+        assert_eq!(table.lines().count(), breakdown.periods.len() + 1);
+    }
+}
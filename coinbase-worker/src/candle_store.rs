@@ -0,0 +1,341 @@
+//! D1-backed candle cache with higher-timeframe aggregation
+//!
+//! `get_product_stats` fetches a window of hourly candles just to compute 24h
+//! high/low/trend and then throws them away, re-fetching the same history on every
+//! call. This module gives those candles somewhere to live: a D1 table keyed by
+//! `(product_id, granularity, start)`, so repeat reads can be served from storage and
+//! longer timeframes (6h, 1d) can be derived from stored 1h candles instead of
+//! re-fetching them from Coinbase.
+//!
+//! `CoinbaseClient` has no `Env`/D1 handle wired into it (its six call sites in
+//! `lib.rs` all construct it from a bare `CoinbaseAuth`), and threading one through
+//! its constructor would ripple across all of them. So rather than making
+//! `get_product_stats` reach into D1 itself, this module follows
+//! `watchdog-worker/src/d1.rs`'s pattern of plain functions that take a `&D1Database`
+//! explicitly - callers that already hold an `Env` (a scheduled handler, say) can read
+//! the cache or backfill it directly, and fall back to `CoinbaseClient`'s live fetch
+//! when the cache is cold or stale.
+
+use crate::client::{Candle, CoinbaseClient};
+use crate::error::{Result, TradingError};
+use worker::D1Database;
+
+/// Candle caps out Coinbase's candles endpoint at roughly this many rows per call.
+const MAX_CANDLES_PER_PAGE: i64 = 300;
+
+/// Candle resolution the store is keyed by. `SixHour` and `OneDay` are never fetched
+/// directly - `aggregate` derives them from stored `OneHour` candles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinute,
+    OneHour,
+    SixHour,
+    OneDay,
+}
+
+impl Resolution {
+    /// Coinbase's `granularity` query value for this resolution
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::OneMinute => "ONE_MINUTE",
+            Resolution::FiveMinute => "FIVE_MINUTE",
+            Resolution::OneHour => "ONE_HOUR",
+            Resolution::SixHour => "SIX_HOUR",
+            Resolution::OneDay => "ONE_DAY",
+        }
+    }
+
+    /// Bucket width in seconds
+    pub fn seconds(self) -> i64 {
+        match self {
+            Resolution::OneMinute => 60,
+            Resolution::FiveMinute => 300,
+            Resolution::OneHour => 3600,
+            Resolution::SixHour => 21_600,
+            Resolution::OneDay => 86_400,
+        }
+    }
+}
+
+/// One cached OHLCV row. `start` is the bucket's Unix-second boundary - the same
+/// quantity Coinbase's wire `Candle.start` carries, just parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCandle {
+    pub start: i64,
+    pub low: f64,
+    pub high: f64,
+    pub open: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl StoredCandle {
+    /// Parse a wire `Candle` into a `StoredCandle`, ready for `upsert_candles`
+    pub fn from_candle(candle: &Candle) -> Result<Self> {
+        let parse = |field: &str, label: &str| {
+            field
+                .parse::<f64>()
+                .map_err(|_| TradingError::CoinbaseApi(format!("Invalid candle {label}: {field:?}")))
+        };
+        Ok(Self {
+            start: candle
+                .start
+                .parse()
+                .map_err(|_| TradingError::CoinbaseApi(format!("Invalid candle start: {:?}", candle.start)))?,
+            low: parse(&candle.low, "low")?,
+            high: parse(&candle.high, "high")?,
+            open: parse(&candle.open, "open")?,
+            close: parse(&candle.close, "close")?,
+            volume: parse(&candle.volume, "volume")?,
+        })
+    }
+}
+
+/// D1 row shape for `candles`, matching `build_candles_upsert_statement`'s columns
+#[derive(Debug, serde::Deserialize)]
+struct CandleRow {
+    start: i64,
+    low: f64,
+    high: f64,
+    open: f64,
+    close: f64,
+    volume: f64,
+}
+
+impl From<CandleRow> for StoredCandle {
+    fn from(row: CandleRow) -> Self {
+        Self {
+            start: row.start,
+            low: row.low,
+            high: row.high,
+            open: row.open,
+            close: row.close,
+            volume: row.volume,
+        }
+    }
+}
+
+/// Build the upsert statement for one candle row, keyed by `(product_id, granularity,
+/// start)`. Separated from `upsert_candles` so callers needing manual batching can
+/// build statements without paying for a round trip per row.
+fn build_candles_upsert_statement(
+    d1: &D1Database,
+    product_id: &str,
+    granularity: Resolution,
+    candle: &StoredCandle,
+) -> Result<worker::D1PreparedStatement> {
+    let statement = d1.prepare(
+        "INSERT INTO candles (product_id, granularity, start, low, high, open, close, volume)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT (product_id, granularity, start)
+         DO UPDATE SET low = excluded.low, high = excluded.high, open = excluded.open,
+             close = excluded.close, volume = excluded.volume",
+    );
+    statement
+        .bind(&[
+            product_id.into(),
+            granularity.as_str().into(),
+            candle.start.into(),
+            candle.low.into(),
+            candle.high.into(),
+            candle.open.into(),
+            candle.close.into(),
+            candle.volume.into(),
+        ])
+        .map_err(|e| TradingError::Storage(format!("Failed to bind candle upsert: {e}")))
+}
+
+/// Upsert a page of candles for `product_id`/`granularity` into D1
+pub async fn upsert_candles(
+    d1: &D1Database,
+    product_id: &str,
+    granularity: Resolution,
+    candles: &[StoredCandle],
+) -> Result<()> {
+    for candle in candles {
+        let statement = build_candles_upsert_statement(d1, product_id, granularity, candle)?;
+        statement
+            .run()
+            .await
+            .map_err(|e| TradingError::Storage(format!("Failed to upsert candle: {e}")))?;
+    }
+    Ok(())
+}
+
+/// Read cached candles for `product_id`/`granularity` in `[start, end)`, ascending by
+/// time. An empty result means the range hasn't been fetched yet, not necessarily that
+/// no trading happened - callers should treat it as a cache miss.
+pub async fn get_cached_candles(
+    d1: &D1Database,
+    product_id: &str,
+    granularity: Resolution,
+    start: i64,
+    end: i64,
+) -> Result<Vec<StoredCandle>> {
+    let statement = d1
+        .prepare(
+            "SELECT start, low, high, open, close, volume FROM candles
+             WHERE product_id = ? AND granularity = ? AND start >= ? AND start < ?
+             ORDER BY start ASC",
+        )
+        .bind(&[product_id.into(), granularity.as_str().into(), start.into(), end.into()])
+        .map_err(|e| TradingError::Storage(format!("Failed to bind candle read: {e}")))?;
+
+    let result = statement
+        .all()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read cached candles: {e}")))?;
+    let rows: Vec<CandleRow> = result
+        .results()
+        .map_err(|e| TradingError::Storage(format!("Failed to decode cached candles: {e}")))?;
+    Ok(rows.into_iter().map(StoredCandle::from).collect())
+}
+
+/// Derive `target`-resolution candles from ascending, gap-free `OneHour` candles.
+/// Buckets are aligned to `target.seconds()`; a bucket with no hourly candles is
+/// simply absent from the result rather than synthesized.
+pub fn aggregate(hourly: &[StoredCandle], target: Resolution) -> Vec<StoredCandle> {
+    if hourly.is_empty() || target == Resolution::OneHour {
+        return hourly.to_vec();
+    }
+
+    let bucket_seconds = target.seconds();
+    let mut buckets: Vec<StoredCandle> = Vec::new();
+    for candle in hourly {
+        let bucket_start = (candle.start / bucket_seconds) * bucket_seconds;
+        match buckets.last_mut().filter(|b| b.start == bucket_start) {
+            Some(bucket) => {
+                bucket.high = bucket.high.max(candle.high);
+                bucket.low = bucket.low.min(candle.low);
+                bucket.close = candle.close;
+                bucket.volume += candle.volume;
+            }
+            None => buckets.push(StoredCandle {
+                start: bucket_start,
+                low: candle.low,
+                high: candle.high,
+                open: candle.open,
+                close: candle.close,
+                volume: candle.volume,
+            }),
+        }
+    }
+    buckets
+}
+
+/// Page through `[from, to)` fetching hourly candles, filling gaps left by prior
+/// partial fetches. A page that already holds a candle for every hour is skipped; any
+/// other page is re-fetched whole and every fetched candle is upserted. Returns the
+/// number of candles written. Pages at `MAX_CANDLES_PER_PAGE` hours, matching
+/// Coinbase's per-call cap.
+pub async fn backfill_candles(
+    client: &CoinbaseClient,
+    d1: &D1Database,
+    product_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<u32> {
+    let page_seconds = MAX_CANDLES_PER_PAGE * Resolution::OneHour.seconds();
+    let mut written = 0u32;
+    let mut cursor = from;
+
+    while cursor < to {
+        let page_end = (cursor + page_seconds).min(to);
+        let cached = get_cached_candles(d1, product_id, Resolution::OneHour, cursor, page_end).await?;
+        if cached.len() < ((page_end - cursor) / Resolution::OneHour.seconds()).max(1) as usize {
+            let fetched = client
+                .get_candles_range(product_id, Resolution::OneHour.as_str(), cursor, page_end)
+                .await?;
+            let parsed: Result<Vec<StoredCandle>> = fetched.iter().map(StoredCandle::from_candle).collect();
+            let parsed = parsed?;
+            written += parsed.len() as u32;
+            upsert_candles(d1, product_id, Resolution::OneHour, &parsed).await?;
+        }
+        cursor = page_end;
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(start: i64, low: f64, high: f64, open: f64, close: f64, volume: f64) -> StoredCandle {
+        StoredCandle { start, low, high, open, close, volume }
+    }
+
+    #[test]
+    fn test_resolution_seconds() {
+        assert_eq!(Resolution::OneHour.seconds(), 3600);
+        assert_eq!(Resolution::SixHour.seconds(), 6 * 3600);
+        assert_eq!(Resolution::OneDay.seconds(), 24 * 3600);
+    }
+
+    #[test]
+    fn test_aggregate_six_hour_from_hourly() {
+        let hourly: Vec<StoredCandle> = (0..6)
+            .map(|i| candle(i * 3600, 100.0 - i as f64, 110.0 + i as f64, 105.0, 106.0, 10.0))
+            .collect();
+
+        let six_hour = aggregate(&hourly, Resolution::SixHour);
+        assert_eq!(six_hour.len(), 1);
+        assert_eq!(six_hour[0].start, 0);
+        assert_eq!(six_hour[0].open, 105.0);
+        assert_eq!(six_hour[0].close, 106.0);
+        assert_eq!(six_hour[0].high, 115.0);
+        assert_eq!(six_hour[0].low, 95.0);
+        assert_eq!(six_hour[0].volume, 60.0);
+    }
+
+    #[test]
+    fn test_aggregate_splits_across_bucket_boundaries() {
+        let hourly = vec![
+            candle(0, 10.0, 20.0, 15.0, 16.0, 1.0),
+            candle(21_600, 11.0, 21.0, 16.0, 17.0, 1.0),
+        ];
+
+        let days = aggregate(&hourly, Resolution::OneDay);
+        assert_eq!(days.len(), 1);
+        let six_hours = aggregate(&hourly, Resolution::SixHour);
+        assert_eq!(six_hours.len(), 2);
+        assert_eq!(six_hours[0].start, 0);
+        assert_eq!(six_hours[1].start, 21_600);
+    }
+
+    #[test]
+    fn test_aggregate_one_hour_is_identity() {
+        let hourly = vec![candle(0, 1.0, 2.0, 1.5, 1.8, 5.0)];
+        assert_eq!(aggregate(&hourly, Resolution::OneHour), hourly);
+    }
+
+    #[test]
+    fn test_stored_candle_from_candle() {
+        let candle = Candle {
+            start: "3600".to_string(),
+            low: "99.5".to_string(),
+            high: "101.2".to_string(),
+            open: "100.0".to_string(),
+            close: "100.8".to_string(),
+            volume: "42.0".to_string(),
+        };
+        let stored = StoredCandle::from_candle(&candle).unwrap();
+        assert_eq!(stored.start, 3600);
+        assert_eq!(stored.low, 99.5);
+        assert_eq!(stored.volume, 42.0);
+    }
+
+    #[test]
+    fn test_stored_candle_from_candle_rejects_bad_start() {
+        let candle = Candle {
+            start: "not-a-timestamp".to_string(),
+            low: "1.0".to_string(),
+            high: "1.0".to_string(),
+            open: "1.0".to_string(),
+            close: "1.0".to_string(),
+            volume: "1.0".to_string(),
+        };
+        assert!(StoredCandle::from_candle(&candle).is_err());
+    }
+}
@@ -6,8 +6,13 @@
 //! - Less room for diversification
 //! - Need for capital preservation to grow
 
-/// Capital tier classification
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::amount::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Capital tier classification. Variants are declared in ascending portfolio-size
+/// order so the derived `PartialOrd`/`Ord` (`Micro < Tiny < ... < Large`) can be used
+/// directly by `TierTransition` to tell an upgrade from a downgrade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum CapitalTier {
     /// $0 - $100: Insufficient capital, trading disabled
     Micro,
@@ -25,7 +30,8 @@ pub enum CapitalTier {
 
 impl CapitalTier {
     /// Determine tier from portfolio value
-    pub fn from_portfolio(value: f64) -> Self {
+    pub fn from_portfolio(value: Amount) -> Self {
+        let value = value.to_dollars();
         if value < 100.0 {
             CapitalTier::Micro
         } else if value < 500.0 {
@@ -123,6 +129,20 @@ impl CapitalTier {
             CapitalTier::Large => "LARGE",
         }
     }
+
+    /// Dollar value of this tier's lower boundary (e.g. `Tiny` starts at $100) - the
+    /// threshold `TierTransition` applies its hysteresis margin around when deciding
+    /// whether a crossing into or out of this tier is real.
+    fn lower_boundary(&self) -> f64 {
+        match self {
+            CapitalTier::Micro => 0.0,
+            CapitalTier::Tiny => 100.0,
+            CapitalTier::Small => 500.0,
+            CapitalTier::Medium => 2000.0,
+            CapitalTier::Standard => 5000.0,
+            CapitalTier::Large => 25000.0,
+        }
+    }
 }
 
 /// Tier-adjusted parameters for trading
@@ -135,12 +155,16 @@ pub struct TierParameters {
     pub can_trade: bool,
     pub entry_threshold_multiplier: f64,
     pub recommendation: String,
+    /// How far through a `TierTransition` ramp these parameters are: `0.0` when
+    /// fully settled on `tier` (the only value `for_portfolio` ever produces), up to
+    /// just under `1.0` mid-ramp. See `TierTransition::evaluate`.
+    pub transition_progress: f64,
 }
 
 impl TierParameters {
-    /// Calculate tier parameters for a given portfolio value
-    pub fn for_portfolio(value: f64) -> Self {
-        let tier = CapitalTier::from_portfolio(value);
+    /// Tier parameters for `tier`, with no transition in progress - the building
+    /// block both `for_portfolio` and `TierTransition::evaluate` assemble from.
+    fn settled(tier: CapitalTier) -> Self {
         Self {
             tier,
             max_positions: tier.max_positions(),
@@ -149,12 +173,178 @@ impl TierParameters {
             can_trade: tier.can_trade(),
             entry_threshold_multiplier: tier.entry_threshold_multiplier(),
             recommendation: tier.recommendation().to_string(),
+            transition_progress: 0.0,
+        }
+    }
+
+    /// Calculate tier parameters for a given portfolio value directly, with no
+    /// hysteresis or ramping applied - an instant snap to whatever tier `value`
+    /// raw-classifies as. See `TierTransition::evaluate` for the smoothed version.
+    pub fn for_portfolio(value: Amount) -> Self {
+        Self::settled(CapitalTier::from_portfolio(value))
+    }
+
+    /// Build a `TierConfigSnapshot` of these parameters plus `fee_tier`, computing
+    /// `round_trip_percent`/`min_profitable_tp` for `reference_target_net_profit_percent`
+    /// - see `crate::get_tier_config`, which serves this as `/api/tier-config`.
+    pub fn config_snapshot(&self, fee_tier: FeeTier, reference_target_net_profit_percent: f64) -> TierConfigSnapshot {
+        TierConfigSnapshot {
+            tier: self.tier.name().to_string(),
+            max_positions: self.max_positions,
+            max_position_percent: self.max_position_percent,
+            risk_per_trade_percent: self.risk_per_trade_percent,
+            can_trade: self.can_trade,
+            entry_threshold_multiplier: self.entry_threshold_multiplier,
+            fee_tier,
+            round_trip_percent: fee_tier.round_trip_percent(),
+            reference_target_net_profit_percent,
+            min_profitable_tp_percent: fee_tier.min_profitable_tp(reference_target_net_profit_percent),
         }
     }
 }
 
+/// Serializable snapshot of the full resolved tier/fee configuration a running bot
+/// is using. Its shape is locked by the snapshot test below so downstream
+/// dashboards/tooling can rely on a stable schema even as `TierParameters`/`FeeTier`
+/// evolve internally - see `TierParameters::config_snapshot`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TierConfigSnapshot {
+    pub tier: String,
+    pub max_positions: usize,
+    pub max_position_percent: f64,
+    pub risk_per_trade_percent: f64,
+    pub can_trade: bool,
+    pub entry_threshold_multiplier: f64,
+    pub fee_tier: FeeTier,
+    pub round_trip_percent: f64,
+    pub reference_target_net_profit_percent: f64,
+    pub min_profitable_tp_percent: f64,
+}
+
+/// Smooths `CapitalTier` transitions across evaluation cycles, so a portfolio
+/// hovering near a tier boundary doesn't repeatedly snap `risk_per_trade_percent`/
+/// `max_position_percent` up and down and a brief dip doesn't instantly tighten
+/// sizing. Persisted across cycles (see `TradingStateData::tier_transition`) so the
+/// ramp and hysteresis state survive a worker restart between evaluations.
+///
+/// A transition only *starts* once the raw tier clears its boundary by
+/// `hysteresis_percent` (of the boundary's dollar value) in the direction of the
+/// crossing, and then *ramps* `max_position_percent`/`risk_per_trade_percent`
+/// linearly toward the new tier's values over `transition_cycles` evaluations
+/// instead of jumping immediately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierTransition {
+    /// The tier fully ramped into as of the last evaluation - what `evaluate`
+    /// reports once no transition is in progress.
+    settled: CapitalTier,
+    /// The tier being ramped toward, once a transition has cleared the hysteresis
+    /// margin. `None` means no transition in progress.
+    target: Option<CapitalTier>,
+    /// Evaluation cycles elapsed since the transition to `target` began.
+    cycles_elapsed: u32,
+}
+
+impl TierTransition {
+    /// Start fully settled at whatever tier `value` raw-classifies to, with no
+    /// transition in progress - the seed used the first time a portfolio value is
+    /// observed (no prior state to continue from).
+    pub fn new(value: Amount) -> Self {
+        Self { settled: CapitalTier::from_portfolio(value), target: None, cycles_elapsed: 0 }
+    }
+
+    /// Advance one evaluation cycle: re-classify `value`, apply hysteresis to decide
+    /// whether to (re)start a transition, advance an in-progress ramp, and return
+    /// the resulting (possibly interpolated) tier parameters.
+    pub fn evaluate(&mut self, value: Amount, hysteresis_percent: f64, transition_cycles: u32) -> TierParameters {
+        let raw_tier = CapitalTier::from_portfolio(value);
+
+        match self.target {
+            None => {
+                if raw_tier != self.settled && Self::clears_hysteresis(value, self.settled, raw_tier, hysteresis_percent) {
+                    self.target = Some(raw_tier);
+                    self.cycles_elapsed = 0;
+                }
+            }
+            Some(target) => {
+                if raw_tier == self.settled {
+                    // Reverted back before the ramp finished - cancel it outright
+                    // rather than ramp partway and un-ramp, which would just be the
+                    // flapping this hysteresis/ramp exists to prevent.
+                    self.target = None;
+                    self.cycles_elapsed = 0;
+                } else if raw_tier != target && Self::clears_hysteresis(value, target, raw_tier, hysteresis_percent) {
+                    // Kept moving past the tier being ramped toward - retarget
+                    // instead of finishing a ramp into a now-stale tier.
+                    self.target = Some(raw_tier);
+                    self.cycles_elapsed = 0;
+                } else {
+                    self.cycles_elapsed += 1;
+                    if self.cycles_elapsed >= transition_cycles.max(1) {
+                        self.settled = target;
+                        self.target = None;
+                        self.cycles_elapsed = 0;
+                    }
+                }
+            }
+        }
+
+        self.current_parameters(transition_cycles.max(1))
+    }
+
+    /// Whether `value` has cleared `hysteresis_percent` past the boundary between
+    /// `from` and `to`, in the direction of `to` - an upgrade must clear the higher
+    /// tier's lower boundary by the margin above it, a downgrade must fall the
+    /// margin below the lower tier's own boundary.
+    fn clears_hysteresis(value: Amount, from: CapitalTier, to: CapitalTier, hysteresis_percent: f64) -> bool {
+        let dollars = value.to_dollars();
+        if to > from {
+            let boundary = to.lower_boundary();
+            dollars >= boundary + boundary * (hysteresis_percent / 100.0)
+        } else {
+            let boundary = from.lower_boundary();
+            dollars <= boundary - boundary * (hysteresis_percent / 100.0)
+        }
+    }
+
+    fn current_parameters(&self, transition_cycles: u32) -> TierParameters {
+        let settled_params = TierParameters::settled(self.settled);
+        let Some(target) = self.target else {
+            return settled_params;
+        };
+
+        let target_params = TierParameters::settled(target);
+        let progress = (self.cycles_elapsed as f64 / transition_cycles as f64).min(1.0);
+
+        TierParameters {
+            // Report the settled tier's identity/gating fields until the ramp
+            // actually completes - only the two ramped magnitudes move early.
+            tier: self.settled,
+            max_positions: settled_params.max_positions,
+            max_position_percent: lerp(settled_params.max_position_percent, target_params.max_position_percent, progress),
+            risk_per_trade_percent: lerp(settled_params.risk_per_trade_percent, target_params.risk_per_trade_percent, progress),
+            can_trade: settled_params.can_trade && target_params.can_trade,
+            entry_threshold_multiplier: lerp(
+                settled_params.entry_threshold_multiplier,
+                target_params.entry_threshold_multiplier,
+                progress,
+            ),
+            recommendation: format!(
+                "Transitioning {} -> {} ({:.0}% complete)",
+                settled_params.tier.name(),
+                target_params.tier.name(),
+                progress * 100.0
+            ),
+            transition_progress: progress,
+        }
+    }
+}
+
+fn lerp(from: f64, to: f64, progress: f64) -> f64 {
+    from + (to - from) * progress
+}
+
 /// Fee tier based on 30-day trading volume
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct FeeTier {
     pub taker_fee_percent: f64,
     pub maker_fee_percent: f64,
@@ -186,13 +376,15 @@ impl FeeTier {
     }
 
     /// Calculate minimum position size for trade to be worthwhile
-    /// Ensures expected profit exceeds a minimum dollar threshold
-    pub fn min_position_for_profit(&self, expected_move_percent: f64, min_profit_usd: f64) -> f64 {
+    /// Ensures expected profit exceeds a minimum dollar threshold. Returns `None`
+    /// (rather than an infinite/sentinel position size) when the expected move
+    /// doesn't even cover round-trip fees, so no position is worthwhile.
+    pub fn min_position_for_profit(&self, expected_move_percent: f64, min_profit: Amount) -> Option<Amount> {
         let net_profit_percent = expected_move_percent - self.round_trip_percent();
         if net_profit_percent <= 0.0 {
-            f64::MAX // Position would need to be infinite (not profitable)
+            None
         } else {
-            min_profit_usd / (net_profit_percent / 100.0)
+            min_profit.checked_mul(100.0 / net_profit_percent)
         }
     }
 }
@@ -203,30 +395,30 @@ mod tests {
 
     #[test]
     fn test_tier_classification() {
-        assert_eq!(CapitalTier::from_portfolio(50.0), CapitalTier::Micro);
-        assert_eq!(CapitalTier::from_portfolio(100.0), CapitalTier::Tiny);
-        assert_eq!(CapitalTier::from_portfolio(500.0), CapitalTier::Small);
-        assert_eq!(CapitalTier::from_portfolio(2000.0), CapitalTier::Medium);
-        assert_eq!(CapitalTier::from_portfolio(5000.0), CapitalTier::Standard);
-        assert_eq!(CapitalTier::from_portfolio(25000.0), CapitalTier::Large);
-        assert_eq!(CapitalTier::from_portfolio(100000.0), CapitalTier::Large);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(50.0)), CapitalTier::Micro);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(100.0)), CapitalTier::Tiny);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(500.0)), CapitalTier::Small);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(2000.0)), CapitalTier::Medium);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(5000.0)), CapitalTier::Standard);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(25000.0)), CapitalTier::Large);
+        assert_eq!(CapitalTier::from_portfolio(Amount::from_dollars(100000.0)), CapitalTier::Large);
     }
 
     #[test]
     fn test_tier_parameters() {
         // Micro tier cannot trade
-        let micro = TierParameters::for_portfolio(50.0);
+        let micro = TierParameters::for_portfolio(Amount::from_dollars(50.0));
         assert!(!micro.can_trade);
         assert_eq!(micro.max_positions, 0);
 
         // Tiny tier is ultra conservative
-        let tiny = TierParameters::for_portfolio(300.0);
+        let tiny = TierParameters::for_portfolio(Amount::from_dollars(300.0));
         assert!(tiny.can_trade);
         assert_eq!(tiny.max_positions, 1);
         assert_eq!(tiny.risk_per_trade_percent, 0.5);
 
         // Standard tier has normal parameters
-        let standard = TierParameters::for_portfolio(10000.0);
+        let standard = TierParameters::for_portfolio(Amount::from_dollars(10000.0));
         assert!(standard.can_trade);
         assert_eq!(standard.max_positions, 4);
         assert_eq!(standard.risk_per_trade_percent, 2.0);
@@ -258,12 +450,12 @@ mod tests {
 
         // If expected move is 3%, fees are 1.2%, net profit is 1.8%
         // To make $1 profit: $1 / 0.018 = $55.56 minimum position
-        let min_pos = fees.min_position_for_profit(3.0, 1.0);
-        assert!((min_pos - 55.56).abs() < 1.0);
+        let min_pos = fees.min_position_for_profit(3.0, Amount::from_dollars(1.0)).unwrap();
+        assert!((min_pos.to_dollars() - 55.56).abs() < 1.0);
 
         // If expected move equals fees, no profit possible
-        let impossible = fees.min_position_for_profit(1.2, 1.0);
-        assert_eq!(impossible, f64::MAX);
+        let impossible = fees.min_position_for_profit(1.2, Amount::from_dollars(1.0));
+        assert_eq!(impossible, None);
     }
 
     #[test]
@@ -286,4 +478,82 @@ mod tests {
             prev_positions = positions;
         }
     }
+
+    #[test]
+    fn test_transition_ignores_crossing_within_hysteresis_band() {
+        let mut transition = TierTransition::new(Amount::from_dollars(4900.0)); // Medium
+        // $5,100 clears the Medium->Standard boundary ($5,000) on paper, but not by
+        // the 5% margin ($250) - should be treated as noise, not a real crossing.
+        let params = transition.evaluate(Amount::from_dollars(5100.0), 5.0, 5);
+        assert_eq!(params.tier, CapitalTier::Medium);
+        assert_eq!(params.transition_progress, 0.0);
+    }
+
+    #[test]
+    fn test_transition_ramps_linearly_over_configured_cycles() {
+        let mut transition = TierTransition::new(Amount::from_dollars(4900.0)); // Medium
+        // $6,000 clears the 5% margin above the $5,000 boundary - starts a transition
+        // that ramps over 3 cycles.
+        let first = transition.evaluate(Amount::from_dollars(6000.0), 5.0, 3);
+        assert_eq!(first.tier, CapitalTier::Medium); // still reporting the settled tier
+        assert!((first.transition_progress - 0.0).abs() < 1e-9);
+
+        let second = transition.evaluate(Amount::from_dollars(6000.0), 5.0, 3);
+        assert!((second.transition_progress - 1.0 / 3.0).abs() < 1e-9);
+        // 2/3 of the way between Medium's 1.5% and Standard's 2.0% risk.
+        let third = transition.evaluate(Amount::from_dollars(6000.0), 5.0, 3);
+        assert!((third.risk_per_trade_percent - (1.5 + 0.5 * 2.0 / 3.0)).abs() < 1e-9);
+
+        let fourth = transition.evaluate(Amount::from_dollars(6000.0), 5.0, 3);
+        assert_eq!(fourth.tier, CapitalTier::Standard);
+        assert_eq!(fourth.transition_progress, 0.0); // settled, ramp complete
+        assert_eq!(fourth.risk_per_trade_percent, 2.0);
+    }
+
+    #[test]
+    fn test_transition_reverting_before_completion_cancels_it() {
+        let mut transition = TierTransition::new(Amount::from_dollars(4900.0)); // Medium
+        transition.evaluate(Amount::from_dollars(6000.0), 5.0, 5); // starts ramping toward Standard
+        let reverted = transition.evaluate(Amount::from_dollars(4900.0), 5.0, 5); // back to Medium
+        assert_eq!(reverted.tier, CapitalTier::Medium);
+        assert_eq!(reverted.transition_progress, 0.0);
+    }
+
+    #[test]
+    fn test_tier_config_snapshot_schema_is_stable() {
+        let params = TierParameters::for_portfolio(Amount::from_dollars(10_000.0)); // Standard
+        let fee_tier = FeeTier::from_volume(500.0); // lowest volume bracket
+        let snapshot = params.config_snapshot(fee_tier, 1.0);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "tier": "STANDARD",
+                "max_positions": 4,
+                "max_position_percent": 25.0,
+                "risk_per_trade_percent": 2.0,
+                "can_trade": true,
+                "entry_threshold_multiplier": 1.0,
+                "fee_tier": { "taker_fee_percent": 0.60, "maker_fee_percent": 0.40 },
+                "round_trip_percent": 1.2,
+                "reference_target_net_profit_percent": 1.0,
+                "min_profitable_tp_percent": 2.2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_tier_config_snapshot_serializes_as_deterministic_json_string() {
+        let params = TierParameters::for_portfolio(Amount::from_dollars(50.0)); // Micro
+        let fee_tier = FeeTier::from_volume(100_000.0); // highest volume bracket
+        let snapshot = params.config_snapshot(fee_tier, 0.5);
+
+        let expected = "{\"tier\":\"MICRO\",\"max_positions\":0,\"max_position_percent\":0.0,\
+            \"risk_per_trade_percent\":0.0,\"can_trade\":false,\"entry_threshold_multiplier\":1.5,\
+            \"fee_tier\":{\"taker_fee_percent\":0.2,\"maker_fee_percent\":0.1},\
+            \"round_trip_percent\":0.4,\"reference_target_net_profit_percent\":0.5,\
+            \"min_profitable_tp_percent\":0.9}";
+        assert_eq!(serde_json::to_string(&snapshot).unwrap(), expected);
+    }
 }
@@ -7,10 +7,13 @@
 //!
 //! Rate limits: 30 requests/second (generous!)
 
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::auth::CoinbaseAuth;
 use crate::error::{Result, TradingError};
+use crate::fx::ExchangeRates;
+use crate::money::{decimal_from_f64, parse_money, round_to_increment};
 use crate::types::OrderSide;
 
 const BASE_URL: &str = "https://api.coinbase.com";
@@ -18,6 +21,63 @@ const BASE_URL: &str = "https://api.coinbase.com";
 /// Coinbase API client
 pub struct CoinbaseClient {
     auth: CoinbaseAuth,
+    /// Reused across requests for connection pooling, rather than a fresh
+    /// `reqwest::Client` per call
+    http: reqwest::Client,
+    /// Token bucket throttling `get`/`post` to `RATE_LIMIT_PER_SECOND`. `RefCell`
+    /// rather than `&mut self` because the engine holds `CoinbaseClient` behind a
+    /// shared `Box<dyn Exchange>` (see `exchange.rs`) - fine on this single-threaded
+    /// wasm32 target.
+    limiter: std::cell::RefCell<TokenBucket>,
+}
+
+/// Requests/second the token bucket allows before it starts making callers wait,
+/// matching Coinbase's documented rate limit
+const RATE_LIMIT_PER_SECOND: f64 = 30.0;
+
+/// How many times a 429 is retried (with exponential backoff off `Retry-After`) before
+/// `get`/`post` give up and surface `TradingError::RateLimit` to the caller
+const MAX_RETRY_ATTEMPTS: u32 = 3;
+
+/// Simple token bucket: refills continuously at `refill_per_second`, capped at
+/// `capacity`, and each request consumes one token (waiting for a refill if empty).
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: f64,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, refill_per_second: f64) -> Self {
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_second,
+            last_refill: chrono::Utc::now().timestamp_millis() as f64 / 1000.0,
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = chrono::Utc::now().timestamp_millis() as f64 / 1000.0;
+        let elapsed = (now - self.last_refill).max(0.0);
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Seconds to wait before a token is available for this call. Always consumes a
+    /// token (the wait, if any, already accounts for it not being free yet).
+    fn acquire_wait_seconds(&mut self) -> f64 {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            0.0
+        } else {
+            let wait = (1.0 - self.tokens) / self.refill_per_second;
+            self.tokens = 0.0;
+            wait
+        }
+    }
 }
 
 /// Account response from Coinbase
@@ -43,6 +103,14 @@ pub struct Balance {
     pub currency: String,
 }
 
+impl Balance {
+    /// `value` parsed losslessly as `Decimal`, for callers that need exact money math
+    /// rather than `f64`'s approximations
+    pub fn decimal(&self) -> Result<Decimal> {
+        parse_money(&self.value)
+    }
+}
+
 /// Product (trading pair) info from Coinbase API
 #[allow(clippy::struct_field_names)] // Matches Coinbase API schema
 #[derive(Debug, Deserialize)]
@@ -57,6 +125,31 @@ pub struct Product {
     pub base_increment: String,
 }
 
+impl Product {
+    /// `price` parsed losslessly as `Decimal`
+    pub fn price_decimal(&self) -> Result<Decimal> {
+        parse_money(&self.price)
+    }
+
+    /// `base_increment` parsed losslessly as `Decimal` - the smallest step a base-size
+    /// order for this product can be sized in
+    pub fn base_increment_decimal(&self) -> Result<Decimal> {
+        parse_money(&self.base_increment)
+    }
+
+    /// `quote_increment` parsed losslessly as `Decimal` - the smallest step a
+    /// quote-size (USD) order for this product can be sized in
+    pub fn quote_increment_decimal(&self) -> Result<Decimal> {
+        parse_money(&self.quote_increment)
+    }
+
+    /// `base_min_size` parsed losslessly as `Decimal` - the smallest base-size order
+    /// Coinbase will accept for this product
+    pub fn base_min_size_decimal(&self) -> Result<Decimal> {
+        parse_money(&self.base_min_size)
+    }
+}
+
 /// Product stats with 24h high/low and trend data
 #[derive(Debug, Clone)]
 pub struct ProductStats {
@@ -71,6 +164,12 @@ pub struct ProductStats {
     pub avg_6h: f64,
 }
 
+/// Wrapper for the public product-list response
+#[derive(Debug, Deserialize)]
+struct ProductsResponse {
+    products: Vec<Product>,
+}
+
 /// Candle data
 #[derive(Debug, Deserialize)]
 pub struct CandlesResponse {
@@ -78,7 +177,6 @@ pub struct CandlesResponse {
 }
 
 /// OHLCV candle data from Coinbase API
-#[allow(dead_code)] // Fields available for future use
 #[derive(Debug, Deserialize)]
 pub struct Candle {
     pub start: String,
@@ -89,6 +187,72 @@ pub struct Candle {
     pub volume: String,
 }
 
+/// Wrapper for the public `product_book` response
+#[derive(Debug, Deserialize)]
+struct ProductBookResponse {
+    pricebook: RawPricebook,
+}
+
+/// Wrapper for the public `v2/exchange-rates` response
+#[derive(Debug, Deserialize)]
+struct ExchangeRatesResponse {
+    data: ExchangeRatesData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeRatesData {
+    currency: String,
+    rates: std::collections::HashMap<String, String>,
+}
+
+/// Raw bid/ask levels as Coinbase returns them - strings, best-first, unbounded depth
+#[derive(Debug, Deserialize)]
+struct RawPricebook {
+    bids: Vec<RawBookLevel>,
+    asks: Vec<RawBookLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawBookLevel {
+    price: String,
+    size: String,
+}
+
+/// One resting order-book level, parsed to `f64` for the slippage-walk math in
+/// `liquidation::liquidation_price` - money-precision isn't needed here since the
+/// result only feeds a reported estimate, not an order.
+#[derive(Debug, Clone, Copy)]
+pub struct BookLevel {
+    pub price: f64,
+    pub size: f64,
+}
+
+/// Bid/ask levels for one symbol, best-first, as returned by `get_product_book`.
+#[derive(Debug, Clone, Default)]
+pub struct OrderbookLevels {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl TryFrom<RawPricebook> for OrderbookLevels {
+    type Error = TradingError;
+
+    fn try_from(raw: RawPricebook) -> Result<Self> {
+        let parse_levels = |levels: Vec<RawBookLevel>| -> Result<Vec<BookLevel>> {
+            levels
+                .into_iter()
+                .map(|l| {
+                    Ok(BookLevel {
+                        price: l.price.parse().map_err(|_| TradingError::CoinbaseApi(format!("Invalid book price {}", l.price)))?,
+                        size: l.size.parse().map_err(|_| TradingError::CoinbaseApi(format!("Invalid book size {}", l.size)))?,
+                    })
+                })
+                .collect()
+        };
+        Ok(OrderbookLevels { bids: parse_levels(raw.bids)?, asks: parse_levels(raw.asks)? })
+    }
+}
+
 /// Order request
 #[derive(Debug, Serialize)]
 pub struct OrderRequest {
@@ -121,6 +285,75 @@ pub struct OrderResponse {
     pub error_response: Option<ErrorResponse>,
 }
 
+/// A single historical/open order's fill status, as reported by Coinbase
+#[derive(Debug, Deserialize)]
+pub struct OrderStatus {
+    pub order_id: String,
+    pub status: String,
+    pub filled_size: String,
+    pub average_filled_price: String,
+    /// Fraction of the order filled so far, Coinbase's own string (e.g. `"0.5"` for 50%)
+    #[serde(default)]
+    pub completion_percentage: String,
+}
+
+impl OrderStatus {
+    /// Coinbase reports a fully executed order with `status == "FILLED"`
+    pub fn is_filled(&self) -> bool {
+        self.status == "FILLED"
+    }
+}
+
+/// Wrapper for the single-order GET response
+#[derive(Debug, Deserialize)]
+struct OrderStatusResponse {
+    order: OrderStatus,
+}
+
+/// Wrapper for the historical-orders-list GET response
+#[derive(Debug, Deserialize)]
+struct OrdersResponse {
+    orders: Vec<OrderStatus>,
+}
+
+/// `order_status` filter for `CoinbaseClient::list_orders`, passed through as
+/// Coinbase's own query value
+#[derive(Debug, Clone, Copy)]
+pub enum OrderListFilter {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+impl OrderListFilter {
+    fn as_query_value(self) -> &'static str {
+        match self {
+            OrderListFilter::Open => "OPEN",
+            OrderListFilter::Filled => "FILLED",
+            OrderListFilter::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+/// Request body for batch order cancellation
+#[derive(Debug, Serialize)]
+struct CancelOrdersRequest {
+    order_ids: Vec<String>,
+}
+
+/// Per-order result of a batch cancellation
+#[derive(Debug, Deserialize)]
+pub struct CancelResult {
+    pub success: bool,
+    pub order_id: String,
+}
+
+/// Response from batch order cancellation
+#[derive(Debug, Deserialize)]
+struct CancelOrdersResponse {
+    results: Vec<CancelResult>,
+}
+
 /// Error response
 #[derive(Debug, Deserialize)]
 pub struct ErrorResponse {
@@ -132,7 +365,11 @@ pub struct ErrorResponse {
 impl CoinbaseClient {
     /// Create new client with authentication
     pub fn new(auth: CoinbaseAuth) -> Self {
-        Self { auth }
+        Self {
+            auth,
+            http: reqwest::Client::new(),
+            limiter: std::cell::RefCell::new(TokenBucket::new(RATE_LIMIT_PER_SECOND, RATE_LIMIT_PER_SECOND)),
+        }
     }
 
     /// Get all accounts (balances)
@@ -141,21 +378,69 @@ impl CoinbaseClient {
         self.get(path).await
     }
 
-    /// Get USD + USDC balance (both count as available cash)
+    /// Get USD + USDC balance (both count as available cash). Accumulates via
+    /// `Decimal` so summing many accounts' balances loses no precision, converting
+    /// to `f64` only at the end for callers that do relative strategy math with it.
     pub async fn get_usd_balance(&self) -> Result<f64> {
+        use rust_decimal::prelude::ToPrimitive;
+
         let response = self.get_accounts().await?;
 
-        let mut total = 0.0;
+        let mut total = Decimal::ZERO;
         for account in response.accounts {
             // Count both USD and USDC as available cash (USDC is 1:1 with USD)
             if account.currency == "USD" || account.currency == "USDC" {
-                if let Ok(val) = account.available_balance.value.parse::<f64>() {
+                if let Ok(val) = account.available_balance.decimal() {
                     total += val;
                 }
             }
         }
 
-        Ok(total)
+        Ok(total.to_f64().unwrap_or(0.0))
+    }
+
+    /// Every account with a non-zero available balance, as `(currency, amount)` pairs -
+    /// unlike `get_usd_balance`, this doesn't filter to `USD`/`USDC` so a multi-currency
+    /// portfolio report (see `crate::fx`) can account for every holding instead of
+    /// silently dropping EUR/GBP/crypto balances.
+    pub async fn get_all_balances(&self) -> Result<Vec<(String, f64)>> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let response = self.get_accounts().await?;
+
+        Ok(response
+            .accounts
+            .into_iter()
+            .filter_map(|account| {
+                let balance = account.available_balance.decimal().ok()?.to_f64()?;
+                (balance != 0.0).then_some((account.currency, balance))
+            })
+            .collect())
+    }
+
+    /// Spot conversion rates for every currency Coinbase quotes against `base`, via the
+    /// public `exchange-rates` endpoint (no auth required). Used to normalize
+    /// multi-currency balances and position values into `Config::base_currency` (see
+    /// `crate::fx::ExchangeRates`).
+    pub async fn get_exchange_rates(&self, base: &str) -> Result<ExchangeRates> {
+        let url = format!("https://api.coinbase.com/v2/exchange-rates?currency={base}");
+
+        let response = self
+            .http
+            .get(&url)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?;
+
+        let parsed: ExchangeRatesResponse = Self::handle_response(response).await?;
+        let rates = parsed
+            .data
+            .rates
+            .into_iter()
+            .filter_map(|(currency, rate)| rate.parse::<f64>().ok().map(|r| (currency, r)))
+            .collect();
+
+        Ok(ExchangeRates { base: parsed.data.currency, rates })
     }
 
     /// Get product info (price, volume, etc.)
@@ -168,7 +453,8 @@ impl CoinbaseClient {
     pub async fn get_product_public(&self, product_id: &str) -> Result<Product> {
         let url = format!("https://api.coinbase.com/api/v3/brokerage/market/products/{product_id}");
 
-        let response = reqwest::Client::new()
+        let response = self
+            .http
             .get(&url)
             .header("Content-Type", "application/json")
             .send()
@@ -177,6 +463,23 @@ impl CoinbaseClient {
         Self::handle_response(response).await
     }
 
+    /// List all tradable products via the public API (no auth required). Used for
+    /// pairlist discovery (see `crate::pairlist::Pairlist`) rather than single-symbol
+    /// lookups, so it doesn't need `symbol`.
+    pub async fn list_products_public(&self) -> Result<Vec<Product>> {
+        let url = "https://api.coinbase.com/api/v3/brokerage/market/products";
+
+        let response = self
+            .http
+            .get(url)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?;
+
+        let parsed: ProductsResponse = Self::handle_response(response).await?;
+        Ok(parsed.products)
+    }
+
     /// Get current price for a symbol
     pub async fn get_price(&self, symbol: &str) -> Result<f64> {
         let product = self.get_product(symbol).await?;
@@ -186,6 +489,25 @@ impl CoinbaseClient {
             .map_err(|_| TradingError::CoinbaseApi(format!("Invalid price for {symbol}")))
     }
 
+    /// Get the live order book for a symbol via the public API (no auth required).
+    /// `limit` caps how many levels per side come back - enough to walk for a
+    /// liquidation-value estimate without pulling the entire book.
+    pub async fn get_product_book(&self, symbol: &str, limit: u32) -> Result<OrderbookLevels> {
+        let url = format!(
+            "https://api.coinbase.com/api/v3/brokerage/market/product_book?product_id={symbol}&limit={limit}"
+        );
+
+        let response = self
+            .http
+            .get(&url)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?;
+
+        let parsed: ProductBookResponse = Self::handle_response(response).await?;
+        parsed.pricebook.try_into()
+    }
+
     /// Get 24h price change percentage
     pub async fn get_price_change_24h(&self, symbol: &str) -> Result<f64> {
         let product = self.get_product(symbol).await?;
@@ -195,6 +517,49 @@ impl CoinbaseClient {
             .map_err(|_| TradingError::CoinbaseApi(format!("Invalid price change for {symbol}")))
     }
 
+    /// Get recent hourly candles via the public endpoint (no auth required).
+    /// Used for support/resistance pivot detection; returns an empty vec on any
+    /// fetch/parse failure rather than erroring, matching `get_product_stats`'s
+    /// candle-fetch fallback behavior.
+    pub async fn get_recent_candles(&self, symbol: &str, limit: u32) -> Vec<Candle> {
+        let url = format!(
+            "https://api.coinbase.com/api/v3/brokerage/market/products/{symbol}/candles?granularity=ONE_HOUR&limit={limit}"
+        );
+        match self
+            .http
+            .get(&url)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+        {
+            Ok(response) => response
+                .json::<CandlesResponse>()
+                .await
+                .map(|r| r.candles)
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Get candles of `granularity` for `[start, end)` (Unix seconds) via the public endpoint.
+    /// Unlike `get_recent_candles`, this takes an explicit range instead of `limit` and
+    /// propagates fetch/parse failures instead of swallowing them, since `candle_store`'s
+    /// `backfill_candles` needs to know a page actually failed rather than silently
+    /// treating it as "no candles in range".
+    pub async fn get_candles_range(&self, symbol: &str, granularity: &str, start: i64, end: i64) -> Result<Vec<Candle>> {
+        let url = format!(
+            "https://api.coinbase.com/api/v3/brokerage/market/products/{symbol}/candles?granularity={granularity}&start={start}&end={end}"
+        );
+        let response = self
+            .http
+            .get(&url)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?;
+        let parsed: CandlesResponse = Self::handle_response(response).await?;
+        Ok(parsed.candles)
+    }
+
     /// Get comprehensive product stats including real 24h high/low and trend
     /// Uses PUBLIC endpoints for market data consistency with /api/scan
     pub async fn get_product_stats(&self, symbol: &str) -> Result<ProductStats> {
@@ -212,7 +577,8 @@ impl CoinbaseClient {
         let url = format!(
             "https://api.coinbase.com/api/v3/brokerage/market/products/{symbol}/candles?granularity=ONE_HOUR&limit=24"
         );
-        let candles: CandlesResponse = match reqwest::Client::new()
+        let candles: CandlesResponse = match self
+            .http
             .get(&url)
             .header("Content-Type", "application/json")
             .send()
@@ -286,7 +652,8 @@ impl CoinbaseClient {
         let url = format!(
             "https://api.coinbase.com/api/v3/brokerage/market/products/{symbol}/candles?granularity=ONE_HOUR&limit=24"
         );
-        let candles: CandlesResponse = match reqwest::Client::new()
+        let candles: CandlesResponse = match self
+            .http
             .get(&url)
             .header("Content-Type", "application/json")
             .send()
@@ -344,14 +711,19 @@ impl CoinbaseClient {
         })
     }
 
-    /// Place a market buy order (by quote size in USD)
+    /// Place a market buy order (by quote size in USD). Rounds down to the product's
+    /// `quote_increment` before serializing so Coinbase doesn't reject it for
+    /// exceeding the increment `f64` formatting alone can't guarantee.
     pub async fn market_buy(&self, symbol: &str, usd_amount: f64) -> Result<OrderResponse> {
+        let quote_increment = self.get_product(symbol).await?.quote_increment_decimal()?;
+        let rounded = round_to_increment(decimal_from_f64(usd_amount)?, quote_increment);
+
         let order = OrderRequest {
             client_order_id: uuid::Uuid::new_v4().to_string(),
             product_id: symbol.to_string(),
             side: "BUY".to_string(),
             order_configuration: OrderConfiguration::MarketMarketIoc {
-                quote_size: Some(format!("{usd_amount:.2}")),
+                quote_size: Some(rounded.to_string()),
                 base_size: None,
             },
         };
@@ -359,22 +731,28 @@ impl CoinbaseClient {
         self.place_order(order).await
     }
 
-    /// Place a market sell order (by base size)
+    /// Place a market sell order (by base size). Rounds down to the product's
+    /// `base_increment` before serializing, same reasoning as `market_buy`.
     pub async fn market_sell(&self, symbol: &str, quantity: f64) -> Result<OrderResponse> {
+        let base_increment = self.get_product(symbol).await?.base_increment_decimal()?;
+        let rounded = round_to_increment(decimal_from_f64(quantity)?, base_increment);
+
         let order = OrderRequest {
             client_order_id: uuid::Uuid::new_v4().to_string(),
             product_id: symbol.to_string(),
             side: "SELL".to_string(),
             order_configuration: OrderConfiguration::MarketMarketIoc {
                 quote_size: None,
-                base_size: Some(format!("{quantity:.8}")),
+                base_size: Some(rounded.to_string()),
             },
         };
 
         self.place_order(order).await
     }
 
-    /// Place a limit order
+    /// Place a limit order. Rounds `quantity`/`price` down to the product's
+    /// `base_increment`/`quote_increment` before serializing, same reasoning as
+    /// `market_buy`/`market_sell`.
     pub async fn limit_order(
         &self,
         symbol: &str,
@@ -382,6 +760,10 @@ impl CoinbaseClient {
         quantity: f64,
         price: f64,
     ) -> Result<OrderResponse> {
+        let product = self.get_product(symbol).await?;
+        let rounded_quantity = round_to_increment(decimal_from_f64(quantity)?, product.base_increment_decimal()?);
+        let rounded_price = round_to_increment(decimal_from_f64(price)?, product.quote_increment_decimal()?);
+
         let order = OrderRequest {
             client_order_id: uuid::Uuid::new_v4().to_string(),
             product_id: symbol.to_string(),
@@ -390,8 +772,8 @@ impl CoinbaseClient {
                 OrderSide::Sell => "SELL".to_string(),
             },
             order_configuration: OrderConfiguration::LimitLimitGtc {
-                base_size: format!("{quantity:.8}"),
-                limit_price: format!("{price:.2}"),
+                base_size: rounded_quantity.to_string(),
+                limit_price: rounded_price.to_string(),
                 post_only: false,
             },
         };
@@ -405,39 +787,139 @@ impl CoinbaseClient {
         self.post(path, &order).await
     }
 
-    /// Perform GET request with authentication
-    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
-        let jwt = self.auth.generate_jwt("GET", path)?;
-        let url = format!("{BASE_URL}{path}");
+    /// Fetch the current fill status of a previously-placed order
+    pub async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        let path = format!("/api/v3/brokerage/orders/historical/{order_id}");
+        let response: OrderStatusResponse = self.get(&path).await?;
+        Ok(response.order)
+    }
 
-        let response = reqwest::Client::new()
-            .get(&url)
-            .header("Authorization", format!("Bearer {jwt}"))
-            .header("Content-Type", "application/json")
-            .send()
-            .await?;
+    /// Cancel a still-open order
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let results = self.cancel_orders(&[order_id]).await?;
 
-        Self::handle_response(response).await
+        if results.iter().any(|r| r.order_id == order_id && !r.success) {
+            return Err(TradingError::CoinbaseApi(format!(
+                "Failed to cancel order {order_id}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a batch of still-open orders in one request, returning each order's
+    /// individual success/failure rather than erroring out on the first failure
+    pub async fn cancel_orders(&self, order_ids: &[&str]) -> Result<Vec<CancelResult>> {
+        let path = "/api/v3/brokerage/orders/batch_cancel";
+        let body = CancelOrdersRequest {
+            order_ids: order_ids.iter().map(|id| id.to_string()).collect(),
+        };
+        let response: CancelOrdersResponse = self.post(path, &body).await?;
+        Ok(response.results)
+    }
+
+    /// List historical orders matching `filter` (open/filled/cancelled)
+    pub async fn list_orders(&self, filter: OrderListFilter) -> Result<Vec<OrderStatus>> {
+        let path = format!(
+            "/api/v3/brokerage/orders/historical/batch?order_status={}",
+            filter.as_query_value()
+        );
+        let response: OrdersResponse = self.get(&path).await?;
+        Ok(response.orders)
     }
 
-    /// Perform POST request with authentication
+    /// Wait for a free slot in the token bucket before firing a request
+    async fn throttle(&self) {
+        let wait = self.limiter.borrow_mut().acquire_wait_seconds();
+        if wait > 0.0 {
+            worker::Delay::from(std::time::Duration::from_secs_f64(wait)).await;
+        }
+    }
+
+    /// `Retry-After` seconds if `response` was rate limited, else `None`
+    fn retry_after(response: &reqwest::Response) -> Option<u64> {
+        if response.status() != 429 {
+            return None;
+        }
+        Some(
+            response
+                .headers()
+                .get("Retry-After")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1),
+        )
+    }
+
+    /// Sleep before the next 429 retry: `retry_after` doubled per attempt, plus up to
+    /// 20% jitter so retrying callers don't all wake up in lockstep
+    async fn backoff_delay(attempt: u32, retry_after: u64) {
+        let exponential = retry_after as f64 * 2f64.powi(attempt as i32 - 1);
+        // Derive jitter from a fresh UUID's low bits rather than pulling in `rand`
+        // for one fraction - the same trick `generate_jwt`'s nonce already uses.
+        let jitter_fraction = (uuid::Uuid::new_v4().as_u128() & 0xFFFF) as f64 / u16::MAX as f64;
+        let seconds = exponential * (1.0 + 0.2 * jitter_fraction);
+        worker::console_warn!("Rate limited, retrying in {:.1}s (attempt {})", seconds, attempt);
+        worker::Delay::from(std::time::Duration::from_secs_f64(seconds)).await;
+    }
+
+    /// Perform GET request with authentication, rate limiting, and 429 retry
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        let url = format!("{BASE_URL}{path}");
+        let mut attempt = 0;
+
+        loop {
+            self.throttle().await;
+            let jwt = self.auth.generate_jwt("GET", path)?;
+            let response = self
+                .http
+                .get(&url)
+                .header("Authorization", format!("Bearer {jwt}"))
+                .header("Content-Type", "application/json")
+                .send()
+                .await?;
+
+            match Self::retry_after(&response) {
+                Some(retry_after) if attempt < MAX_RETRY_ATTEMPTS => {
+                    attempt += 1;
+                    Self::backoff_delay(attempt, retry_after).await;
+                }
+                Some(retry_after) => return Err(TradingError::RateLimit(retry_after)),
+                None => return Self::handle_response(response).await,
+            }
+        }
+    }
+
+    /// Perform POST request with authentication, rate limiting, and 429 retry
     async fn post<T: for<'de> Deserialize<'de>, B: Serialize>(
         &self,
         path: &str,
         body: &B,
     ) -> Result<T> {
-        let jwt = self.auth.generate_jwt("POST", path)?;
         let url = format!("{BASE_URL}{path}");
-
-        let response = reqwest::Client::new()
-            .post(&url)
-            .header("Authorization", format!("Bearer {jwt}"))
-            .header("Content-Type", "application/json")
-            .json(body)
-            .send()
-            .await?;
-
-        Self::handle_response(response).await
+        let mut attempt = 0;
+
+        loop {
+            self.throttle().await;
+            let jwt = self.auth.generate_jwt("POST", path)?;
+            let response = self
+                .http
+                .post(&url)
+                .header("Authorization", format!("Bearer {jwt}"))
+                .header("Content-Type", "application/json")
+                .json(body)
+                .send()
+                .await?;
+
+            match Self::retry_after(&response) {
+                Some(retry_after) if attempt < MAX_RETRY_ATTEMPTS => {
+                    attempt += 1;
+                    Self::backoff_delay(attempt, retry_after).await;
+                }
+                Some(retry_after) => return Err(TradingError::RateLimit(retry_after)),
+                None => return Self::handle_response(response).await,
+            }
+        }
     }
 
     /// Handle API response, checking for errors
@@ -446,17 +928,6 @@ impl CoinbaseClient {
     ) -> Result<T> {
         let status = response.status();
 
-        if status == 429 {
-            // Rate limited
-            let retry_after = response
-                .headers()
-                .get("Retry-After")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(1);
-            return Err(TradingError::RateLimit(retry_after));
-        }
-
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -491,4 +962,49 @@ mod tests {
         assert!(json.contains("BTC-USD"));
         assert!(json.contains("BUY"));
     }
+
+    #[test]
+    fn test_order_list_filter_query_values() {
+        assert_eq!(OrderListFilter::Open.as_query_value(), "OPEN");
+        assert_eq!(OrderListFilter::Filled.as_query_value(), "FILLED");
+        assert_eq!(OrderListFilter::Cancelled.as_query_value(), "CANCELLED");
+    }
+
+    #[test]
+    fn test_product_increment_rounding() {
+        let product = Product {
+            product_id: "BTC-USD".to_string(),
+            price: "50000.12".to_string(),
+            price_percentage_change_24h: "1.0".to_string(),
+            volume_24h: "1000".to_string(),
+            base_min_size: "0.0001".to_string(),
+            base_max_size: "100".to_string(),
+            quote_increment: "0.01".to_string(),
+            base_increment: "0.00000001".to_string(),
+        };
+
+        let quote_increment = product.quote_increment_decimal().expect("valid increment");
+        let oversized_quote = decimal_from_f64(123.456789).expect("valid decimal");
+        assert_eq!(round_to_increment(oversized_quote, quote_increment).to_string(), "123.45");
+
+        let base_increment = product.base_increment_decimal().expect("valid increment");
+        let precise_base = decimal_from_f64(0.123456789).expect("valid decimal");
+        assert_eq!(round_to_increment(precise_base, base_increment).to_string(), "0.12345678");
+    }
+
+    #[test]
+    fn test_token_bucket_starts_full() {
+        let mut bucket = TokenBucket::new(30.0, 30.0);
+        // A fresh bucket should let the first several requests through with no wait
+        for _ in 0..5 {
+            assert_eq!(bucket.acquire_wait_seconds(), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_token_bucket_waits_once_exhausted() {
+        let mut bucket = TokenBucket::new(1.0, 1.0);
+        assert_eq!(bucket.acquire_wait_seconds(), 0.0); // consumes the only token
+        assert!(bucket.acquire_wait_seconds() > 0.0); // bucket empty, must wait for a refill
+    }
 }
@@ -1,6 +1,10 @@
 //! Configuration management for the trading bot
 
 use crate::error::{Result, TradingError};
+use crate::lots::CostBasisMethod;
+use crate::types::PositionSide;
+use serde::Deserialize;
+use std::collections::HashMap;
 use worker::Env;
 
 /// Trading bot configuration
@@ -24,6 +28,9 @@ pub struct Config {
     pub max_sl_percent: f64,        // Max stop-loss bound
     pub min_tp_percent: f64,        // Min take-profit bound
     pub max_tp_percent: f64,        // Max take-profit bound
+    /// Trailing-stop distance as a multiple of ATR (`Position.entry_volatility / 2`),
+    /// used in place of `trailing_stop_percent` whenever a position carries volatility.
+    pub atr_trail_multiplier: f64,
 
     /// Dynamic Position Sizing (Risk-Based)
     /// Note: These are BASE values - actual values are adjusted by CapitalTier
@@ -54,8 +61,282 @@ pub struct Config {
     pub min_volume_usd: f64,            // Minimum 24h volume in USD
     pub max_position_age_hours: f64,    // Time-based exit (0 = disabled)
 
+    /// Allow the strategy to emit `TradingSignal::Short` entries. Off by default since
+    /// short delivery (borrow/margin) isn't wired into every account tier.
+    pub enable_shorts: bool,
+
+    /// Support/resistance detection (see `support_resistance::SupportResistance`)
+    /// Use detected S/R clusters to drive entries instead of the 24h range-position heuristic
+    pub enable_sr_filter: bool,
+    /// Swing-pivot lookback window: a bar's high/low must exceed this many neighbors on each side
+    pub sr_pivot_window: usize,
+    /// Cluster pivots within this % of each other into the same support/resistance level
+    pub sr_tolerance_percent: f64,
+    /// Drop clusters whose total touch volume is below this threshold (filters noise pivots)
+    pub sr_min_cluster_volume: f64,
+    /// How close (%) price must be to a detected level to act on it
+    pub sr_proximity_percent: f64,
+
+    /// DCA (dollar-cost-average) position adjustment
+    /// Add to a losing position every time it drops this many % further below the average entry
+    pub dca_step_percent: f64,
+    /// Maximum number of DCA adds per position (0 disables averaging entirely)
+    pub max_entry_adjustments: u8,
+
+    /// Edge/expectancy-based position sizing (see `edge::TradeHistory`)
+    /// Scale tier risk by a fractional-Kelly stake derived from realized per-symbol
+    /// expectancy, and refuse to trade symbols with expectancy <= 0.
+    pub enable_edge_sizing: bool,
+    /// Minimum closed trades for a symbol before its expectancy is trusted
+    pub edge_min_trades: usize,
+    /// Upper bound on the fractional-Kelly stake (full Kelly is considered too aggressive)
+    pub edge_kelly_cap: f64,
+    /// Assumed win probability fed to `kelly::kelly_fraction` for a symbol with no
+    /// realized trade history yet - see `TradingStrategy::calculate_position_size`.
+    pub kelly_win_probability_estimate: f64,
+
+    /// Scaled take-profit ladder: (R-multiple, fraction of remaining position to close).
+    /// e.g. `[(1.0, 0.5), (2.0, 0.3), (3.0, 0.2)]` closes 50% at +1R, 30% at +2R, 20% at +3R.
+    /// Empty means single all-or-nothing TP (current behavior).
+    pub tp_levels: Vec<(f64, f64)>,
+    /// Once this many scaled take-profit targets have fired, move the hard stop-loss
+    /// to breakeven (entry price) to lock in a risk-free runner. `None` disables this.
+    pub move_stop_to_breakeven_after: Option<usize>,
+
+    /// Time-decaying minimal-ROI table: sorted `(holding_minutes, required_profit_percent)`
+    /// pairs, read via `roi_target`. `check_exit` picks the largest key `<=` the
+    /// position's current holding duration and exits once PnL% meets that bucket's
+    /// threshold, e.g. `[(0, 4.0), (60, 2.0), (240, 1.0), (2880, 0.0)]` requires 4%
+    /// immediately, easing to accepting any non-negative profit after two days. Empty
+    /// disables ROI exits entirely, leaving the hard `max_position_age_hours` cutoff
+    /// as the only time exit.
+    pub minimal_roi: Vec<(u64, f64)>,
+
+    /// Margin mode gating leverage. `Spot` (the default) ignores `target_leverage`
+    /// entirely; `Isolated`/`Cross` allow it, subject to `leverage_tiers`. See
+    /// `effective_trading_mode` for the no-tiers fallback.
+    pub trading_mode: TradingMode,
+    /// Desired leverage for new positions. `1.0` (the default) is plain spot sizing;
+    /// anything higher is capped by `leverage_tiers` and gated by the liquidation
+    /// guardrail in `TradingStrategy::calculate_position_size`.
+    pub target_leverage: f64,
+    /// Per-notional-band leverage/maintenance-margin schedule, ascending by
+    /// `max_notional_usd`. Empty disables leverage entirely (`target_leverage` is
+    /// ignored and sizing behaves exactly as it did before leverage support existed) -
+    /// see `effective_trading_mode`.
+    pub leverage_tiers: Vec<LeverageTier>,
+
+    /// Cost-of-carry charged per hour held, as a fraction of entry notional (e.g.
+    /// `0.0001` = 0.01%/hr). `0.0` (the default) disables funding accounting entirely:
+    /// spot users see no change in PnL or exit behavior.
+    pub funding_rate_per_hour: f64,
+    /// Once accrued funding erodes at least this fraction of unrealized profit,
+    /// `check_exit` fires `ExitReason::CarryExceeded` rather than let a winning-on-price
+    /// position keep bleeding carry. `None` disables the check (funding still drags
+    /// net PnL, it just never forces an exit on its own).
+    pub max_funding_drag_fraction: Option<f64>,
+
+    /// How long to wait (via `TradingEngine::reconcile_pending_orders` polling each
+    /// cycle) for an entry/full-close order to report `FILLED` before cancelling it.
+    /// Mirrors freqtrade's `unfilledtimeout`.
+    pub unfilled_order_timeout_seconds: u64,
+    /// How many times to re-place an order that timed out unfilled before giving up
+    /// on it entirely. Mirrors freqtrade's `exit_timeout_count`.
+    pub max_order_retries: u32,
+
+    /// Dynamic pairlist (freqtrade-style pair discovery): rank all tradable products
+    /// by volume and filter by price/spread/blacklist at the start of each cycle,
+    /// instead of only scanning the fixed `symbols` watchlist. Off by default -
+    /// `symbols` stays the scan list until this is enabled (see `pairlist::Pairlist`).
+    pub enable_dynamic_pairlist: bool,
+    /// How many top-volume pairs survive the ranking stage
+    pub pairlist_top_n: usize,
+    /// Minimum 24h quote volume (USD) for a pair to be considered
+    pub pairlist_min_volume_usd: f64,
+    /// Price bounds (USD) a pair's current price must fall within
+    pub pairlist_min_price: f64,
+    pub pairlist_max_price: f64,
+    /// Maximum spread (%) a pair may have
+    pub pairlist_max_spread_percent: f64,
+    /// Symbols never considered regardless of rank (e.g. low-liquidity coins)
+    pub pairlist_blacklist: Vec<String>,
+
     /// Symbols to trade
     pub symbols: Vec<String>,
+
+    /// Automatic entry circuit-breakers layered on top of the static
+    /// `daily_trade_limit` (see `protections::Protections`). Each rule is
+    /// independently toggleable and reads its own parameters from env vars.
+    /// No re-entry on a symbol for `cooldown_minutes` after it last closed.
+    pub enable_cooldown_protection: bool,
+    pub cooldown_minutes: u64,
+
+    /// Pause entries on every symbol for `stoploss_guard_stop_minutes` once
+    /// `stoploss_guard_trades` stop-losses land within a rolling
+    /// `stoploss_guard_lookback_minutes` window.
+    pub enable_stoploss_guard: bool,
+    pub stoploss_guard_trades: u32,
+    pub stoploss_guard_lookback_minutes: u64,
+    pub stoploss_guard_stop_minutes: u64,
+
+    /// Halt entries on every symbol while the portfolio's peak-to-trough decline
+    /// over the trailing `drawdown_protection_lookback_minutes` exceeds
+    /// `max_drawdown_protection_percent`.
+    pub enable_drawdown_protection: bool,
+    pub max_drawdown_protection_percent: f64,
+    pub drawdown_protection_lookback_minutes: u64,
+
+    /// Cost-basis method for matching sells against open lots in `LotLedger`,
+    /// backing the per-asset realized/unrealized PnL breakdown on `/api/portfolio`.
+    pub cost_basis_method: CostBasisMethod,
+
+    /// Reporting currency for `/api/portfolio`'s formatted totals (see `crate::fx`).
+    /// Cash balances and position values are converted into this currency via live
+    /// FX/crypto rates before summing, instead of the hardcoded `USD`/`$` the endpoint
+    /// used to assume. Internal trading math (order sizing, P&L accumulators) is
+    /// unaffected - Coinbase accounts/orders stay USD-denominated regardless.
+    pub base_currency: String,
+
+    /// Upper bound (%) on the implied slippage `/api/portfolio`'s `liquidation_value`
+    /// will assume when the live order book is thin: if walking bid levels to fill a
+    /// position's full quantity implies a worse exit price than this, the exit price
+    /// is capped here instead of reporting an unbounded worst case. Must be in
+    /// `(0, 100]` (see `Config::validate`) - 0 would mean "no slippage tolerated at
+    /// all", which can't be met by any real book.
+    pub max_liquidation_slippage_percent: f64,
+
+    /// Margin (%, of the boundary value) a portfolio must clear past a
+    /// `CapitalTier` boundary before `crate::capital_tier::TierTransition` starts
+    /// ramping toward it, in either direction. Keeps a portfolio oscillating right
+    /// at a boundary (e.g. hovering near $5,000) from repeatedly re-triggering a
+    /// transition it hasn't even finished ramping through yet.
+    pub tier_hysteresis_percent: f64,
+
+    /// Number of evaluation cycles `TierTransition` takes to ramp
+    /// `max_position_percent`/`risk_per_trade_percent` fully from the old tier's
+    /// values to the new tier's, once a transition has cleared the hysteresis
+    /// margin. `1` ramps in a single step (no smoothing).
+    pub tier_transition_cycles: u32,
+
+    /// Per-symbol overrides layered on top of this config by `resolved_for`, sourced
+    /// from a layered config file's `pair_overrides` table (see `Config::from_layered`).
+    /// Env vars can't express per-symbol data, so this is always empty for a plain
+    /// `Config::from_env` and only ever populated via `from_layered`.
+    pub pair_overrides: HashMap<String, PairOverride>,
+}
+
+/// One symbol's (or a layered config file's top-level) TP/SL/entry
+/// threshold/sizing/filter overrides. Every field is optional - unset ones leave
+/// whatever `Config` already resolved to (env-default, or file-base once
+/// `Config::from_layered` has overlaid the file on top of env) untouched.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PairOverride {
+    pub take_profit_percent: Option<f64>,
+    pub stop_loss_percent: Option<f64>,
+    pub trailing_stop_percent: Option<f64>,
+    pub base_entry_threshold: Option<f64>,
+    pub max_risk_per_trade_percent: Option<f64>,
+    pub max_portfolio_per_position: Option<f64>,
+    pub enable_trend_filter: Option<bool>,
+    pub enable_volume_filter: Option<bool>,
+    pub enable_market_regime_filter: Option<bool>,
+    pub enable_sr_filter: Option<bool>,
+}
+
+impl PairOverride {
+    /// Apply every field this override sets onto `config`, leaving fields it
+    /// leaves unset alone.
+    fn apply(&self, config: &mut Config) {
+        if let Some(v) = self.take_profit_percent {
+            config.take_profit_percent = v;
+        }
+        if let Some(v) = self.stop_loss_percent {
+            config.stop_loss_percent = v;
+        }
+        if let Some(v) = self.trailing_stop_percent {
+            config.trailing_stop_percent = v;
+        }
+        if let Some(v) = self.base_entry_threshold {
+            config.base_entry_threshold = v;
+        }
+        if let Some(v) = self.max_risk_per_trade_percent {
+            config.max_risk_per_trade_percent = v;
+        }
+        if let Some(v) = self.max_portfolio_per_position {
+            config.max_portfolio_per_position = v;
+        }
+        if let Some(v) = self.enable_trend_filter {
+            config.enable_trend_filter = v;
+        }
+        if let Some(v) = self.enable_volume_filter {
+            config.enable_volume_filter = v;
+        }
+        if let Some(v) = self.enable_market_regime_filter {
+            config.enable_market_regime_filter = v;
+        }
+        if let Some(v) = self.enable_sr_filter {
+            config.enable_sr_filter = v;
+        }
+    }
+}
+
+/// A layered config document for `Config::from_layered`: the same overridable
+/// fields as `PairOverride`, applied globally as the "file-base" layer, plus the
+/// per-symbol `pair_overrides` table.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigFile {
+    #[serde(flatten)]
+    pub base: PairOverride,
+    #[serde(default)]
+    pub pair_overrides: HashMap<String, PairOverride>,
+}
+
+/// One band of a leverage/maintenance-margin schedule (see `Config::leverage_tiers`).
+/// Applies to positions whose notional is at most `max_notional_usd`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LeverageTier {
+    pub max_notional_usd: f64,
+    pub max_leverage: f64,
+    pub maintenance_margin_rate: f64,
+    /// Fixed maintenance-margin discount for this bracket (USD), the tiered-schedule
+    /// term exchanges like Binance publish alongside `maintenance_margin_rate` so the
+    /// required maintenance margin isn't purely proportional to notional. `0.0` for a
+    /// schedule that only uses the rate.
+    pub maintenance_amount: f64,
+}
+
+/// Margin mode for new positions (see `Config::trading_mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TradingMode {
+    #[default]
+    Spot,
+    Isolated,
+    Cross,
+}
+
+impl std::fmt::Display for TradingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradingMode::Spot => write!(f, "spot"),
+            TradingMode::Isolated => write!(f, "isolated"),
+            TradingMode::Cross => write!(f, "cross"),
+        }
+    }
+}
+
+fn parse_trading_mode(raw: &str) -> TradingMode {
+    match raw.trim().to_lowercase().as_str() {
+        "isolated" => TradingMode::Isolated,
+        "cross" => TradingMode::Cross,
+        _ => TradingMode::Spot,
+    }
+}
+
+fn parse_cost_basis_method(raw: &str) -> CostBasisMethod {
+    match raw.trim().to_lowercase().as_str() {
+        "average_cost" | "average" => CostBasisMethod::AverageCost,
+        _ => CostBasisMethod::Fifo,
+    }
 }
 
 impl Config {
@@ -102,7 +383,11 @@ impl Config {
             max_tp_percent: env.var("MAX_TP_PERCENT")
                 .map(|v| v.to_string().parse().unwrap_or(10.0))
                 .unwrap_or(10.0),
-            
+
+            atr_trail_multiplier: env.var("ATR_TRAIL_MULTIPLIER")
+                .map(|v| v.to_string().parse().unwrap_or(1.5))
+                .unwrap_or(1.5),
+
             // Dynamic Position Sizing
             max_risk_per_trade_percent: env.var("MAX_RISK_PER_TRADE_PERCENT")
                 .map(|v| v.to_string().parse().unwrap_or(2.0))
@@ -181,9 +466,351 @@ impl Config {
             max_position_age_hours: env.var("MAX_POSITION_AGE_HOURS")
                 .map(|v| v.to_string().parse().unwrap_or(48.0))
                 .unwrap_or(48.0),  // Give trades 48h to work out
+
+            enable_shorts: env.var("ENABLE_SHORTS")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),  // Off until borrow/margin delivery is confirmed for the account
+
+            enable_sr_filter: env.var("ENABLE_SR_FILTER")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),  // Off until validated against the range-position baseline
+
+            sr_pivot_window: env.var("SR_PIVOT_WINDOW")
+                .map(|v| v.to_string().parse().unwrap_or(2))
+                .unwrap_or(2),
+
+            sr_tolerance_percent: env.var("SR_TOLERANCE_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(0.5))
+                .unwrap_or(0.5),
+
+            sr_min_cluster_volume: env.var("SR_MIN_CLUSTER_VOLUME")
+                .map(|v| v.to_string().parse().unwrap_or(0.0))
+                .unwrap_or(0.0),  // Off by default - callers opt in by raising this
+
+            sr_proximity_percent: env.var("SR_PROXIMITY_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(1.0))
+                .unwrap_or(1.0),
+
+            dca_step_percent: env.var("DCA_STEP_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(2.0))
+                .unwrap_or(2.0),
+
+            max_entry_adjustments: env.var("MAX_ENTRY_ADJUSTMENTS")
+                .map(|v| v.to_string().parse().unwrap_or(0))
+                .unwrap_or(0),  // Off by default - averaging down is opt-in
+
+            enable_edge_sizing: env.var("ENABLE_EDGE_SIZING")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),  // Off until a symbol has built up real trade history
+
+            edge_min_trades: env.var("EDGE_MIN_TRADES")
+                .map(|v| v.to_string().parse().unwrap_or(20))
+                .unwrap_or(20),
+
+            edge_kelly_cap: env.var("EDGE_KELLY_CAP")
+                .map(|v| v.to_string().parse().unwrap_or(0.5))
+                .unwrap_or(0.5),  // Half-Kelly - full Kelly is too aggressive for live capital
+
+            kelly_win_probability_estimate: env.var("KELLY_WIN_PROBABILITY_ESTIMATE")
+                .map(|v| v.to_string().parse().unwrap_or(0.5))
+                .unwrap_or(0.5),  // Coin-flip until a symbol's real trade history can replace the guess
+
+            // Scaled take-profit ladder, e.g. "1.0:0.5,2.0:0.3,3.0:0.2"
+            tp_levels: env.var("TP_LEVELS")
+                .map(|v| Self::parse_tp_levels(&v.to_string()))
+                .unwrap_or_default(),
+
+            move_stop_to_breakeven_after: env.var("MOVE_STOP_TO_BREAKEVEN_AFTER")
+                .ok()
+                .and_then(|v| v.to_string().parse().ok()),
+
+            // Minimal-ROI table, e.g. "0:4.0,60:2.0,240:1.0,2880:0.0"
+            minimal_roi: env.var("MINIMAL_ROI")
+                .map(|v| Self::parse_minimal_roi(&v.to_string()))
+                .unwrap_or_default(),
+
+            trading_mode: env.var("TRADING_MODE")
+                .map(|v| parse_trading_mode(&v.to_string()))
+                .unwrap_or_default(),
+
+            target_leverage: env.var("TARGET_LEVERAGE")
+                .map(|v| v.to_string().parse().unwrap_or(1.0))
+                .unwrap_or(1.0),  // Spot sizing by default
+
+            // Leverage tiers. `LEVERAGE_TIERS_JSON` (a JSON array of
+            // `{max_notional_usd, max_leverage, maintenance_margin_rate, maintenance_amount}`
+            // objects) wins when set; otherwise fall back to the flat
+            // "5000:10:0.01:0,25000:5:0.02:25,1000000:3:0.05:1500"
+            // (max_notional_usd:max_leverage:maintenance_margin_rate:maintenance_amount) form.
+            leverage_tiers: env.var("LEVERAGE_TIERS_JSON")
+                .ok()
+                .and_then(|v| serde_json::from_str(&v.to_string()).ok())
+                .or_else(|| env.var("LEVERAGE_TIERS").ok().map(|v| Self::parse_leverage_tiers(&v.to_string())))
+                .unwrap_or_default(),
+
+            funding_rate_per_hour: env.var("FUNDING_RATE_PER_HOUR")
+                .map(|v| v.to_string().parse().unwrap_or(0.0))
+                .unwrap_or(0.0),  // Spot (no carry) by default
+
+            max_funding_drag_fraction: env.var("MAX_FUNDING_DRAG_FRACTION")
+                .ok()
+                .and_then(|v| v.to_string().parse().ok()),
+
+            unfilled_order_timeout_seconds: env.var("UNFILLED_ORDER_TIMEOUT_SECONDS")
+                .map(|v| v.to_string().parse().unwrap_or(30))
+                .unwrap_or(30),
+
+            max_order_retries: env.var("MAX_ORDER_RETRIES")
+                .map(|v| v.to_string().parse().unwrap_or(1))
+                .unwrap_or(1),
+
+            enable_dynamic_pairlist: env.var("ENABLE_DYNAMIC_PAIRLIST")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),  // Off by default - symbols stays the fixed watchlist
+
+            pairlist_top_n: env.var("PAIRLIST_TOP_N")
+                .map(|v| v.to_string().parse().unwrap_or(10))
+                .unwrap_or(10),
+
+            pairlist_min_volume_usd: env.var("PAIRLIST_MIN_VOLUME_USD")
+                .map(|v| v.to_string().parse().unwrap_or(1_000_000.0))
+                .unwrap_or(1_000_000.0),
+
+            pairlist_min_price: env.var("PAIRLIST_MIN_PRICE")
+                .map(|v| v.to_string().parse().unwrap_or(0.01))
+                .unwrap_or(0.01),
+
+            pairlist_max_price: env.var("PAIRLIST_MAX_PRICE")
+                .map(|v| v.to_string().parse().unwrap_or(100_000.0))
+                .unwrap_or(100_000.0),
+
+            pairlist_max_spread_percent: env.var("PAIRLIST_MAX_SPREAD_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(1.0))
+                .unwrap_or(1.0),
+
+            // Blacklisted symbols, e.g. "SHIB-USD,DOGE-USD"
+            pairlist_blacklist: env.var("PAIRLIST_BLACKLIST")
+                .map(|v| v.to_string().split(',').map(String::from).collect())
+                .unwrap_or_default(),
+
+            enable_cooldown_protection: env.var("ENABLE_COOLDOWN_PROTECTION")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),  // Off by default - no behavior change until opted in
+
+            cooldown_minutes: env.var("COOLDOWN_MINUTES")
+                .map(|v| v.to_string().parse().unwrap_or(60))
+                .unwrap_or(60),
+
+            enable_stoploss_guard: env.var("ENABLE_STOPLOSS_GUARD")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),
+
+            stoploss_guard_trades: env.var("STOPLOSS_GUARD_TRADES")
+                .map(|v| v.to_string().parse().unwrap_or(3))
+                .unwrap_or(3),
+
+            stoploss_guard_lookback_minutes: env.var("STOPLOSS_GUARD_LOOKBACK_MINUTES")
+                .map(|v| v.to_string().parse().unwrap_or(60))
+                .unwrap_or(60),
+
+            stoploss_guard_stop_minutes: env.var("STOPLOSS_GUARD_STOP_MINUTES")
+                .map(|v| v.to_string().parse().unwrap_or(120))
+                .unwrap_or(120),
+
+            enable_drawdown_protection: env.var("ENABLE_DRAWDOWN_PROTECTION")
+                .map(|v| v.to_string().to_lowercase() == "true")
+                .unwrap_or(false),
+
+            max_drawdown_protection_percent: env.var("MAX_DRAWDOWN_PROTECTION_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(10.0))
+                .unwrap_or(10.0),
+
+            drawdown_protection_lookback_minutes: env.var("DRAWDOWN_PROTECTION_LOOKBACK_MINUTES")
+                .map(|v| v.to_string().parse().unwrap_or(1440))
+                .unwrap_or(1440),  // 24h
+
+            cost_basis_method: env.var("COST_BASIS_METHOD")
+                .map(|v| parse_cost_basis_method(&v.to_string()))
+                .unwrap_or_default(),
+
+            max_liquidation_slippage_percent: env.var("MAX_LIQUIDATION_SLIPPAGE_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(5.0))
+                .unwrap_or(5.0),
+
+            base_currency: env.var("BASE_CURRENCY")
+                .map(|v| v.to_string().trim().to_uppercase())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| "USD".to_string()),
+
+            tier_hysteresis_percent: env.var("TIER_HYSTERESIS_PERCENT")
+                .map(|v| v.to_string().parse().unwrap_or(5.0))
+                .unwrap_or(5.0),
+
+            tier_transition_cycles: env.var("TIER_TRANSITION_CYCLES")
+                .map(|v| v.to_string().parse().unwrap_or(5))
+                .unwrap_or(5),
+
+            pair_overrides: HashMap::new(),
         })
     }
-    
+
+    /// Layer a TOML- or JSON-formatted config file on top of the env-sourced defaults.
+    /// `file_contents` is sniffed by its first non-whitespace character (`{` => JSON,
+    /// otherwise TOML) since either is an acceptable "config file" per the deployment
+    /// docs and the repo has no standing preference between the two. Resolution order
+    /// is pair-override -> file-base -> env-default: `from_env` forms the lowest-priority
+    /// base, the file's top-level fields override it, and its `pair_overrides` table is
+    /// kept as-is for later per-symbol resolution via `resolved_for` (env vars can't
+    /// express per-symbol data, so that table only ever comes from the file).
+    pub fn from_layered(env: &Env, file_contents: Option<&str>) -> Result<Self> {
+        let mut config = Self::from_env(env)?;
+        let Some(raw) = file_contents.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(config);
+        };
+
+        let file: ConfigFile = if raw.starts_with('{') {
+            serde_json::from_str(raw)
+                .map_err(|e| TradingError::Config(format!("invalid JSON config file: {e}")))?
+        } else {
+            toml::from_str(raw)
+                .map_err(|e| TradingError::Config(format!("invalid TOML config file: {e}")))?
+        };
+
+        file.base.apply(&mut config);
+        config.pair_overrides = file.pair_overrides;
+        Ok(config)
+    }
+
+    /// This config with `symbol`'s `pair_overrides` entry (if any) layered on top,
+    /// per the pair-override -> file-base -> env-default resolution order documented
+    /// on `from_layered`. Symbols with no override get an identical clone back.
+    pub fn resolved_for(&self, symbol: &str) -> Self {
+        let mut resolved = self.clone();
+        if let Some(overrides) = self.pair_overrides.get(symbol) {
+            overrides.apply(&mut resolved);
+        }
+        resolved
+    }
+
+    /// Parse a "R:fraction,R:fraction,..." string into a sorted TP ladder
+    fn parse_tp_levels(raw: &str) -> Vec<(f64, f64)> {
+        let mut levels: Vec<(f64, f64)> = raw
+            .split(',')
+            .filter_map(|entry| {
+                let (r, frac) = entry.split_once(':')?;
+                Some((r.trim().parse().ok()?, frac.trim().parse().ok()?))
+            })
+            .collect();
+        levels.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        levels
+    }
+
+    /// Parse a "minutes:percent,minutes:percent,..." string into a sorted ROI table
+    fn parse_minimal_roi(raw: &str) -> Vec<(u64, f64)> {
+        let mut table: Vec<(u64, f64)> = raw
+            .split(',')
+            .filter_map(|entry| {
+                let (minutes, percent) = entry.split_once(':')?;
+                Some((minutes.trim().parse().ok()?, percent.trim().parse().ok()?))
+            })
+            .collect();
+        table.sort_by_key(|(minutes, _)| *minutes);
+        table
+    }
+
+    /// Required profit percent for a position held `age_minutes`, per `minimal_roi`:
+    /// the largest bucket whose minute-key is `<= age_minutes`. Falls back to
+    /// `take_profit_percent` when `minimal_roi` is empty, so deployments that never
+    /// set `MINIMAL_ROI` see the same single-threshold behavior as before the ladder
+    /// existed. `None` only when the table is non-empty but `age_minutes` hasn't
+    /// reached its earliest bucket yet.
+    pub fn roi_target(&self, age_minutes: u64) -> Option<f64> {
+        if self.minimal_roi.is_empty() {
+            return Some(self.take_profit_percent);
+        }
+        self.minimal_roi
+            .iter()
+            .filter(|(minutes, _)| *minutes <= age_minutes)
+            .next_back()
+            .map(|(_, percent)| *percent)
+    }
+
+    /// `trading_mode` as actually applied: `Spot` whenever `leverage_tiers` is empty,
+    /// regardless of the stored setting, so a leaveraged mode configured without a
+    /// tier table never silently grants leverage - existing spot-only deployments
+    /// that don't set `LEVERAGE_TIERS`/`LEVERAGE_TIERS_JSON` are unaffected no matter
+    /// what `TRADING_MODE` says.
+    pub fn effective_trading_mode(&self) -> TradingMode {
+        if self.leverage_tiers.is_empty() {
+            TradingMode::Spot
+        } else {
+            self.trading_mode
+        }
+    }
+
+    /// Find the leverage tier applicable to a given notional size: the narrowest band
+    /// whose `max_notional_usd` still covers it, or the top band as a conservative
+    /// ceiling when the notional exceeds every configured band. `None` when no tiers
+    /// are configured at all.
+    fn leverage_tier_for_notional(&self, notional_usd: f64) -> Option<LeverageTier> {
+        self.leverage_tiers
+            .iter()
+            .find(|t| notional_usd <= t.max_notional_usd)
+            .copied()
+            .or_else(|| self.leverage_tiers.last().copied())
+    }
+
+    /// Maximum leverage available for a position of `notional_usd`, per whichever
+    /// `leverage_tiers` bracket covers it. `1.0` (spot) in `effective_trading_mode`'s
+    /// `Spot` mode, independent of `target_leverage`.
+    pub fn max_leverage_for(&self, notional_usd: f64) -> f64 {
+        if self.effective_trading_mode() == TradingMode::Spot {
+            return 1.0;
+        }
+        self.leverage_tier_for_notional(notional_usd).map_or(1.0, |t| t.max_leverage)
+    }
+
+    /// Estimated liquidation price for a position opened at `entry` with `leverage`
+    /// and `notional_usd` at entry, using the maintenance-margin rate and fixed
+    /// maintenance amount of whichever `leverage_tiers` bracket covers `notional_usd` -
+    /// the same tiered-margin schedule shape exchanges like Binance publish. `None`
+    /// in `effective_trading_mode`'s `Spot` mode, at `leverage <= 1.0`, or with
+    /// `entry <= 0.0`.
+    pub fn liquidation_price(&self, entry: f64, leverage: f64, side: PositionSide, notional_usd: f64) -> Option<f64> {
+        if self.effective_trading_mode() == TradingMode::Spot || leverage <= 1.0 || entry <= 0.0 {
+            return None;
+        }
+        let tier = self.leverage_tier_for_notional(notional_usd)?;
+        let quantity = notional_usd / entry;
+        let initial_margin = notional_usd / leverage;
+        let maintenance_margin = (notional_usd * tier.maintenance_margin_rate - tier.maintenance_amount).max(0.0);
+        let price_move = (initial_margin - maintenance_margin).max(0.0) / quantity;
+
+        Some(match side {
+            PositionSide::Long => entry - price_move,
+            PositionSide::Short => entry + price_move,
+        })
+    }
+
+    /// Parse a "max_notional:max_leverage:maintenance_margin_rate:maintenance_amount,..."
+    /// string into a sorted leverage schedule. The trailing `maintenance_amount` is
+    /// optional per entry and defaults to `0.0`.
+    fn parse_leverage_tiers(raw: &str) -> Vec<LeverageTier> {
+        let mut tiers: Vec<LeverageTier> = raw
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(':');
+                let max_notional_usd = parts.next()?.trim().parse().ok()?;
+                let max_leverage = parts.next()?.trim().parse().ok()?;
+                let maintenance_margin_rate = parts.next()?.trim().parse().ok()?;
+                let maintenance_amount = parts.next().and_then(|p| p.trim().parse().ok()).unwrap_or(0.0);
+                Some(LeverageTier { max_notional_usd, max_leverage, maintenance_margin_rate, maintenance_amount })
+            })
+            .collect();
+        tiers.sort_by(|a, b| a.max_notional_usd.partial_cmp(&b.max_notional_usd).unwrap_or(std::cmp::Ordering::Equal));
+        tiers
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<()> {
         if self.take_profit_percent <= 0.0 {
@@ -198,6 +825,100 @@ impl Config {
         if self.symbols.is_empty() {
             return Err(TradingError::Config("At least one symbol required".into()));
         }
+        if self.tp_levels.iter().any(|(r, frac)| *r <= 0.0 || *frac <= 0.0 || *frac > 1.0) {
+            return Err(TradingError::Config("tp_levels must have positive R-multiples and fractions in (0, 1]".into()));
+        }
+        if self.dca_step_percent <= 0.0 {
+            return Err(TradingError::Config("dca_step_percent must be positive".into()));
+        }
+        if self.edge_kelly_cap <= 0.0 || self.edge_kelly_cap > 1.0 {
+            return Err(TradingError::Config("edge_kelly_cap must be in (0, 1]".into()));
+        }
+        if self.kelly_win_probability_estimate <= 0.0 || self.kelly_win_probability_estimate >= 1.0 {
+            return Err(TradingError::Config("kelly_win_probability_estimate must be in (0, 1)".into()));
+        }
+        if self.move_stop_to_breakeven_after == Some(0) {
+            return Err(TradingError::Config("move_stop_to_breakeven_after must be at least 1".into()));
+        }
+        if self.atr_trail_multiplier <= 0.0 {
+            return Err(TradingError::Config("atr_trail_multiplier must be positive".into()));
+        }
+        if self.minimal_roi.iter().any(|(_, percent)| !percent.is_finite() || *percent < 0.0) {
+            return Err(TradingError::Config("minimal_roi percentages must be finite and non-negative".into()));
+        }
+        if self.minimal_roi.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(TradingError::Config("minimal_roi entries must be sorted by strictly increasing holding_minutes".into()));
+        }
+        if self.target_leverage < 1.0 {
+            return Err(TradingError::Config("target_leverage must be at least 1.0".into()));
+        }
+        if self.leverage_tiers.iter().any(|t| {
+            t.max_notional_usd <= 0.0
+                || t.max_leverage < 1.0
+                || !(0.0..1.0).contains(&t.maintenance_margin_rate)
+                || t.maintenance_amount < 0.0
+        }) {
+            return Err(TradingError::Config(
+                "leverage_tiers entries need max_notional_usd > 0, max_leverage >= 1.0, maintenance_margin_rate in [0, 1), and maintenance_amount >= 0".into(),
+            ));
+        }
+        if !self.leverage_tiers.is_empty() {
+            let ceiling = self.leverage_tiers.iter().map(|t| t.max_leverage).fold(f64::MIN, f64::max);
+            if self.target_leverage > ceiling {
+                return Err(TradingError::Config(format!(
+                    "target_leverage ({:.1}x) exceeds the highest configured leverage_tiers ceiling ({ceiling:.1}x)",
+                    self.target_leverage
+                )));
+            }
+        }
+        if self.funding_rate_per_hour < 0.0 {
+            return Err(TradingError::Config("funding_rate_per_hour cannot be negative".into()));
+        }
+        if self.max_funding_drag_fraction.is_some_and(|f| !(0.0..=1.0).contains(&f)) {
+            return Err(TradingError::Config("max_funding_drag_fraction must be in [0, 1]".into()));
+        }
+        if self.unfilled_order_timeout_seconds == 0 {
+            return Err(TradingError::Config("unfilled_order_timeout_seconds must be positive".into()));
+        }
+        if self.pairlist_top_n == 0 {
+            return Err(TradingError::Config("pairlist_top_n must be positive".into()));
+        }
+        if self.pairlist_min_price <= 0.0 || self.pairlist_max_price <= self.pairlist_min_price {
+            return Err(TradingError::Config("pairlist_max_price must be greater than pairlist_min_price > 0".into()));
+        }
+        if self.pairlist_max_spread_percent <= 0.0 {
+            return Err(TradingError::Config("pairlist_max_spread_percent must be positive".into()));
+        }
+        if self.cooldown_minutes == 0 {
+            return Err(TradingError::Config("cooldown_minutes must be positive".into()));
+        }
+        if self.stoploss_guard_trades == 0 {
+            return Err(TradingError::Config("stoploss_guard_trades must be positive".into()));
+        }
+        if self.stoploss_guard_lookback_minutes == 0 {
+            return Err(TradingError::Config("stoploss_guard_lookback_minutes must be positive".into()));
+        }
+        if self.stoploss_guard_stop_minutes == 0 {
+            return Err(TradingError::Config("stoploss_guard_stop_minutes must be positive".into()));
+        }
+        if self.max_drawdown_protection_percent <= 0.0 {
+            return Err(TradingError::Config("max_drawdown_protection_percent must be positive".into()));
+        }
+        if self.drawdown_protection_lookback_minutes == 0 {
+            return Err(TradingError::Config("drawdown_protection_lookback_minutes must be positive".into()));
+        }
+        if self.max_liquidation_slippage_percent <= 0.0 || self.max_liquidation_slippage_percent > 100.0 {
+            return Err(TradingError::Config("max_liquidation_slippage_percent must be in (0, 100]".into()));
+        }
+        if self.base_currency.is_empty() {
+            return Err(TradingError::Config("base_currency must not be empty".into()));
+        }
+        if self.tier_hysteresis_percent < 0.0 {
+            return Err(TradingError::Config("tier_hysteresis_percent must not be negative".into()));
+        }
+        if self.tier_transition_cycles == 0 {
+            return Err(TradingError::Config("tier_transition_cycles must be positive".into()));
+        }
         Ok(())
     }
 }
@@ -205,10 +926,291 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_config_defaults() {
         // Config validation tests would go here
         // Note: Full tests require mocking Env
     }
+
+    fn test_config() -> Config {
+        Config {
+            environment: "test".to_string(),
+            log_level: "debug".to_string(),
+            take_profit_percent: 1.5,
+            stop_loss_percent: 1.0,
+            trailing_stop_percent: 0.5,
+            atr_sl_multiplier: 1.0,
+            atr_tp_multiplier: 2.0,
+            min_sl_percent: 0.5,
+            max_sl_percent: 5.0,
+            min_tp_percent: 1.0,
+            max_tp_percent: 10.0,
+            atr_trail_multiplier: 1.5,
+            max_risk_per_trade_percent: 2.0,
+            max_portfolio_per_position: 25.0,
+            min_position_usd: 10.0,
+            cash_reserve_percent: 15.0,
+            max_total_positions: 8,
+            base_fee_percent: 0.60,
+            base_entry_threshold: 60.0,
+            min_entry_threshold: 40.0,
+            max_entry_threshold: 85.0,
+            cycle_interval_seconds: 15,
+            symbols: vec!["BTC-USD".to_string()],
+            daily_trade_limit: 30,
+            max_consecutive_errors: 5,
+            enable_trend_filter: false,
+            enable_volume_filter: false,
+            enable_market_regime_filter: false,
+            min_volume_usd: 1_000_000.0,
+            max_position_age_hours: 48.0,
+            enable_shorts: false,
+            enable_sr_filter: false,
+            sr_pivot_window: 2,
+            sr_tolerance_percent: 0.5,
+            sr_min_cluster_volume: 0.0,
+            sr_proximity_percent: 1.0,
+            dca_step_percent: 2.0,
+            max_entry_adjustments: 0,
+            enable_edge_sizing: false,
+            edge_min_trades: 20,
+            edge_kelly_cap: 0.5,
+            kelly_win_probability_estimate: 0.5,
+            tp_levels: vec![],
+            move_stop_to_breakeven_after: None,
+            minimal_roi: vec![],
+            trading_mode: TradingMode::Spot,
+            target_leverage: 1.0,
+            leverage_tiers: vec![],
+            funding_rate_per_hour: 0.0,
+            max_funding_drag_fraction: None,
+            unfilled_order_timeout_seconds: 30,
+            max_order_retries: 1,
+            enable_dynamic_pairlist: false,
+            pairlist_top_n: 10,
+            pairlist_min_volume_usd: 1_000_000.0,
+            pairlist_min_price: 0.01,
+            pairlist_max_price: 100_000.0,
+            pairlist_max_spread_percent: 1.0,
+            pairlist_blacklist: vec![],
+            enable_cooldown_protection: false,
+            cooldown_minutes: 60,
+            enable_stoploss_guard: false,
+            stoploss_guard_trades: 3,
+            stoploss_guard_lookback_minutes: 60,
+            stoploss_guard_stop_minutes: 120,
+            enable_drawdown_protection: false,
+            max_drawdown_protection_percent: 10.0,
+            drawdown_protection_lookback_minutes: 1440,
+            cost_basis_method: CostBasisMethod::Fifo,
+            max_liquidation_slippage_percent: 5.0,
+            base_currency: "USD".to_string(),
+            tier_hysteresis_percent: 5.0,
+            tier_transition_cycles: 5,
+            pair_overrides: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_roi_target_falls_back_to_take_profit_percent_when_empty() {
+        let config = test_config();
+        assert_eq!(config.roi_target(0), Some(config.take_profit_percent));
+        assert_eq!(config.roi_target(10_000), Some(config.take_profit_percent));
+    }
+
+    #[test]
+    fn test_roi_target_picks_largest_bucket_at_or_before_age() {
+        let mut config = test_config();
+        config.minimal_roi = vec![(0, 4.0), (60, 2.0), (240, 1.0), (2880, 0.0)];
+
+        assert_eq!(config.roi_target(10), Some(4.0));
+        assert_eq!(config.roi_target(90), Some(2.0));
+        assert_eq!(config.roi_target(4000), Some(0.0));
+    }
+
+    #[test]
+    fn test_roi_target_none_before_earliest_bucket() {
+        let mut config = test_config();
+        config.minimal_roi = vec![(60, 2.0)];
+
+        assert_eq!(config.roi_target(10), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_roi_percent() {
+        let mut config = test_config();
+        config.minimal_roi = vec![(0, -1.0)];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_unsorted_roi_table() {
+        let mut config = test_config();
+        config.minimal_roi = vec![(60, 2.0), (0, 4.0)];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_sorted_roi_table() {
+        let mut config = test_config();
+        config.minimal_roi = vec![(0, 4.0), (60, 2.0)];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_resolved_for_unknown_symbol_is_unchanged() {
+        let config = test_config();
+        let resolved = config.resolved_for("ETH-USD");
+        assert_eq!(resolved.take_profit_percent, config.take_profit_percent);
+        assert!(resolved.pair_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_resolved_for_applies_only_set_fields() {
+        let mut config = test_config();
+        config.pair_overrides.insert(
+            "ETH-USD".to_string(),
+            PairOverride { take_profit_percent: Some(3.0), ..Default::default() },
+        );
+
+        let resolved = config.resolved_for("ETH-USD");
+        assert_eq!(resolved.take_profit_percent, 3.0);
+        // Unset fields fall through to the base config untouched.
+        assert_eq!(resolved.stop_loss_percent, config.stop_loss_percent);
+
+        let unaffected = config.resolved_for("BTC-USD");
+        assert_eq!(unaffected.take_profit_percent, config.take_profit_percent);
+    }
+
+    #[test]
+    fn test_pair_overrides_entry_is_kept_on_config() {
+        // `from_layered` itself needs a real `Env`, which can't be constructed here,
+        // so this only checks that an inserted `pair_overrides` entry stays on the config.
+        let mut config = test_config();
+        config.pair_overrides.insert("ETH-USD".to_string(), PairOverride::default());
+        assert_eq!(config.pair_overrides.len(), 1);
+    }
+
+    #[test]
+    fn test_config_file_json_parses_base_and_pair_overrides() {
+        let raw = r#"{
+            "take_profit_percent": 2.5,
+            "pair_overrides": {
+                "ETH-USD": { "take_profit_percent": 4.0 }
+            }
+        }"#;
+        let file: ConfigFile = serde_json::from_str(raw).expect("valid JSON config file");
+        assert_eq!(file.base.take_profit_percent, Some(2.5));
+        assert_eq!(
+            file.pair_overrides.get("ETH-USD").and_then(|o| o.take_profit_percent),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn test_config_file_toml_parses_base_and_pair_overrides() {
+        let raw = r#"
+            take_profit_percent = 2.5
+
+            [pair_overrides."ETH-USD"]
+            take_profit_percent = 4.0
+        "#;
+        let file: ConfigFile = toml::from_str(raw).expect("valid TOML config file");
+        assert_eq!(file.base.take_profit_percent, Some(2.5));
+        assert_eq!(
+            file.pair_overrides.get("ETH-USD").and_then(|o| o.take_profit_percent),
+            Some(4.0)
+        );
+    }
+
+    fn leveraged_config() -> Config {
+        let mut config = test_config();
+        config.trading_mode = TradingMode::Isolated;
+        config.target_leverage = 10.0;
+        config.leverage_tiers = vec![
+            LeverageTier {
+                max_notional_usd: 10_000.0,
+                max_leverage: 10.0,
+                maintenance_margin_rate: 0.01,
+                maintenance_amount: 0.0,
+            },
+            LeverageTier {
+                max_notional_usd: 100_000.0,
+                max_leverage: 5.0,
+                maintenance_margin_rate: 0.025,
+                maintenance_amount: 50.0,
+            },
+        ];
+        config
+    }
+
+    #[test]
+    fn test_effective_trading_mode_falls_back_to_spot_without_tiers() {
+        let mut config = test_config();
+        config.trading_mode = TradingMode::Isolated;
+        assert_eq!(config.effective_trading_mode(), TradingMode::Spot);
+    }
+
+    #[test]
+    fn test_effective_trading_mode_honors_setting_with_tiers() {
+        let config = leveraged_config();
+        assert_eq!(config.effective_trading_mode(), TradingMode::Isolated);
+    }
+
+    #[test]
+    fn test_max_leverage_for_picks_the_covering_tier() {
+        let config = leveraged_config();
+        assert_eq!(config.max_leverage_for(5_000.0), 10.0);
+        assert_eq!(config.max_leverage_for(50_000.0), 5.0);
+        // Beyond every band: conservative ceiling from the top tier.
+        assert_eq!(config.max_leverage_for(1_000_000.0), 5.0);
+    }
+
+    #[test]
+    fn test_max_leverage_for_is_one_in_spot_mode() {
+        let config = test_config();
+        assert_eq!(config.max_leverage_for(5_000.0), 1.0);
+    }
+
+    #[test]
+    fn test_liquidation_price_long_below_entry_short_above() {
+        let config = leveraged_config();
+
+        let long_liq = config.liquidation_price(50_000.0, 10.0, PositionSide::Long, 5_000.0).expect("long liq");
+        assert!(long_liq < 50_000.0);
+
+        let short_liq = config.liquidation_price(50_000.0, 10.0, PositionSide::Short, 5_000.0).expect("short liq");
+        assert!(short_liq > 50_000.0);
+    }
+
+    #[test]
+    fn test_liquidation_price_none_in_spot_mode_or_at_1x() {
+        let config = leveraged_config();
+        assert_eq!(config.liquidation_price(50_000.0, 1.0, PositionSide::Long, 5_000.0), None);
+
+        let mut spot = test_config();
+        spot.leverage_tiers = vec![];
+        assert_eq!(spot.liquidation_price(50_000.0, 10.0, PositionSide::Long, 5_000.0), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_target_leverage_above_tier_ceiling() {
+        let mut config = leveraged_config();
+        config.target_leverage = 20.0; // Above both tiers' max_leverage
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_target_leverage_within_tier_ceiling() {
+        let config = leveraged_config();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_trading_mode_defaults_to_spot_for_unknown_values() {
+        assert_eq!(parse_trading_mode("isolated"), TradingMode::Isolated);
+        assert_eq!(parse_trading_mode("CROSS"), TradingMode::Cross);
+        assert_eq!(parse_trading_mode("margin"), TradingMode::Spot);
+    }
 }
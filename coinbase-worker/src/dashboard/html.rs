@@ -63,6 +63,26 @@ pub const TEMPLATE: &str = r#"
                         <div class="metric-label">Win Rate</div>
                         <div class="metric-value" id="winRate">--</div>
                     </div>
+                    <div class="metric">
+                        <div class="metric-label">Profit Factor</div>
+                        <div class="metric-value" id="profitFactor">--</div>
+                    </div>
+                    <div class="metric">
+                        <div class="metric-label">Max Drawdown</div>
+                        <div class="metric-value" id="maxDrawdown">--</div>
+                    </div>
+                    <div class="metric">
+                        <div class="metric-label">Avg Win</div>
+                        <div class="metric-value" id="avgWin">--</div>
+                    </div>
+                    <div class="metric">
+                        <div class="metric-label">Avg Loss</div>
+                        <div class="metric-value" id="avgLoss">--</div>
+                    </div>
+                    <div class="metric">
+                        <div class="metric-label">Sharpe</div>
+                        <div class="metric-value" id="sharpe">--</div>
+                    </div>
                 </div>
             </div>
 
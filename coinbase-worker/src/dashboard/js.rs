@@ -88,6 +88,25 @@ function updateStatus(status) {
     document.getElementById('totalTrades').textContent = status.total_trades || '0';
     document.getElementById('totalPnl').textContent = formatUSD(status.total_pnl);
     document.getElementById('totalPnl').className = 'card-value ' + getPnlClass(status.total_pnl);
+
+    document.getElementById('winRate').textContent = status.win_rate != null
+        ? Math.round(status.win_rate * 100) + '%'
+        : '--';
+    document.getElementById('profitFactor').textContent = status.profit_factor != null
+        ? status.profit_factor.toFixed(2)
+        : '--';
+    document.getElementById('maxDrawdown').textContent = status.max_drawdown != null
+        ? formatUSD(status.max_drawdown)
+        : '--';
+}
+
+function updatePerformance(perf) {
+    if (!perf || !perf.stats) return;
+
+    const s = perf.stats;
+    document.getElementById('avgWin').textContent = s.avg_win != null ? formatUSD(s.avg_win) : '--';
+    document.getElementById('avgLoss').textContent = s.avg_loss != null ? formatUSD(s.avg_loss) : '--';
+    document.getElementById('sharpe').textContent = s.sharpe != null ? s.sharpe.toFixed(2) : '--';
 }
 
 function updatePortfolio(debug) {
@@ -143,14 +162,6 @@ function updatePositionsTable(portfolio) {
             <td>${pos.hours_held || '--'}</td>
         </tr>`;
     }).join('');
-    
-    // Win rate calculation
-    const wins = portfolio.positions.filter(p => {
-        const pnl = p.pnl_percent || '';
-        return pnl.startsWith('+') && pnl !== '+0.00%';
-    }).length;
-    const total = portfolio.positions.length;
-    document.getElementById('winRate').textContent = total > 0 ? Math.round(wins / total * 100) + '%' : '--';
 }
 
 function updateScanGrid(scan) {
@@ -185,19 +196,21 @@ function updateScanGrid(scan) {
 // Main Update Function
 // ============================================================================
 async function updateDashboard() {
-    const [debug, portfolio, status, scan] = await Promise.all([
+    const [debug, portfolio, status, scan, performance] = await Promise.all([
         fetchJSON('/api/debug'),
         fetchJSON('/api/portfolio'),
         fetchJSON('/api/status'),
-        fetchJSON('/api/scan')
+        fetchJSON('/api/scan'),
+        fetchJSON('/api/performance')
     ]);
-    
+
     updateTimestamp();
     updateStatus(status);
     updatePortfolio(debug);
     updateRiskSizing(debug);
     updatePositionsTable(portfolio);
     updateScanGrid(scan);
+    updatePerformance(performance);
 }
 
 // ============================================================================
@@ -0,0 +1,172 @@
+//! Expectancy tracking from realized trade history
+//!
+//! Feeds `TradingStrategy::calculate_position_size` with a fractional-Kelly
+//! stake scaled by how well the strategy has actually performed on a given
+//! symbol, following freqtrade's Edge positioning approach.
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one closed trade, expressed in R-multiples (PnL / amount risked)
+/// so wins and losses are comparable across symbols and position sizes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeOutcome {
+    pub symbol: String,
+    pub r_multiple: f64,
+}
+
+/// Rolling record of realized trade outcomes, used to compute per-symbol
+/// expectancy and a fractional-Kelly sizing fraction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeHistory {
+    pub outcomes: Vec<TradeOutcome>,
+}
+
+/// Expectancy stats for a single symbol over its realized trade history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeStats {
+    pub win_rate: f64,
+    pub avg_win_r: f64,
+    pub avg_loss_r: f64,
+    pub expectancy: f64,
+    pub sample_size: usize,
+}
+
+impl TradeHistory {
+    /// Record a closed trade's outcome in R-multiples
+    pub fn record(&mut self, symbol: &str, r_multiple: f64) {
+        self.outcomes.push(TradeOutcome {
+            symbol: symbol.to_string(),
+            r_multiple,
+        });
+    }
+
+    /// Compute expectancy stats for `symbol`, or `None` if there aren't at
+    /// least `min_samples` closed trades for it yet.
+    pub fn edge_for(&self, symbol: &str, min_samples: usize) -> Option<EdgeStats> {
+        let samples: Vec<f64> = self
+            .outcomes
+            .iter()
+            .filter(|o| o.symbol == symbol)
+            .map(|o| o.r_multiple)
+            .collect();
+
+        if samples.len() < min_samples.max(1) {
+            return None;
+        }
+
+        let wins: Vec<f64> = samples.iter().copied().filter(|r| *r > 0.0).collect();
+        let losses: Vec<f64> = samples.iter().copied().filter(|r| *r <= 0.0).collect();
+
+        let win_rate = wins.len() as f64 / samples.len() as f64;
+        let avg_win_r = if wins.is_empty() {
+            0.0
+        } else {
+            wins.iter().sum::<f64>() / wins.len() as f64
+        };
+        let avg_loss_r = if losses.is_empty() {
+            0.0
+        } else {
+            losses.iter().map(|r| r.abs()).sum::<f64>() / losses.len() as f64
+        };
+
+        let expectancy = win_rate * avg_win_r - (1.0 - win_rate) * avg_loss_r;
+
+        Some(EdgeStats {
+            win_rate,
+            avg_win_r,
+            avg_loss_r,
+            expectancy,
+            sample_size: samples.len(),
+        })
+    }
+}
+
+impl EdgeStats {
+    /// Fractional-Kelly stake: `f = clamp(win_rate - (1 - win_rate) / reward_risk, 0, kelly_cap)`.
+    /// Falls back to 0 (no edge) when there's no loss sample to weigh the reward against.
+    pub fn kelly_fraction(&self, kelly_cap: f64) -> f64 {
+        if self.avg_loss_r <= 0.0 {
+            return 0.0;
+        }
+        let reward_risk = self.avg_win_r / self.avg_loss_r;
+        if reward_risk <= 0.0 {
+            return 0.0;
+        }
+        (self.win_rate - (1.0 - self.win_rate) / reward_risk).clamp(0.0, kelly_cap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_for_insufficient_samples() {
+        let mut history = TradeHistory::default();
+        history.record("BTC-USD", 1.0);
+        history.record("BTC-USD", -1.0);
+
+        assert!(history.edge_for("BTC-USD", 5).is_none());
+        assert!(history.edge_for("ETH-USD", 1).is_none());
+    }
+
+    #[test]
+    fn test_edge_for_computes_expectancy() {
+        let mut history = TradeHistory::default();
+        // 3 wins of +2R, 2 losses of -1R: win_rate 0.6, avg_win 2, avg_loss 1
+        // expectancy = 0.6*2 - 0.4*1 = 1.2 - 0.4 = 0.8
+        for _ in 0..3 {
+            history.record("BTC-USD", 2.0);
+        }
+        for _ in 0..2 {
+            history.record("BTC-USD", -1.0);
+        }
+
+        let edge = history.edge_for("BTC-USD", 5).expect("enough samples");
+        assert_eq!(edge.sample_size, 5);
+        assert!((edge.win_rate - 0.6).abs() < 0.0001);
+        assert!((edge.avg_win_r - 2.0).abs() < 0.0001);
+        assert!((edge.avg_loss_r - 1.0).abs() < 0.0001);
+        assert!((edge.expectancy - 0.8).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_edge_for_negative_expectancy() {
+        let mut history = TradeHistory::default();
+        // 1 win of +1R, 4 losses of -1R: expectancy = 0.2*1 - 0.8*1 = -0.6
+        history.record("ETH-USD", 1.0);
+        for _ in 0..4 {
+            history.record("ETH-USD", -1.0);
+        }
+
+        let edge = history.edge_for("ETH-USD", 5).expect("enough samples");
+        assert!(edge.expectancy < 0.0);
+    }
+
+    #[test]
+    fn test_kelly_fraction_clamped_to_cap() {
+        // Very strong edge: win_rate 0.9, reward_risk 3 -> f = 0.9 - 0.1/3 ≈ 0.867
+        let edge = EdgeStats {
+            win_rate: 0.9,
+            avg_win_r: 3.0,
+            avg_loss_r: 1.0,
+            expectancy: 2.6,
+            sample_size: 10,
+        };
+        assert!((edge.kelly_fraction(1.0) - 0.8667).abs() < 0.001);
+        // Capped at 0.25
+        assert_eq!(edge.kelly_fraction(0.25), 0.25);
+    }
+
+    #[test]
+    fn test_kelly_fraction_zero_without_losses() {
+        let edge = EdgeStats {
+            win_rate: 1.0,
+            avg_win_r: 2.0,
+            avg_loss_r: 0.0,
+            expectancy: 2.0,
+            sample_size: 5,
+        };
+        assert_eq!(edge.kelly_fraction(0.5), 0.0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Exchange abstraction - lets `TradingEngine` run against any venue
+//!
+//! `TradingEngine` previously called `CoinbaseClient` directly, so adding a second
+//! exchange meant touching engine logic. Modeled on zenbot's plugin-exchange
+//! approach: the engine depends only on the `Exchange` trait, and `CoinbaseClient`
+//! is one implementation among potentially several (Alpaca, Kraken, ...).
+
+use crate::client::{CoinbaseClient, Candle, OrderResponse, OrderStatus, ProductStats};
+use crate::error::Result;
+use crate::pairlist::PairlistCandidate;
+use crate::symbol_filters::SymbolFilters;
+use rust_decimal::Decimal;
+
+/// A single currency's available cash balance, normalized across exchanges.
+/// Coinbase's nested `AccountsResponse`/`Account`/`Balance` shape collapses to this
+/// at the `Exchange` boundary so engine code never sees exchange-specific response types.
+#[derive(Debug, Clone)]
+pub struct CashBalance {
+    pub currency: String,
+    pub available: f64,
+}
+
+/// Trading venue the engine runs against. Futures aren't `Send` (Cloudflare Workers'
+/// `worker`/`reqwest` types aren't either), so this uses `async_trait(?Send)` rather
+/// than the default `Send`-bound expansion.
+#[async_trait::async_trait(?Send)]
+pub trait Exchange {
+    /// Current price for a symbol
+    async fn get_price(&self, symbol: &str) -> Result<f64>;
+    /// 24h price/volume/trend stats for a symbol
+    async fn get_product_stats(&self, symbol: &str) -> Result<ProductStats>;
+    /// Available cash balances across all currencies held
+    async fn get_accounts(&self) -> Result<Vec<CashBalance>>;
+    /// Place a market buy sized in quote currency (USD)
+    async fn market_buy(&self, symbol: &str, usd_amount: f64) -> Result<OrderResponse>;
+    /// Place a market sell sized in base currency
+    async fn market_sell(&self, symbol: &str, quantity: f64) -> Result<OrderResponse>;
+    /// Fetch the current fill status of a previously-placed order
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus>;
+    /// Cancel a still-open order
+    async fn cancel_order(&self, order_id: &str) -> Result<()>;
+    /// Recent hourly candles for a symbol, for support/resistance detection.
+    /// Returns an empty vec on fetch/parse failure rather than erroring.
+    async fn get_recent_candles(&self, symbol: &str, limit: u32) -> Vec<Candle>;
+    /// All tradable symbols with the stats a pairlist stage ranks/filters on (see
+    /// `crate::pairlist::Pairlist`). Unlike the other methods, this isn't scoped to a
+    /// single symbol - it's the dynamic-pairlist entry point.
+    async fn get_tradable_products(&self) -> Result<Vec<PairlistCandidate>>;
+    /// This symbol's tick/lot-size trading rules, fetched fresh each call. Callers
+    /// that want caching (e.g. `TradingEngine`, via `TradingStateData::symbol_filters`)
+    /// are responsible for it - this just reports what the exchange says right now.
+    async fn get_symbol_filters(&self, symbol: &str) -> Result<SymbolFilters>;
+}
+
+#[async_trait::async_trait(?Send)]
+impl Exchange for CoinbaseClient {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        CoinbaseClient::get_price(self, symbol).await
+    }
+
+    async fn get_product_stats(&self, symbol: &str) -> Result<ProductStats> {
+        CoinbaseClient::get_product_stats(self, symbol).await
+    }
+
+    async fn get_accounts(&self) -> Result<Vec<CashBalance>> {
+        let response = CoinbaseClient::get_accounts(self).await?;
+        Ok(response
+            .accounts
+            .into_iter()
+            .filter_map(|a| {
+                let available = a.available_balance.value.parse().ok()?;
+                Some(CashBalance {
+                    currency: a.currency,
+                    available,
+                })
+            })
+            .collect())
+    }
+
+    async fn market_buy(&self, symbol: &str, usd_amount: f64) -> Result<OrderResponse> {
+        CoinbaseClient::market_buy(self, symbol, usd_amount).await
+    }
+
+    async fn market_sell(&self, symbol: &str, quantity: f64) -> Result<OrderResponse> {
+        CoinbaseClient::market_sell(self, symbol, quantity).await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<OrderStatus> {
+        CoinbaseClient::get_order(self, order_id).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        CoinbaseClient::cancel_order(self, order_id).await
+    }
+
+    async fn get_recent_candles(&self, symbol: &str, limit: u32) -> Vec<Candle> {
+        CoinbaseClient::get_recent_candles(self, symbol, limit).await
+    }
+
+    async fn get_tradable_products(&self) -> Result<Vec<PairlistCandidate>> {
+        let products = CoinbaseClient::list_products_public(self).await?;
+        Ok(products
+            .into_iter()
+            .filter_map(|p| {
+                let price: f64 = p.price.parse().ok()?;
+                if price <= 0.0 {
+                    return None;
+                }
+                let volume_24h: f64 = p.volume_24h.parse().unwrap_or(0.0);
+                let quote_increment: f64 = p.quote_increment.parse().unwrap_or(0.0);
+                Some(PairlistCandidate {
+                    symbol: p.product_id,
+                    price,
+                    volume_24h,
+                    spread_percent: quote_increment / price * 100.0,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        let product = CoinbaseClient::get_product(self, symbol).await?;
+        Ok(SymbolFilters {
+            price_tick: product.quote_increment_decimal()?,
+            qty_step: product.base_increment_decimal()?,
+            min_qty: product.base_min_size_decimal()?,
+            // Coinbase's product schema has no minimum-notional field (unlike the
+            // LOT_SIZE/MIN_NOTIONAL venues `SymbolFilters` was modeled on) - only
+            // `min_qty` and the tick/step grid are enforced for a live product.
+            min_notional: Decimal::ZERO,
+        })
+    }
+}
@@ -0,0 +1,171 @@
+//! Multi-currency FX normalization and locale-aware money formatting
+//!
+//! `/api/portfolio` used to hardcode every cash balance to `USD`/`USDC` and format
+//! every figure with a bare `$`, so a non-USD account balance (EUR, a BTC-quoted
+//! holding, etc.) was silently dropped from the total and every user saw dollar signs
+//! regardless of `Config::base_currency`. `ExchangeRates` converts an amount between
+//! any two currencies it covers via a single fetched rate table
+//! (`CoinbaseClient::get_exchange_rates`), and `format_currency` renders the result
+//! with the right symbol, decimal precision, and thousands separators for the target
+//! currency instead of a bare `$`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Spot conversion rates, all quoted against `base` (e.g. `rates["EUR"] == 0.95` means
+/// `1 base == 0.95 EUR`). Covers fiat and crypto currencies alike - Coinbase's
+/// `exchange-rates` endpoint returns both from the same call. `Serialize`/`Deserialize`
+/// let this be cached (see `crate::price_cache`) instead of refetched on every call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    /// Convert `amount` from currency `from` to currency `to`, routing through `base`
+    /// (the currency the rate table is quoted against) when neither side already is
+    /// it. A currency missing from `rates` is treated as 1:1 with `base` rather than
+    /// failing the whole conversion, since a quote gap on one minor currency shouldn't
+    /// make the rest of a multi-asset portfolio unreportable.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> f64 {
+        if from == to {
+            return amount;
+        }
+        let in_base = if from == self.base { amount } else { amount / self.rates.get(from).copied().unwrap_or(1.0) };
+        if to == self.base {
+            in_base
+        } else {
+            in_base * self.rates.get(to).copied().unwrap_or(1.0)
+        }
+    }
+}
+
+/// Symbol, decimal precision, and symbol placement for a currency's display format.
+struct CurrencyFormat {
+    symbol: &'static str,
+    decimals: usize,
+    /// `true` puts `symbol` after the number with a space (e.g. `"1.23 BTC"`) instead
+    /// of immediately before it (e.g. `"$1.23"`) - matches how each currency is
+    /// conventionally written rather than forcing every one through the same shape.
+    symbol_suffix: bool,
+}
+
+fn currency_format(currency: &str) -> CurrencyFormat {
+    match currency {
+        "USD" | "USDC" => CurrencyFormat { symbol: "$", decimals: 2, symbol_suffix: false },
+        "EUR" => CurrencyFormat { symbol: "€", decimals: 2, symbol_suffix: false },
+        "GBP" => CurrencyFormat { symbol: "£", decimals: 2, symbol_suffix: false },
+        "JPY" => CurrencyFormat { symbol: "¥", decimals: 0, symbol_suffix: false },
+        "BTC" => CurrencyFormat { symbol: "BTC", decimals: 8, symbol_suffix: true },
+        "ETH" => CurrencyFormat { symbol: "ETH", decimals: 6, symbol_suffix: true },
+        other => CurrencyFormat { symbol: other, decimals: 2, symbol_suffix: true },
+    }
+}
+
+/// Insert `,` thousands separators into the integer part of a fixed-decimal number
+/// string (e.g. `"1234567.89"` -> `"1,234,567.89"`). No locale crate is vendored here,
+/// so this is the plain digit-grouping every reporting currency below actually needs.
+fn with_thousands_separators(formatted: &str) -> String {
+    let (sign, rest) = formatted.strip_prefix('-').map_or(("", formatted), |r| ("-", r));
+    let (int_part, frac_part) = rest.split_once('.').unwrap_or((rest, ""));
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, digit) in int_part.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let grouped: String = grouped.chars().rev().collect();
+
+    if frac_part.is_empty() {
+        format!("{sign}{grouped}")
+    } else {
+        format!("{sign}{grouped}.{frac_part}")
+    }
+}
+
+/// Render `amount` (already in `currency`) with that currency's symbol, decimal
+/// precision, and thousands separators - e.g. `format_currency(1234.5, "USD")` ->
+/// `"$1,234.50"`, `format_currency(0.001, "BTC")` -> `"0.00100000 BTC"`.
+pub fn format_currency(amount: f64, currency: &str) -> String {
+    let format = currency_format(currency);
+    let magnitude = with_thousands_separators(&format!("{:.*}", format.decimals, amount.abs()));
+    let sign = if amount < 0.0 { "-" } else { "" };
+
+    if format.symbol_suffix {
+        format!("{sign}{magnitude} {}", format.symbol)
+    } else {
+        format!("{sign}{}{magnitude}", format.symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> ExchangeRates {
+        ExchangeRates {
+            base: "USD".to_string(),
+            rates: HashMap::from([("EUR".to_string(), 0.90), ("BTC".to_string(), 0.00001)]),
+        }
+    }
+
+    #[test]
+    fn test_convert_same_currency_is_identity() {
+        assert_eq!(rates().convert(100.0, "USD", "USD"), 100.0);
+    }
+
+    #[test]
+    fn test_convert_from_base() {
+        assert_eq!(rates().convert(100.0, "USD", "EUR"), 90.0);
+    }
+
+    #[test]
+    fn test_convert_to_base() {
+        assert!((rates().convert(90.0, "EUR", "USD") - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_convert_between_two_non_base_currencies_routes_through_base() {
+        // 1 BTC = 1 / 0.00001 = 100,000 USD here, then 100,000 USD * 0.90 = 90,000 EUR
+        let converted = rates().convert(1.0, "BTC", "EUR");
+        assert!((converted - 90_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_convert_missing_currency_falls_back_to_1_to_1_with_base() {
+        assert_eq!(rates().convert(50.0, "USD", "GBP"), 50.0);
+    }
+
+    #[test]
+    fn test_format_currency_usd_adds_thousands_separators() {
+        assert_eq!(format_currency(1234567.891, "USD"), "$1,234,567.89");
+    }
+
+    #[test]
+    fn test_format_currency_negative_amount() {
+        assert_eq!(format_currency(-42.5, "USD"), "-$42.50");
+    }
+
+    #[test]
+    fn test_format_currency_eur_symbol() {
+        assert_eq!(format_currency(99.9, "EUR"), "€99.90");
+    }
+
+    #[test]
+    fn test_format_currency_btc_uses_suffix_and_high_precision() {
+        assert_eq!(format_currency(0.001, "BTC"), "0.00100000 BTC");
+    }
+
+    #[test]
+    fn test_format_currency_jpy_has_no_decimals() {
+        assert_eq!(format_currency(1500.0, "JPY"), "¥1,500");
+    }
+
+    #[test]
+    fn test_format_currency_unknown_currency_falls_back_to_code_suffix() {
+        assert_eq!(format_currency(12.3, "XYZ"), "12.30 XYZ");
+    }
+}
@@ -0,0 +1,246 @@
+//! D1-backed trade history, open-position mirror, and cycle audit log
+//!
+//! `TradingStateData` keeps a capped `closed_trades` ring buffer (see
+//! `MAX_CLOSED_TRADES` in `types.rs`) inside the single KV `STATE` blob, so history
+//! older than the last 500 trades is gone and there is no way to query it - win rate,
+//! per-symbol performance, and cumulative P&L over time only exist as running sums.
+//! This module gives that history a durable, queryable home in D1: a `closed_trades`
+//! table (one row per full or partial exit, unbounded), a `positions` table mirroring
+//! the live open-position set for external visibility, and a `cycles` table recording
+//! a summary of every trading cycle run.
+//!
+//! Follows `candle_store.rs`'s pattern of plain functions that take a `&D1Database`
+//! explicitly rather than reaching into `Env` themselves, for the same reason: neither
+//! `TradingEngine` nor `CoinbaseClient` has a D1 handle wired in, and threading one
+//! through would ripple across every call site. Callers that already hold an `Env`
+//! (the scheduled handler, the `/api/history` and `/api/stats` routes) call these
+//! directly after a cycle completes.
+
+use crate::error::{Result, TradingError};
+use crate::types::{ClosedTrade, PositionSide, PositionStore, TradingCycleResult};
+use worker::D1Database;
+
+/// D1 row shape for `closed_trades`, matching `record_closed_trade`'s insert columns
+#[derive(Debug, serde::Deserialize)]
+struct ClosedTradeRow {
+    symbol: String,
+    side: String,
+    entry_price: f64,
+    exit_price: f64,
+    quantity: f64,
+    pnl: f64,
+    closed_at: String,
+    reason: Option<String>,
+    opened_at: Option<String>,
+}
+
+impl From<ClosedTradeRow> for ClosedTrade {
+    fn from(row: ClosedTradeRow) -> Self {
+        Self {
+            symbol: row.symbol,
+            side: if row.side == "Short" { PositionSide::Short } else { PositionSide::Long },
+            entry_price: row.entry_price,
+            exit_price: row.exit_price,
+            quantity: row.quantity,
+            pnl: row.pnl,
+            closed_at: row.closed_at,
+            reason: row.reason,
+            opened_at: row.opened_at,
+        }
+    }
+}
+
+/// Insert one realized close (full exit, partial take-profit, or rollover) into the
+/// durable `closed_trades` history. Called once per `TradingStateData::record_closed_trade`.
+pub async fn record_closed_trade(d1: &D1Database, trade: &ClosedTrade) -> Result<()> {
+    let statement = d1
+        .prepare(
+            "INSERT INTO closed_trades
+                (symbol, side, entry_price, exit_price, quantity, pnl, closed_at, reason, opened_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&[
+            trade.symbol.clone().into(),
+            format!("{:?}", trade.side).into(),
+            trade.entry_price.into(),
+            trade.exit_price.into(),
+            trade.quantity.into(),
+            trade.pnl.into(),
+            trade.closed_at.clone().into(),
+            trade.reason.clone().into(),
+            trade.opened_at.clone().into(),
+        ])
+        .map_err(|e| TradingError::Storage(format!("Failed to bind closed trade insert: {e}")))?;
+
+    statement
+        .run()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to insert closed trade: {e}")))?;
+    Ok(())
+}
+
+/// Replace the `positions` table's contents with `positions`, mirroring the live
+/// open-position set for external querying. A full resync rather than an incremental
+/// diff - simpler to reason about, and cheap at the handful of positions this bot
+/// ever holds at once.
+pub async fn sync_open_positions(d1: &D1Database, positions: &PositionStore) -> Result<()> {
+    d1.prepare("DELETE FROM positions")
+        .run()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to clear positions: {e}")))?;
+
+    for position in positions.iter() {
+        let statement = d1
+            .prepare(
+                "INSERT INTO positions
+                    (symbol, side, quantity, entry_price, entry_time, stop_loss_price, take_profit_price)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&[
+                position.symbol.clone().into(),
+                format!("{:?}", position.side).into(),
+                position.quantity.into(),
+                position.entry_price.into(),
+                position.entry_time.clone().into(),
+                position.stop_loss_price.into(),
+                position.take_profit_price.into(),
+            ])
+            .map_err(|e| TradingError::Storage(format!("Failed to bind position upsert: {e}")))?;
+
+        statement
+            .run()
+            .await
+            .map_err(|e| TradingError::Storage(format!("Failed to upsert position: {e}")))?;
+    }
+
+    Ok(())
+}
+
+/// Record a summary row for one completed trading cycle, for `cycles` audit history.
+pub async fn record_cycle(d1: &D1Database, ran_at: &str, result: &TradingCycleResult) -> Result<()> {
+    let statement = d1
+        .prepare(
+            "INSERT INTO cycles (ran_at, success, positions_opened, positions_closed, cycle_time_ms, message)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&[
+            ran_at.into(),
+            result.success.into(),
+            (result.positions_opened as i64).into(),
+            (result.positions_closed as i64).into(),
+            (result.cycle_time_ms as i64).into(),
+            result.message.clone().into(),
+        ])
+        .map_err(|e| TradingError::Storage(format!("Failed to bind cycle record: {e}")))?;
+
+    statement
+        .run()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to record cycle: {e}")))?;
+    Ok(())
+}
+
+/// Closed trades, most recent first, optionally filtered to one `symbol`. Backs
+/// `GET /api/history`.
+pub async fn get_trade_history(d1: &D1Database, symbol: Option<&str>, limit: u32) -> Result<Vec<ClosedTrade>> {
+    let statement = match symbol {
+        Some(symbol) => d1
+            .prepare("SELECT * FROM closed_trades WHERE symbol = ? ORDER BY id DESC LIMIT ?")
+            .bind(&[symbol.into(), limit.into()]),
+        None => d1
+            .prepare("SELECT * FROM closed_trades ORDER BY id DESC LIMIT ?")
+            .bind(&[limit.into()]),
+    }
+    .map_err(|e| TradingError::Storage(format!("Failed to bind history query: {e}")))?;
+
+    let result = statement
+        .all()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read trade history: {e}")))?;
+    let rows: Vec<ClosedTradeRow> = result
+        .results()
+        .map_err(|e| TradingError::Storage(format!("Failed to decode trade history: {e}")))?;
+    Ok(rows.into_iter().map(ClosedTrade::from).collect())
+}
+
+/// Closed-trade analytics computed entirely in SQL, for `GET /api/stats`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TradeStats {
+    pub total_trades: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub total_pnl: f64,
+    pub avg_pnl: f64,
+    /// `None` when no closed trade has an `opened_at` recorded yet.
+    pub avg_hold_hours: Option<f64>,
+    pub best_symbol: Option<String>,
+    pub worst_symbol: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SummaryRow {
+    total_trades: i64,
+    wins: i64,
+    losses: i64,
+    total_pnl: Option<f64>,
+    avg_pnl: Option<f64>,
+    avg_hold_hours: Option<f64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SymbolPnlRow {
+    symbol: String,
+}
+
+pub async fn get_stats(d1: &D1Database) -> Result<TradeStats> {
+    let summary = d1
+        .prepare(
+            "SELECT
+                COUNT(*) AS total_trades,
+                SUM(CASE WHEN pnl > 0 THEN 1 ELSE 0 END) AS wins,
+                SUM(CASE WHEN pnl <= 0 THEN 1 ELSE 0 END) AS losses,
+                SUM(pnl) AS total_pnl,
+                AVG(pnl) AS avg_pnl,
+                AVG((julianday(closed_at) - julianday(opened_at)) * 24) AS avg_hold_hours
+             FROM closed_trades",
+        )
+        .first::<SummaryRow>(None)
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to compute trade stats: {e}")))?;
+
+    let best = d1
+        .prepare("SELECT symbol FROM closed_trades GROUP BY symbol ORDER BY SUM(pnl) DESC LIMIT 1")
+        .first::<SymbolPnlRow>(None)
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to compute best symbol: {e}")))?;
+
+    let worst = d1
+        .prepare("SELECT symbol FROM closed_trades GROUP BY symbol ORDER BY SUM(pnl) ASC LIMIT 1")
+        .first::<SymbolPnlRow>(None)
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to compute worst symbol: {e}")))?;
+
+    let Some(summary) = summary else {
+        return Ok(TradeStats {
+            total_trades: 0,
+            wins: 0,
+            losses: 0,
+            total_pnl: 0.0,
+            avg_pnl: 0.0,
+            avg_hold_hours: None,
+            best_symbol: None,
+            worst_symbol: None,
+        });
+    };
+
+    Ok(TradeStats {
+        total_trades: summary.total_trades,
+        wins: summary.wins,
+        losses: summary.losses,
+        total_pnl: summary.total_pnl.unwrap_or(0.0),
+        avg_pnl: summary.avg_pnl.unwrap_or(0.0),
+        avg_hold_hours: summary.avg_hold_hours,
+        best_symbol: best.map(|r| r.symbol),
+        worst_symbol: worst.map(|r| r.symbol),
+    })
+}
@@ -0,0 +1,365 @@
+//! Hyperparameter optimization over historical candles
+//!
+//! Sweeps a handful of tunable `Config` fields against stored OHLCV data, replaying
+//! each candidate through `Backtester::run` (the same `analyze` / `should_enter` /
+//! `check_exit` pipeline live trading uses) and scoring the result with an
+//! `Objective`. Starts as random sampling over the declared `ParamRange`s and keeps
+//! the top-K candidates, echoing freqtrade's `--print-all` / best-params-as-config
+//! workflow.
+
+use crate::backtest::{Backtester, BacktestReport, Bar};
+use crate::config::Config;
+
+/// One `Config` field hyperopt can sweep, paired with the env var `Config::from_env`
+/// reads it from so `HyperoptReport::best_env_block` can be pasted straight back
+/// into a Worker's environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HyperoptField {
+    BaseEntryThreshold,
+    AtrSlMultiplier,
+    AtrTpMultiplier,
+    MinSlPercent,
+    MaxSlPercent,
+    TrailingStopPercent,
+    MaxRiskPerTradePercent,
+}
+
+impl HyperoptField {
+    fn env_var(self) -> &'static str {
+        match self {
+            Self::BaseEntryThreshold => "BASE_ENTRY_THRESHOLD",
+            Self::AtrSlMultiplier => "ATR_SL_MULTIPLIER",
+            Self::AtrTpMultiplier => "ATR_TP_MULTIPLIER",
+            Self::MinSlPercent => "MIN_SL_PERCENT",
+            Self::MaxSlPercent => "MAX_SL_PERCENT",
+            Self::TrailingStopPercent => "TRAILING_STOP_PERCENT",
+            Self::MaxRiskPerTradePercent => "MAX_RISK_PER_TRADE_PERCENT",
+        }
+    }
+
+    fn apply(self, config: &mut Config, value: f64) {
+        match self {
+            Self::BaseEntryThreshold => config.base_entry_threshold = value,
+            Self::AtrSlMultiplier => config.atr_sl_multiplier = value,
+            Self::AtrTpMultiplier => config.atr_tp_multiplier = value,
+            Self::MinSlPercent => config.min_sl_percent = value,
+            Self::MaxSlPercent => config.max_sl_percent = value,
+            Self::TrailingStopPercent => config.trailing_stop_percent = value,
+            Self::MaxRiskPerTradePercent => config.max_risk_per_trade_percent = value,
+        }
+    }
+}
+
+/// Candidate values hyperopt may assign to a single `HyperoptField`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamRange {
+    /// Inclusive `min..=max` stepped by `step`. A non-positive `step` or `min > max`
+    /// collapses to the single value `min`.
+    Stepped { min: f64, max: f64, step: f64 },
+    /// Explicit candidate values, tried as given.
+    Discrete(Vec<f64>),
+}
+
+impl ParamRange {
+    fn values(&self) -> Vec<f64> {
+        match self {
+            Self::Discrete(values) => values.clone(),
+            Self::Stepped { min, max, step } => {
+                if *step <= 0.0 || min > max {
+                    return vec![*min];
+                }
+                let mut values = Vec::new();
+                let mut value = *min;
+                while value <= *max + f64::EPSILON {
+                    values.push(value);
+                    value += step;
+                }
+                values
+            }
+        }
+    }
+
+    /// One value chosen uniformly at random from `values()`. Derives randomness from
+    /// a fresh UUID's low bits rather than pulling in `rand` for one draw - the same
+    /// trick `CoinbaseClient::backoff_delay` uses for retry jitter.
+    fn sample(&self) -> f64 {
+        let values = self.values();
+        let idx = (uuid::Uuid::new_v4().as_u128() as usize) % values.len();
+        values[idx]
+    }
+}
+
+/// What `Hyperopt::run` maximizes across epochs, mirroring freqtrade's loss functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Raw `BacktestReport::total_profit_percent`.
+    TotalProfit,
+    /// `BacktestReport::sharpe`; epochs with too few trades to compute one sort last.
+    Sharpe,
+    /// Total profit divided by max drawdown (Calmar-style) - rewards return per unit
+    /// of pain, not just raw profit. Drawdowns under 1% are floored to 1% so a config
+    /// that got lucky and never drew down doesn't divide by (near) zero.
+    DrawdownAdjustedReturn,
+}
+
+impl Objective {
+    fn score(self, report: &BacktestReport) -> f64 {
+        match self {
+            Self::TotalProfit => report.total_profit_percent,
+            Self::Sharpe => report.sharpe.unwrap_or(f64::MIN),
+            Self::DrawdownAdjustedReturn => {
+                report.total_profit_percent / report.max_drawdown_percent.max(1.0)
+            }
+        }
+    }
+}
+
+/// One hyperopt trial: the sampled field values, the resulting backtest, and its
+/// `Objective` score.
+#[derive(Debug, Clone)]
+pub struct EpochResult {
+    pub epoch: usize,
+    pub params: Vec<(HyperoptField, f64)>,
+    pub report: BacktestReport,
+    pub score: f64,
+}
+
+/// Full sweep output: every epoch (for a freqtrade-style `--print-all` table) plus
+/// the top-K highest-scoring ones.
+#[derive(Debug, Clone)]
+pub struct HyperoptReport {
+    /// Every epoch run, in the order they completed.
+    pub epochs: Vec<EpochResult>,
+    /// The `top_k` highest-scoring epochs, best first.
+    pub top: Vec<EpochResult>,
+}
+
+impl HyperoptReport {
+    /// The best epoch's sampled fields as a `KEY=value` block, one assignment per
+    /// line, ready to paste back into the Worker's environment. Empty if `top` is.
+    pub fn best_env_block(&self) -> String {
+        self.top.first().map_or_else(String::new, |best| {
+            best.params
+                .iter()
+                .map(|(field, value)| format!("{}={value}", field.env_var()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+}
+
+/// Sweeps tunable `Config` fields against historical candles via `Backtester::run`.
+pub struct Hyperopt;
+
+impl Hyperopt {
+    /// Run `epochs` random-sampled trials over `space`, replaying `candles` through
+    /// `Backtester::run` for each candidate `Config` (cloned from `base_config`) and
+    /// keeping the `top_k` highest-scoring ones under `objective`.
+    pub fn run(
+        base_config: &Config,
+        space: &[(HyperoptField, ParamRange)],
+        candles: &[Bar],
+        objective: Objective,
+        epochs: usize,
+        top_k: usize,
+    ) -> HyperoptReport {
+        let mut results = Vec::with_capacity(epochs);
+
+        for epoch in 0..epochs {
+            let mut candidate = base_config.clone();
+            let params: Vec<(HyperoptField, f64)> = space
+                .iter()
+                .map(|(field, range)| {
+                    let value = range.sample();
+                    field.apply(&mut candidate, value);
+                    (*field, value)
+                })
+                .collect();
+
+            let report = Backtester::run(candles, &candidate);
+            let score = objective.score(&report);
+            results.push(EpochResult { epoch, params, report, score });
+        }
+
+        let mut top = results.clone();
+        top.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        top.truncate(top_k);
+
+        HyperoptReport { epochs: results, top }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TradingMode;
+    use crate::lots::CostBasisMethod;
+    use std::collections::HashMap;
+
+    fn test_config() -> Config {
+        Config {
+            environment: "test".to_string(),
+            log_level: "debug".to_string(),
+            take_profit_percent: 1.5,
+            stop_loss_percent: 1.0,
+            trailing_stop_percent: 0.5,
+            atr_sl_multiplier: 1.0,
+            atr_tp_multiplier: 2.0,
+            min_sl_percent: 0.5,
+            max_sl_percent: 5.0,
+            min_tp_percent: 1.0,
+            max_tp_percent: 10.0,
+            atr_trail_multiplier: 1.5,
+            max_risk_per_trade_percent: 2.0,
+            max_portfolio_per_position: 25.0,
+            min_position_usd: 10.0,
+            cash_reserve_percent: 15.0,
+            max_total_positions: 8,
+            base_fee_percent: 0.60,
+            base_entry_threshold: 60.0,
+            min_entry_threshold: 40.0,
+            max_entry_threshold: 85.0,
+            cycle_interval_seconds: 15,
+            symbols: vec!["BTC-USD".to_string()],
+            daily_trade_limit: 30,
+            max_consecutive_errors: 5,
+            enable_trend_filter: false,
+            enable_volume_filter: false,
+            enable_market_regime_filter: false,
+            min_volume_usd: 1_000_000.0,
+            max_position_age_hours: 48.0,
+            enable_shorts: false,
+            enable_sr_filter: false,
+            sr_pivot_window: 2,
+            sr_tolerance_percent: 0.5,
+            sr_min_cluster_volume: 0.0,
+            sr_proximity_percent: 1.0,
+            dca_step_percent: 2.0,
+            max_entry_adjustments: 0,
+            enable_edge_sizing: false,
+            edge_min_trades: 20,
+            edge_kelly_cap: 0.5,
+            kelly_win_probability_estimate: 0.5,
+            tp_levels: vec![],
+            move_stop_to_breakeven_after: None,
+            minimal_roi: vec![],
+            trading_mode: TradingMode::Spot,
+            target_leverage: 1.0,
+            leverage_tiers: vec![],
+            funding_rate_per_hour: 0.0,
+            max_funding_drag_fraction: None,
+            unfilled_order_timeout_seconds: 30,
+            max_order_retries: 1,
+            enable_dynamic_pairlist: false,
+            pairlist_top_n: 10,
+            pairlist_min_volume_usd: 1_000_000.0,
+            pairlist_min_price: 0.01,
+            pairlist_max_price: 100_000.0,
+            pairlist_max_spread_percent: 1.0,
+            pairlist_blacklist: vec![],
+            enable_cooldown_protection: false,
+            cooldown_minutes: 60,
+            enable_stoploss_guard: false,
+            stoploss_guard_trades: 3,
+            stoploss_guard_lookback_minutes: 60,
+            stoploss_guard_stop_minutes: 120,
+            enable_drawdown_protection: false,
+            max_drawdown_protection_percent: 10.0,
+            drawdown_protection_lookback_minutes: 1440,
+            cost_basis_method: CostBasisMethod::Fifo,
+            max_liquidation_slippage_percent: 5.0,
+            base_currency: "USD".to_string(),
+            tier_hysteresis_percent: 5.0,
+            tier_transition_cycles: 5,
+            pair_overrides: HashMap::new(),
+        }
+    }
+
+    fn flat_bars(n: usize, start_ts: i64, price: f64) -> Vec<Bar> {
+        (0..n)
+            .map(|i| Bar {
+                timestamp: start_ts + i as i64 * 3600,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: 100.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_param_range_stepped_enumerates_inclusive_values() {
+        let range = ParamRange::Stepped { min: 1.0, max: 2.0, step: 0.5 };
+        assert_eq!(range.values(), vec![1.0, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn test_param_range_stepped_non_positive_step_collapses_to_min() {
+        let range = ParamRange::Stepped { min: 1.0, max: 2.0, step: 0.0 };
+        assert_eq!(range.values(), vec![1.0]);
+    }
+
+    #[test]
+    fn test_param_range_discrete_returns_values_as_given() {
+        let range = ParamRange::Discrete(vec![3.0, 1.0, 2.0]);
+        assert_eq!(range.values(), vec![3.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_param_range_sample_always_within_values() {
+        let range = ParamRange::Discrete(vec![10.0, 20.0, 30.0]);
+        for _ in 0..20 {
+            assert!(range.values().contains(&range.sample()));
+        }
+    }
+
+    #[test]
+    fn test_hyperopt_run_produces_requested_epoch_count_and_capped_top_k() {
+        let candles = flat_bars(48, 1_700_000_000, 50_000.0);
+        let space = vec![(
+            HyperoptField::BaseEntryThreshold,
+            ParamRange::Discrete(vec![55.0, 60.0, 65.0]),
+        )];
+
+        let report = Hyperopt::run(&test_config(), &space, &candles, Objective::TotalProfit, 6, 3);
+
+        assert_eq!(report.epochs.len(), 6);
+        assert_eq!(report.top.len(), 3);
+    }
+
+    #[test]
+    fn test_hyperopt_top_is_sorted_best_score_first() {
+        let candles = flat_bars(48, 1_700_000_000, 50_000.0);
+        let space = vec![(
+            HyperoptField::MaxRiskPerTradePercent,
+            ParamRange::Stepped { min: 1.0, max: 3.0, step: 1.0 },
+        )];
+
+        let report = Hyperopt::run(&test_config(), &space, &candles, Objective::TotalProfit, 5, 5);
+
+        for pair in report.top.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+    }
+
+    #[test]
+    fn test_hyperopt_best_env_block_names_swept_fields() {
+        let candles = flat_bars(48, 1_700_000_000, 50_000.0);
+        let space = vec![(
+            HyperoptField::AtrSlMultiplier,
+            ParamRange::Discrete(vec![1.0]),
+        )];
+
+        let report = Hyperopt::run(&test_config(), &space, &candles, Objective::TotalProfit, 1, 1);
+
+        assert_eq!(report.best_env_block(), "ATR_SL_MULTIPLIER=1");
+    }
+
+    #[test]
+    fn test_hyperopt_best_env_block_empty_when_no_epochs() {
+        let candles = flat_bars(48, 1_700_000_000, 50_000.0);
+        let report = Hyperopt::run(&test_config(), &[], &candles, Objective::TotalProfit, 0, 3);
+        assert_eq!(report.best_env_block(), "");
+    }
+}
@@ -0,0 +1,126 @@
+//! Forward-looking, fee-aware fractional-Kelly position sizing
+//!
+//! `CapitalTier::risk_per_trade_percent` labels its largest values "Standard Kelly" in
+//! a comment, but nothing in the codebase actually ran the Kelly criterion - sizing
+//! there is a flat percent-of-portfolio figure. This module is the real computation,
+//! driven by an estimated win probability and payoff ratio rather than `edge.rs`'s
+//! realized-trade-history expectancy: `f = (b*p - (1-p)) / b`, with the round-trip fee
+//! deducted from the gross edge before `b` is computed so fees shrink the bet, a
+//! configurable fractional-Kelly coefficient applied on top, and the result clamped to
+//! the current `CapitalTier`'s `max_position_percent`.
+
+use crate::amount::Amount;
+use crate::capital_tier::{CapitalTier, FeeTier};
+
+/// Floor applied to `b`'s magnitude before dividing by it, so a near-zero expected
+/// loss can't blow the fraction up toward infinity.
+const MIN_PAYOFF_RATIO: f64 = 0.01;
+
+/// Result of `kelly_fraction`: the raw (already fee-adjusted, scaled, and clamped)
+/// fraction of portfolio to risk, and the dollar amount it resolves to, so callers
+/// can log both.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KellySizing {
+    pub fraction: f64,
+    pub amount: Amount,
+}
+
+impl KellySizing {
+    fn zero() -> Self {
+        Self { fraction: 0.0, amount: Amount::ZERO }
+    }
+}
+
+/// Size a position from the classic Kelly criterion, fee-adjusted and clamped.
+///
+/// - `win_probability`: estimated probability the trade wins (`p`), in `[0, 1]`.
+/// - `expected_win_percent`: expected gain (%) if the trade wins - the gross edge.
+/// - `expected_loss_percent`: expected loss (%, positive) if the trade loses.
+/// - `fee_tier`: current `FeeTier`; its `round_trip_percent()` is deducted from the
+///   gross edge before the payoff ratio `b` is computed, so fees shrink the bet.
+/// - `fractional_coefficient`: scales full Kelly down (e.g. `0.25`-`0.5`) since full
+///   Kelly is too aggressive for live capital - the same rationale as
+///   `config::edge_kelly_cap` for `edge::EdgeStats::kelly_fraction`.
+/// - `tier`: current `CapitalTier`; its `max_position_percent()` upper-bounds the
+///   final fraction regardless of how strong the computed edge is.
+/// - `portfolio_value`: total portfolio value the fraction is sized against.
+///
+/// Returns a zero `KellySizing` (no bet) if the fee-adjusted edge isn't positive.
+pub fn kelly_fraction(
+    win_probability: f64,
+    expected_win_percent: f64,
+    expected_loss_percent: f64,
+    fee_tier: &FeeTier,
+    fractional_coefficient: f64,
+    tier: CapitalTier,
+    portfolio_value: Amount,
+) -> KellySizing {
+    let fee_adjusted_edge_percent = expected_win_percent - fee_tier.round_trip_percent();
+    if fee_adjusted_edge_percent <= 0.0 {
+        return KellySizing::zero();
+    }
+
+    let loss_percent = expected_loss_percent.abs().max(f64::EPSILON);
+    let raw_payoff_ratio = fee_adjusted_edge_percent / loss_percent;
+    let payoff_ratio = if raw_payoff_ratio.abs() < MIN_PAYOFF_RATIO {
+        MIN_PAYOFF_RATIO.copysign(raw_payoff_ratio)
+    } else {
+        raw_payoff_ratio
+    };
+
+    let full_kelly = (payoff_ratio * win_probability - (1.0 - win_probability)) / payoff_ratio;
+    let full_kelly = full_kelly.clamp(0.0, 1.0);
+
+    let fraction = (full_kelly * fractional_coefficient).min(tier.max_position_percent() / 100.0).max(0.0);
+    let amount = portfolio_value.checked_mul(fraction).unwrap_or(Amount::ZERO);
+
+    KellySizing { fraction, amount }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_tier(round_trip_percent: f64) -> FeeTier {
+        FeeTier { taker_fee_percent: round_trip_percent / 2.0, maker_fee_percent: round_trip_percent / 2.0 }
+    }
+
+    #[test]
+    fn test_zero_when_fee_adjusted_edge_not_positive() {
+        let sizing = kelly_fraction(0.6, 0.5, 1.0, &fee_tier(0.6), 0.5, CapitalTier::Standard, Amount::from_dollars(10_000.0));
+        assert_eq!(sizing.fraction, 0.0);
+        assert_eq!(sizing.amount, Amount::ZERO);
+    }
+
+    #[test]
+    fn test_positive_edge_scales_by_fractional_coefficient() {
+        // win 2%, lose 1%, 60% win rate, 0.1% round-trip fees: b = (2 - 0.1)/1 = 1.9,
+        // f = (1.9*0.6 - 0.4)/1.9 ≈ 0.3895, half of that ≈ 0.1947
+        let sizing = kelly_fraction(0.6, 2.0, 1.0, &fee_tier(0.1), 0.5, CapitalTier::Large, Amount::from_dollars(10_000.0));
+        assert!((sizing.fraction - 0.1947).abs() < 0.001, "fraction was {}", sizing.fraction);
+        assert_eq!(sizing.amount, Amount::from_dollars(1_947.37));
+    }
+
+    #[test]
+    fn test_fraction_clamped_to_tier_max_position_percent() {
+        // Huge edge would push full Kelly and the scaled fraction near 1.0, but Tiny
+        // caps max_position_percent at 80%.
+        let sizing = kelly_fraction(0.95, 10.0, 1.0, &fee_tier(0.1), 1.0, CapitalTier::Tiny, Amount::from_dollars(1_000.0));
+        assert!(sizing.fraction <= 0.80 + 1e-9);
+    }
+
+    #[test]
+    fn test_near_zero_expected_loss_does_not_blow_up() {
+        let sizing = kelly_fraction(0.6, 1.0, 0.0, &fee_tier(0.1), 0.5, CapitalTier::Standard, Amount::from_dollars(10_000.0));
+        assert!(sizing.fraction.is_finite());
+        assert!(sizing.fraction >= 0.0 && sizing.fraction <= 1.0);
+    }
+
+    #[test]
+    fn test_full_kelly_capped_at_one_before_scaling() {
+        // Near-certain win_probability with a tiny payoff ratio (b = 0.05): full Kelly
+        // is p - (1-p)/b ≈ 0.979, and the scaled fraction must stay within 1.0.
+        let sizing = kelly_fraction(0.999, 0.05, 1.0, &fee_tier(0.0), 1.0, CapitalTier::Large, Amount::from_dollars(10_000.0));
+        assert!(sizing.fraction <= 1.0);
+    }
+}
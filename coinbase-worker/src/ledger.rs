@@ -0,0 +1,453 @@
+//! Append-only, tamper-evident trade ledger with an incrementally maintained Merkle root
+//!
+//! Trades in `TradingStateData` live inside one mutable KV blob (`STATE_KEY`), so a bad
+//! `save_trading_state` write can silently rewrite history - there is no way to tell a
+//! legitimate update from overwritten trade data. This module gives executed trades a
+//! parallel, append-only home: each `Trade` is canonically serialized, SHA-256-hashed
+//! into a leaf, and appended to a KV-backed list while a running Merkle root is
+//! maintained incrementally via the "frontier" - the O(log N) right-edge of completed
+//! subtrees, the same technique used by Certificate Transparency logs and the Eth2
+//! deposit contract's incremental Merkle tree. Appending leaf N only touches O(log N)
+//! frontier entries rather than rehashing the whole tree.
+//!
+//! Leaf and internal-node hashes are domain-separated (`LEAF_PREFIX` / `NODE_PREFIX`) so
+//! a leaf's hash can never collide with an internal node's, which would otherwise let a
+//! forged leaf masquerade as a subtree root.
+//!
+//! Unless the leaf count is a power of two, the frontier is left holding several
+//! unmerged "peaks" of different heights. `bag_peaks` folds them into one root (oldest,
+//! tallest peak on the left), the same "peak bagging" used by Merkle Mountain Ranges.
+
+use crate::error::{Result, TradingError};
+use crate::types::Trade;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use worker::Env;
+
+const LEDGER_COUNT_KEY: &str = "ledger_count";
+const LEDGER_FRONTIER_KEY: &str = "ledger_frontier";
+const LEDGER_ROOT_KEY: &str = "ledger_root";
+
+fn leaf_key(index: u64) -> String {
+    format!("ledger_leaf_{index}")
+}
+
+/// Domain-separation tags so a leaf hash can never be replayed as an internal node hash.
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+fn hash_leaf(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_decode(s: &str) -> Result<[u8; 32]> {
+    if s.len() != 64 {
+        return Err(TradingError::Storage(format!("Invalid ledger hash length: {s}")));
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| TradingError::Storage(format!("Invalid ledger hash hex: {s}")))?;
+    }
+    Ok(out)
+}
+
+fn leaf_hash_for(trade: &Trade) -> Result<[u8; 32]> {
+    let bytes = serde_json::to_vec(trade)?;
+    Ok(hash_leaf(&bytes))
+}
+
+/// One node on the Merkle frontier: the root of a completed subtree of `2^height`
+/// leaves that hasn't yet been merged into a taller subtree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FrontierNode {
+    height: u32,
+    hash: String,
+}
+
+/// Fold `leaf_hash` into `frontier`, merging equal-height peaks bottom-up exactly as
+/// many times as needed so the frontier never holds two peaks of the same height.
+fn append_leaf(frontier: &mut Vec<FrontierNode>, leaf_hash: [u8; 32]) -> Result<()> {
+    let mut height = 0u32;
+    let mut hash = leaf_hash;
+    while let Some(top) = frontier.last() {
+        if top.height != height {
+            break;
+        }
+        let left = hex_decode(&top.hash)?;
+        hash = hash_node(&left, &hash);
+        frontier.pop();
+        height += 1;
+    }
+    frontier.push(FrontierNode { height, hash: hex_encode(&hash) });
+    Ok(())
+}
+
+/// Fold the frontier's peaks into a single root, oldest (tallest) peak on the left.
+fn bag_peaks(frontier: &[FrontierNode]) -> Result<Option<[u8; 32]>> {
+    let Some(last) = frontier.last() else {
+        return Ok(None);
+    };
+    let mut acc = hex_decode(&last.hash)?;
+    for node in frontier[..frontier.len() - 1].iter().rev() {
+        let left = hex_decode(&node.hash)?;
+        acc = hash_node(&left, &acc);
+    }
+    Ok(Some(acc))
+}
+
+/// Which side of a merge a proof step's sibling hash sits on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofSide {
+    Left,
+    Right,
+}
+
+/// One step of a bottom-up Merkle inclusion proof: the sibling hash to combine with the
+/// running hash, and which side it belongs on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofStep {
+    pub sibling: String,
+    pub side: ProofSide,
+}
+
+/// A leaf's inclusion proof: combining `leaf_hash` with `steps` bottom-up (see
+/// `verify_proof`) must reproduce `root`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerProof {
+    pub leaf_index: u64,
+    pub leaf_hash: String,
+    pub root: String,
+    pub steps: Vec<ProofStep>,
+}
+
+/// Recompute a leaf's path to the root from a proof's sibling steps and check it
+/// matches `proof.root`. Used both to sanity-check proofs this module generates and as
+/// the reusable core of `/api/ledger/verify`'s leaf-level checks.
+pub fn verify_proof(proof: &LedgerProof) -> Result<bool> {
+    let mut acc = hex_decode(&proof.leaf_hash)?;
+    for step in &proof.steps {
+        let sibling = hex_decode(&step.sibling)?;
+        acc = match step.side {
+            ProofSide::Left => hash_node(&sibling, &acc),
+            ProofSide::Right => hash_node(&acc, &sibling),
+        };
+    }
+    Ok(hex_encode(&acc) == proof.root)
+}
+
+/// Rebuild the tree from scratch, tracking `target`'s lineage through every merge (both
+/// the frontier merges in `append_leaf` and the final peak-bagging step), and return its
+/// inclusion proof alongside the resulting root. `None` if `target` is out of range.
+fn build_proof(trades: &[Trade], target: usize) -> Result<Option<LedgerProof>> {
+    if target >= trades.len() {
+        return Ok(None);
+    }
+
+    let mut frontier: Vec<FrontierNode> = Vec::new();
+    let mut current: Option<(u32, [u8; 32])> = None;
+    let mut steps: Vec<ProofStep> = Vec::new();
+    let target_leaf_hash = leaf_hash_for(&trades[target])?;
+
+    for (i, trade) in trades.iter().enumerate() {
+        let mut height = 0u32;
+        let mut hash = leaf_hash_for(trade)?;
+        if i == target {
+            current = Some((height, hash));
+        }
+
+        while let Some(top) = frontier.last() {
+            if top.height != height {
+                break;
+            }
+            let left = hex_decode(&top.hash)?;
+            let right = hash;
+
+            if let Some((current_height, current_hash)) = current {
+                if current_height == height && current_hash == left {
+                    steps.push(ProofStep { sibling: hex_encode(&right), side: ProofSide::Right });
+                    current = Some((height + 1, hash_node(&left, &right)));
+                } else if current_height == height && current_hash == right {
+                    steps.push(ProofStep { sibling: hex_encode(&left), side: ProofSide::Left });
+                    current = Some((height + 1, hash_node(&left, &right)));
+                }
+            }
+
+            hash = hash_node(&left, &right);
+            frontier.pop();
+            height += 1;
+        }
+
+        frontier.push(FrontierNode { height, hash: hex_encode(&hash) });
+    }
+
+    let Some(current) = current else {
+        return Ok(None); // unreachable: target < trades.len() guarantees this was set
+    };
+
+    // Fold the remaining peaks into the root exactly as `bag_peaks` would, extending
+    // the proof past `current`'s own peak whenever it participates in a bagging step.
+    let n = frontier.len();
+    let mut acc = hex_decode(&frontier[n - 1].hash)?;
+    let mut acc_is_current = frontier[n - 1].height == current.0 && hex_decode(&frontier[n - 1].hash)? == current.1;
+    for node in frontier[..n - 1].iter().rev() {
+        let peak = hex_decode(&node.hash)?;
+        if acc_is_current {
+            steps.push(ProofStep { sibling: hex_encode(&peak), side: ProofSide::Left });
+        } else if node.height == current.0 && peak == current.1 {
+            steps.push(ProofStep { sibling: hex_encode(&acc), side: ProofSide::Right });
+            acc_is_current = true;
+        }
+        acc = hash_node(&peak, &acc);
+    }
+
+    Ok(Some(LedgerProof {
+        leaf_index: target as u64,
+        leaf_hash: hex_encode(&target_leaf_hash),
+        root: hex_encode(&acc),
+        steps,
+    }))
+}
+
+/// Recompute the Merkle root from scratch by replaying every stored leaf through
+/// `append_leaf`, independent of whatever frontier/root KV currently holds. This is
+/// what `verify_ledger` trusts instead of the persisted root.
+fn recompute_root(trades: &[Trade]) -> Result<Option<String>> {
+    let mut frontier: Vec<FrontierNode> = Vec::new();
+    for trade in trades {
+        let leaf_hash = leaf_hash_for(trade)?;
+        append_leaf(&mut frontier, leaf_hash)?;
+    }
+    Ok(bag_peaks(&frontier)?.map(|root| hex_encode(&root)))
+}
+
+/// Append `trade` as the next ledger leaf, fold its hash into the persisted frontier,
+/// and persist the updated frontier/root/count. Returns the new root (hex).
+pub async fn append_trade(env: &Env, trade: &Trade) -> Result<String> {
+    let kv = env.kv("STATE").map_err(|e| TradingError::Storage(format!("KV unavailable: {e}")))?;
+
+    let count: u64 = kv
+        .get(LEDGER_COUNT_KEY)
+        .json()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read ledger count: {e}")))?
+        .unwrap_or(0);
+    let mut frontier: Vec<FrontierNode> = kv
+        .get(LEDGER_FRONTIER_KEY)
+        .json()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read ledger frontier: {e}")))?
+        .unwrap_or_default();
+
+    kv.put(&leaf_key(count), trade)
+        .map_err(|e| TradingError::Storage(format!("Failed to bind ledger leaf: {e}")))?
+        .execute()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to store ledger leaf: {e}")))?;
+
+    let leaf_hash = leaf_hash_for(trade)?;
+    append_leaf(&mut frontier, leaf_hash)?;
+    let root = bag_peaks(&frontier)?.expect("frontier is non-empty immediately after append_leaf");
+    let root_hex = hex_encode(&root);
+
+    kv.put(LEDGER_FRONTIER_KEY, &frontier)
+        .map_err(|e| TradingError::Storage(format!("Failed to bind ledger frontier: {e}")))?
+        .execute()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to persist ledger frontier: {e}")))?;
+    kv.put(LEDGER_ROOT_KEY, &root_hex)
+        .map_err(|e| TradingError::Storage(format!("Failed to bind ledger root: {e}")))?
+        .execute()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to persist ledger root: {e}")))?;
+    kv.put(LEDGER_COUNT_KEY, &(count + 1))
+        .map_err(|e| TradingError::Storage(format!("Failed to bind ledger count: {e}")))?
+        .execute()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to persist ledger count: {e}")))?;
+
+    Ok(root_hex)
+}
+
+/// Read every leaf stored so far, in order. O(N) KV reads - fine for the verify/export
+/// endpoints this backs, not meant for the hot trading-cycle path.
+pub async fn get_all_trades(env: &Env) -> Result<Vec<Trade>> {
+    let kv = env.kv("STATE").map_err(|e| TradingError::Storage(format!("KV unavailable: {e}")))?;
+    let count: u64 = kv
+        .get(LEDGER_COUNT_KEY)
+        .json()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read ledger count: {e}")))?
+        .unwrap_or(0);
+
+    let mut trades = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let trade: Trade = kv
+            .get(&leaf_key(i))
+            .json()
+            .await
+            .map_err(|e| TradingError::Storage(format!("Failed to read ledger leaf {i}: {e}")))?
+            .ok_or_else(|| TradingError::Storage(format!("Ledger leaf {i} missing")))?;
+        trades.push(trade);
+    }
+    Ok(trades)
+}
+
+/// The currently persisted root, if any trades have been appended yet.
+pub async fn get_persisted_root(env: &Env) -> Result<Option<String>> {
+    let kv = env.kv("STATE").map_err(|e| TradingError::Storage(format!("KV unavailable: {e}")))?;
+    kv.get(LEDGER_ROOT_KEY)
+        .json()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read ledger root: {e}")))
+}
+
+/// Root + inclusion proof for the trade at `index`, for `GET /api/ledger`.
+pub async fn get_ledger_entry(env: &Env, index: u64) -> Result<Option<LedgerProof>> {
+    let trades = get_all_trades(env).await?;
+    build_proof(&trades, index as usize)
+}
+
+/// Result of recomputing the root from stored leaves and comparing it to what's
+/// persisted, for `GET /api/ledger/verify`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerVerification {
+    pub leaf_count: u64,
+    pub persisted_root: Option<String>,
+    pub computed_root: Option<String>,
+    pub ok: bool,
+}
+
+/// Recompute the root from every stored leaf and compare it to the persisted root,
+/// flagging tampering - i.e. a state edit that bypassed `append_trade`.
+pub async fn verify_ledger(env: &Env) -> Result<LedgerVerification> {
+    let trades = get_all_trades(env).await?;
+    let computed_root = recompute_root(&trades)?;
+    let persisted_root = get_persisted_root(env).await?;
+    let ok = computed_root == persisted_root;
+    Ok(LedgerVerification {
+        leaf_count: trades.len() as u64,
+        persisted_root,
+        computed_root,
+        ok,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OrderSide;
+
+    fn trade(symbol: &str, quantity: f64, price: f64) -> Trade {
+        Trade {
+            id: format!("{symbol}-{quantity}-{price}"),
+            symbol: symbol.to_string(),
+            side: OrderSide::Buy,
+            quantity,
+            price,
+            total_value: quantity * price,
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            pnl: None,
+            order_type: crate::types::OrderType::Market,
+            fee: 0.0,
+            position_side: crate::types::PositionSide::Long,
+        }
+    }
+
+    #[test]
+    fn test_hash_leaf_and_hash_node_are_domain_separated() {
+        let bytes = b"same bytes";
+        let leaf = hash_leaf(bytes);
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        let plain: [u8; 32] = hasher.finalize().into();
+        assert_ne!(leaf, plain);
+    }
+
+    #[test]
+    fn test_append_leaf_merges_equal_height_peaks() {
+        let mut frontier = Vec::new();
+        append_leaf(&mut frontier, hash_leaf(b"a")).unwrap();
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(frontier[0].height, 0);
+
+        append_leaf(&mut frontier, hash_leaf(b"b")).unwrap();
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(frontier[0].height, 1);
+    }
+
+    #[test]
+    fn test_append_leaf_leaves_unmerged_peak_for_odd_count() {
+        let mut frontier = Vec::new();
+        for leaf in [&b"a"[..], &b"b"[..], &b"c"[..]] {
+            append_leaf(&mut frontier, hash_leaf(leaf)).unwrap();
+        }
+        let heights: Vec<u32> = frontier.iter().map(|n| n.height).collect();
+        assert_eq!(heights, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_bag_peaks_single_peak_is_identity() {
+        let mut frontier = Vec::new();
+        append_leaf(&mut frontier, hash_leaf(b"a")).unwrap();
+        let root = bag_peaks(&frontier).unwrap().unwrap();
+        assert_eq!(hex_encode(&root), frontier[0].hash);
+    }
+
+    #[test]
+    fn test_bag_peaks_empty_frontier_is_none() {
+        assert!(bag_peaks(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_build_proof_and_verify_roundtrip_for_every_leaf_count() {
+        for count in 1..=7 {
+            let trades: Vec<Trade> = (0..count).map(|i| trade("BTC-USD", 1.0 + i as f64, 100.0)).collect();
+            let expected_root = recompute_root(&trades).unwrap().unwrap();
+
+            for target in 0..count {
+                let proof = build_proof(&trades, target).unwrap().unwrap();
+                assert_eq!(proof.root, expected_root, "count={count} target={target}");
+                assert!(verify_proof(&proof).unwrap(), "count={count} target={target}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_proof_out_of_range_is_none() {
+        let trades = vec![trade("BTC-USD", 1.0, 100.0)];
+        assert!(build_proof(&trades, 5).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_verify_proof_rejects_tampered_leaf_hash() {
+        let trades: Vec<Trade> = (0..4).map(|i| trade("ETH-USD", 1.0 + i as f64, 200.0)).collect();
+        let mut proof = build_proof(&trades, 2).unwrap().unwrap();
+        assert!(verify_proof(&proof).unwrap());
+
+        proof.leaf_hash = hex_encode(&hash_leaf(b"forged"));
+        assert!(!verify_proof(&proof).unwrap());
+    }
+
+    #[test]
+    fn test_recompute_root_is_order_sensitive() {
+        let a = vec![trade("BTC-USD", 1.0, 100.0), trade("ETH-USD", 2.0, 200.0)];
+        let b = vec![trade("ETH-USD", 2.0, 200.0), trade("BTC-USD", 1.0, 100.0)];
+        assert_ne!(recompute_root(&a).unwrap(), recompute_root(&b).unwrap());
+    }
+}
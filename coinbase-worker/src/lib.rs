@@ -29,26 +29,61 @@
 #![allow(clippy::map_unwrap_or)] // Explicit error handling preference
 #![allow(clippy::manual_clamp)] // Explicit NaN handling in trading code
 
+mod amount;
+mod api;
 mod auth;
+mod backtest;
+mod backtest_report;
+mod candle_store;
 mod capital_tier;
 mod client;
 mod config;
 mod dashboard;
+mod edge;
 mod error;
+mod exchange;
+mod fx;
+mod history;
+mod hyperopt;
+mod kelly;
+mod ledger;
+mod liquidation;
+mod lots;
+mod market_stream;
+mod money;
+mod options;
+mod pairlist;
+mod price_cache;
+mod protections;
 mod strategy;
+mod support_resistance;
+mod symbol_filters;
 mod trading;
 mod types;
 
+use futures::future::join_all;
+use money::decimal_from_f64;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use worker::{
     Context, Env, Request, Response, Router, ScheduleContext, ScheduledEvent, console_log, event,
 };
 
+pub use api::{ApiError, ApiResponse};
 pub use auth::CoinbaseAuth;
-pub use capital_tier::{CapitalTier, FeeTier, TierParameters};
+pub use candle_store::{Resolution, StoredCandle};
+pub use capital_tier::{CapitalTier, FeeTier, TierConfigSnapshot, TierParameters};
 pub use client::CoinbaseClient;
 pub use config::Config;
 pub use error::TradingError;
+pub use exchange::{CashBalance, Exchange};
+pub use fx::{format_currency, ExchangeRates};
+pub use liquidation::LiquidationEstimate;
+pub use lots::{CostBasisMethod, LotLedger};
+pub use market_stream::{CoinbaseMarketStream, MarketUpdate, OrderbookSnapshot, ReconnectBackoff};
+pub use options::OptionLeg;
 pub use strategy::TradingStrategy;
+pub use symbol_filters::SymbolFilters;
 pub use trading::TradingEngine;
 pub use types::*;
 
@@ -69,10 +104,10 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
         .get_async("/health", |_req, ctx| async move {
             let config = match Config::from_env(&ctx.env) {
                 Ok(c) => c,
-                Err(e) => return Response::error(format!("Config error: {e}"), 500),
+                Err(e) => return ApiResponse::<()>::err(&e),
             };
 
-            Response::from_json(&serde_json::json!({
+            ApiResponse::ok(serde_json::json!({
                 "status": "healthy",
                 "version": env!("CARGO_PKG_VERSION"),
                 "environment": config.environment,
@@ -91,13 +126,27 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
             let state = get_trading_state(&ctx.env).await?;
             Response::from_json(&state.positions)
         })
+        // Server-computed closed-trade analytics (win rate, avg win/loss, profit
+        // factor, drawdown, sharpe) - see `TradingStateData::performance_stats`
+        .get_async("/api/performance", |_req, ctx| async move {
+            let state = get_trading_state(&ctx.env).await?;
+            Response::from_json(&PerformanceResponse {
+                closed_trade_count: state.closed_trades.len(),
+                stats: state.performance_stats(),
+            })
+        })
         // Get portfolio with live P&L
         .get_async("/api/portfolio", |_req, ctx| async move {
             match get_portfolio_with_pnl(&ctx.env).await {
-                Ok(result) => Response::from_json(&result),
-                Err(e) => Response::from_json(&serde_json::json!({
-                    "error": format!("{e}")
-                })),
+                Ok(result) => ApiResponse::ok(result),
+                Err(e) => ApiResponse::<()>::err(&e),
+            }
+        })
+        // Resolved tier/fee configuration the bot is currently using
+        .get_async("/api/tier-config", |_req, ctx| async move {
+            match get_tier_config(&ctx.env).await {
+                Ok(snapshot) => ApiResponse::ok(snapshot),
+                Err(e) => ApiResponse::<()>::err(&e),
             }
         })
         // Get trading status
@@ -108,7 +157,7 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
                 "positions": state.positions.len(),
                 "last_cycle": state.last_cycle_time,
                 "total_trades": state.total_trades,
-                "total_pnl": state.total_pnl,
+                "total_pnl": state.total_pnl.to_string(),
                 "consecutive_errors": state.consecutive_errors,
                 "daily_trades": state.daily_trades,
             }))
@@ -117,11 +166,7 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
         .get_async("/api/balance", |_req, ctx| async move {
             let auth = match CoinbaseAuth::from_env(&ctx.env) {
                 Ok(a) => a,
-                Err(e) => {
-                    return Response::from_json(&serde_json::json!({
-                        "error": format!("{e}")
-                    }));
-                }
+                Err(e) => return ApiResponse::<()>::err(&e),
             };
             let client = CoinbaseClient::new(auth);
 
@@ -130,10 +175,7 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
                     let balances: Vec<_> = accounts
                         .accounts
                         .iter()
-                        .filter(|a| {
-                            let val: f64 = a.available_balance.value.parse().unwrap_or(0.0);
-                            val > 0.0
-                        })
+                        .filter(|a| a.available_balance.decimal().is_ok_and(|d| d > Decimal::ZERO))
                         .map(|a| {
                             serde_json::json!({
                                 "currency": a.currency,
@@ -142,27 +184,18 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
                             })
                         })
                         .collect();
-                    Response::from_json(&serde_json::json!({
+                    ApiResponse::ok(serde_json::json!({
                         "accounts": balances
                     }))
                 }
-                Err(e) => Response::from_json(&serde_json::json!({
-                    "error": format!("{e}")
-                })),
+                Err(e) => ApiResponse::<()>::err(&e),
             }
         })
         // Manual trade trigger
         .post_async("/api/trigger", |_req, ctx| async move {
-            // Wrap in catch_unwind would be nice, but async closures...
-            // Instead, try to return more detailed errors
-            let result = run_trading_cycle(&ctx.env).await;
-            match result {
-                Ok(result) => Response::from_json(&result),
-                Err(e) => Response::from_json(&serde_json::json!({
-                    "error": true,
-                    "message": format!("{e}"),
-                    "error_type": format!("{e:?}").split('(').next().unwrap_or("Unknown")
-                })),
+            match run_trading_cycle(&ctx.env).await {
+                Ok(result) => ApiResponse::ok(result),
+                Err(e) => ApiResponse::<()>::err(&e),
             }
         })
         // Test auth only (debug endpoint)
@@ -251,6 +284,99 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> WResult<Response> {
                 })),
             }
         })
+        // Root + Merkle inclusion proof for one trade in the tamper-evident ledger.
+        // `?index=N` selects the trade; defaults to the most recently appended one.
+        .get_async("/api/ledger", |req, ctx| async move {
+            let index = req
+                .url()
+                .ok()
+                .and_then(|u| u.query_pairs().find(|(k, _)| k.as_ref() == "index").map(|(_, v)| v.into_owned()))
+                .and_then(|v| v.parse::<u64>().ok());
+
+            let result = async {
+                let index = match index {
+                    Some(i) => i,
+                    None => {
+                        let trades = ledger::get_all_trades(&ctx.env).await?;
+                        match trades.len().checked_sub(1) {
+                            Some(last) => last as u64,
+                            None => return Ok(None),
+                        }
+                    }
+                };
+                ledger::get_ledger_entry(&ctx.env, index).await
+            }
+            .await;
+
+            match result {
+                Ok(Some(proof)) => Response::from_json(&proof),
+                Ok(None) => Response::from_json(&serde_json::json!({
+                    "error": true,
+                    "message": "No matching ledger entry"
+                })),
+                Err(e) => Response::from_json(&serde_json::json!({
+                    "error": true,
+                    "message": format!("{e}")
+                })),
+            }
+        })
+        // Recompute the Merkle root from every stored leaf and compare it to the
+        // persisted root, flagging any tampering with the append-only trade ledger.
+        .get_async("/api/ledger/verify", |_req, ctx| async move {
+            match ledger::verify_ledger(&ctx.env).await {
+                Ok(result) => Response::from_json(&result),
+                Err(e) => Response::from_json(&serde_json::json!({
+                    "error": true,
+                    "message": format!("{e}")
+                })),
+            }
+        })
+        // Closed-trade history from D1, most recent first. `?symbol=BTC-USD` filters
+        // to one symbol; `?limit=N` caps the page size (default 50).
+        .get_async("/api/history", |req, ctx| async move {
+            let url = match req.url() {
+                Ok(u) => u,
+                Err(e) => return Response::from_json(&serde_json::json!({ "error": true, "message": format!("{e}") })),
+            };
+            let symbol = url.query_pairs().find(|(k, _)| k.as_ref() == "symbol").map(|(_, v)| v.into_owned());
+            let limit = url
+                .query_pairs()
+                .find(|(k, _)| k.as_ref() == "limit")
+                .and_then(|(_, v)| v.parse::<u32>().ok())
+                .unwrap_or(50);
+
+            let result = async {
+                let d1 = ctx.env.d1("DB").map_err(|e| TradingError::Storage(format!("D1 unavailable: {e}")))?;
+                history::get_trade_history(&d1, symbol.as_deref(), limit).await
+            }
+            .await;
+
+            match result {
+                Ok(trades) => Response::from_json(&trades),
+                Err(e) => Response::from_json(&serde_json::json!({
+                    "error": true,
+                    "message": format!("{e}")
+                })),
+            }
+        })
+        // Cumulative realized P&L, win/loss counts, average hold duration, and
+        // best/worst symbol - computed entirely in SQL over the full D1 trade history
+        // rather than the KV blob's capped, in-memory `closed_trades`.
+        .get_async("/api/stats", |_req, ctx| async move {
+            let result = async {
+                let d1 = ctx.env.d1("DB").map_err(|e| TradingError::Storage(format!("D1 unavailable: {e}")))?;
+                history::get_stats(&d1).await
+            }
+            .await;
+
+            match result {
+                Ok(stats) => Response::from_json(&stats),
+                Err(e) => Response::from_json(&serde_json::json!({
+                    "error": true,
+                    "message": format!("{e}")
+                })),
+            }
+        })
         // Fallback
         .run(req, env)
         .await
@@ -290,17 +416,72 @@ async fn run_trading_cycle(env: &Env) -> std::result::Result<TradingCycleResult,
     let auth = CoinbaseAuth::from_env(env)?;
     let client = CoinbaseClient::new(auth);
 
+    let cost_basis_method = config.cost_basis_method;
+
     // Initialize trading engine
-    let engine = TradingEngine::new(client, config);
+    let engine = TradingEngine::new(Box::new(client), config);
 
     // Run the trading cycle
     let result = engine.run_cycle(&mut state).await?;
 
+    // Feed every fill from this cycle into its symbol's lot ledger, so
+    // `/api/portfolio` can report a per-position realized breakdown and weighted
+    // cost basis instead of the blended `total_invested`/`total_pnl` figures.
+    // Route by `pnl`/`position_side`, not raw order side: a short's *opening* fill
+    // is a Sell and its *closing* fill is a Buy, the reverse of a long, so
+    // `trade.side` alone can't tell `record_open` from `record_close` apart.
+    // `pnl` already distinguishes them cleanly - it's only ever `Some` on a close.
+    for trade in &result.trades {
+        let ledger = state
+            .lot_ledgers
+            .entry(trade.symbol.clone())
+            .or_insert_with(|| LotLedger::new(cost_basis_method));
+        if trade.pnl.is_none() {
+            ledger.record_open(trade.position_side, trade.quantity, trade.price, trade.fee, &trade.timestamp);
+        } else {
+            ledger.record_close(trade.quantity, trade.price, trade.fee);
+        }
+    }
+
     // Save updated state to KV
     save_trading_state(env, &state)
         .await
         .map_err(|e| TradingError::Trading(e.to_string()))?;
 
+    // Append each fill to the tamper-evident ledger, independent of the mutable state
+    // blob just saved above. A single trade's append failing shouldn't fail the whole
+    // cycle - the trade already executed and is reflected in `state` - so this only logs.
+    for trade in &result.trades {
+        if let Err(e) = ledger::append_trade(env, trade).await {
+            console_log!("Ledger append failed for trade {}: {}", trade.id, e);
+        }
+    }
+
+    // Mirror the cycle's effects into D1 for durable, queryable history - same
+    // best-effort treatment as the ledger append above, since the KV state already
+    // saved is the source of truth and a D1 hiccup shouldn't fail the cycle.
+    match env.d1("DB") {
+        Ok(d1) => {
+            let new_closed_count = result.trades.iter().filter(|t| t.pnl.is_some()).count();
+            let newly_closed = &state.closed_trades[state.closed_trades.len().saturating_sub(new_closed_count)..];
+            for trade in newly_closed {
+                if let Err(e) = history::record_closed_trade(&d1, trade).await {
+                    console_log!("D1 closed trade record failed for {}: {}", trade.symbol, e);
+                }
+            }
+
+            if let Err(e) = history::sync_open_positions(&d1, &state.positions).await {
+                console_log!("D1 position sync failed: {}", e);
+            }
+
+            let ran_at = state.last_cycle_time.clone().unwrap_or_default();
+            if let Err(e) = history::record_cycle(&d1, &ran_at, &result).await {
+                console_log!("D1 cycle record failed: {}", e);
+            }
+        }
+        Err(e) => console_log!("D1 unavailable, skipping history sync: {}", e),
+    }
+
     Ok(result)
 }
 
@@ -354,6 +535,7 @@ async fn scan_all_symbols(env: &Env) -> std::result::Result<serde_json::Value, T
             stats.low_24h,
             stats.is_uptrend,
             stats.volume_24h,
+            None,
         );
 
         let has_position = state.get_position(symbol).is_some();
@@ -420,26 +602,25 @@ async fn debug_trading_check(env: &Env) -> std::result::Result<serde_json::Value
         .await
         .map_err(|e| TradingError::Trading(e.to_string()))?;
 
-    // Get accounts to check balance (USD + USDC both count as cash)
-    let accounts = client.get_accounts().await?;
-    let usd_balance: f64 = accounts
-        .accounts
-        .iter()
-        .filter(|a| a.currency == "USD" || a.currency == "USDC")
-        .filter_map(|a| a.available_balance.value.parse::<f64>().ok())
-        .sum();
+    // Get accounts to check balance (USD + USDC both count as cash). Goes through
+    // `get_usd_balance`'s `Decimal` accumulation rather than re-summing raw `f64`
+    // parses here, so this debug view can't drift from what live trading sees.
+    let usd_balance = client.get_usd_balance().await.unwrap_or(0.0);
 
     // Calculate total portfolio
-    let mut positions_value = 0.0;
+    let mut positions_value = Decimal::ZERO;
     for pos in &state.positions {
         if let Ok(price) = client.get_price(&pos.symbol).await {
-            positions_value += pos.quantity * price;
+            let qty = decimal_from_f64(pos.quantity).unwrap_or_default();
+            let price = decimal_from_f64(price).unwrap_or_default();
+            positions_value += qty * price;
         }
     }
+    let positions_value = positions_value.to_f64().unwrap_or(0.0);
     let total_portfolio = usd_balance + positions_value;
 
     // Get capital tier info for adaptive parameters
-    let tier_params = capital_tier::TierParameters::for_portfolio(total_portfolio);
+    let tier_params = capital_tier::TierParameters::for_portfolio(amount::Amount::from_dollars(total_portfolio));
     let fee_tier = capital_tier::FeeTier::from_volume(0.0); // Assume low volume for now
 
     // Check AVAX specifically
@@ -450,7 +631,17 @@ async fn debug_trading_check(env: &Env) -> std::result::Result<serde_json::Value
     let range_percent = ((stats.high_24h - stats.low_24h) / stats.low_24h) * 100.0;
     let volatility_factor = (range_percent / 3.0).max(0.5).min(2.0);
 
-    let sizing = strategy.calculate_position_size(total_portfolio, usd_balance, volatility_factor);
+    let symbol_filters = client.get_symbol_filters(symbol).await.ok();
+
+    let sizing = strategy.calculate_position_size(
+        total_portfolio,
+        usd_balance,
+        volatility_factor,
+        stats.price,
+        symbol,
+        None,
+        symbol_filters.as_ref(),
+    );
 
     let analysis = strategy.analyze(
         symbol,
@@ -460,6 +651,7 @@ async fn debug_trading_check(env: &Env) -> std::result::Result<serde_json::Value
         stats.low_24h,
         stats.is_uptrend,
         stats.volume_24h,
+        None,
     );
 
     let should_enter = strategy.should_enter(&analysis, state.positions.len(), total_portfolio);
@@ -506,6 +698,15 @@ async fn debug_trading_check(env: &Env) -> std::result::Result<serde_json::Value
             "tier_cap": tier_params.max_positions,
             "hard_cap": config.max_total_positions,
         },
+        "symbol_filters": match &symbol_filters {
+            Some(filters) => serde_json::json!({
+                "price_tick": filters.price_tick.to_string(),
+                "qty_step": filters.qty_step.to_string(),
+                "min_qty": filters.min_qty.to_string(),
+                "min_notional": filters.min_notional.to_string(),
+            }),
+            None => serde_json::json!({ "error": "Failed to fetch symbol filters" }),
+        },
         "avax_stats": {
             "price": stats.price,
             "change_24h": stats.change_24h,
@@ -529,6 +730,46 @@ async fn debug_trading_check(env: &Env) -> std::result::Result<serde_json::Value
     }))
 }
 
+/// How long a cached price (see `crate::price_cache`) is served without a live
+/// refetch - short, since marks move fast and `/api/portfolio` is meant to be live.
+const PRICE_CACHE_TTL_SECONDS: i64 = 15;
+/// How long cached account balances are served without a live refetch - longer than
+/// prices since cash balances only change on a fill, not every tick.
+const BALANCE_CACHE_TTL_SECONDS: i64 = 60;
+/// How long cached FX/crypto conversion rates (see `crate::fx`) are served without a
+/// live refetch - longest of the three, since spot rates barely move tick to tick.
+const EXCHANGE_RATE_CACHE_TTL_SECONDS: i64 = 300;
+
+/// Reference net-profit target (%) `min_profitable_tp` is computed for in
+/// `get_tier_config` - matches the target `debug_trading_check` already uses for its
+/// own `min_profitable_tp` field.
+const REFERENCE_NET_PROFIT_PERCENT: f64 = 1.0;
+
+/// Resolved tier/fee configuration the bot is currently using, for `/api/tier-config`.
+async fn get_tier_config(env: &Env) -> std::result::Result<capital_tier::TierConfigSnapshot, TradingError> {
+    let auth = CoinbaseAuth::from_env(env)?;
+    let client = CoinbaseClient::new(auth);
+    let state = get_trading_state(env)
+        .await
+        .map_err(|e| TradingError::Trading(e.to_string()))?;
+
+    let usd_balance = client.get_usd_balance().await.unwrap_or(0.0);
+    let mut positions_value = Decimal::ZERO;
+    for pos in &state.positions {
+        if let Ok(price) = client.get_price(&pos.symbol).await {
+            let qty = decimal_from_f64(pos.quantity).unwrap_or_default();
+            let price = decimal_from_f64(price).unwrap_or_default();
+            positions_value += qty * price;
+        }
+    }
+    let total_portfolio = usd_balance + positions_value.to_f64().unwrap_or(0.0);
+
+    let tier_params = capital_tier::TierParameters::for_portfolio(amount::Amount::from_dollars(total_portfolio));
+    let fee_tier = capital_tier::FeeTier::from_volume(0.0); // Assume low volume for now
+
+    Ok(tier_params.config_snapshot(fee_tier, REFERENCE_NET_PROFIT_PERCENT))
+}
+
 /// Get portfolio with live P&L for each position
 async fn get_portfolio_with_pnl(env: &Env) -> std::result::Result<serde_json::Value, TradingError> {
     let config = Config::from_env(env)?;
@@ -539,21 +780,93 @@ async fn get_portfolio_with_pnl(env: &Env) -> std::result::Result<serde_json::Va
         .map_err(|e| TradingError::Trading(e.to_string()))?;
 
     let mut positions_with_pnl = Vec::new();
-    let mut total_invested = 0.0;
-    let mut total_current_value = 0.0;
-    let mut total_unrealized_pnl = 0.0;
+    let mut total_invested = Decimal::ZERO;
+    let mut total_current_value = Decimal::ZERO;
+    let mut total_unrealized_pnl = Decimal::ZERO;
+
+    let mut total_realized_pnl_lots = 0.0;
+    let mut total_liquidation_value = Decimal::ZERO;
+
+    // Every position/order is natively USD-quoted (e.g. `BTC-USD`), so rates are
+    // fetched once against a `USD` base and reused to normalize both position values
+    // and cash balances into `config.base_currency` (see `crate::fx`) below. Cached
+    // (see `crate::price_cache`) since FX rates barely move within a TTL window, and
+    // falls back to the last cached table - or an empty one (1:1 with USD for every
+    // currency) if nothing is cached yet - rather than erroring the whole report.
+    let exchange_rates_cached = price_cache::get_or_fetch(env, "exchange_rates", "USD", EXCHANGE_RATE_CACHE_TTL_SECONDS, || {
+        client.get_exchange_rates("USD")
+    })
+    .await
+    .unwrap_or(price_cache::Cached { value: ExchangeRates { base: "USD".to_string(), rates: std::collections::HashMap::new() }, age_seconds: 0, stale: true });
+    let exchange_rates = exchange_rates_cached.value;
+
+    // Per-position marks, fetched concurrently (joined futures) rather than one at a
+    // time - latency used to scale linearly with position count, so a single slow
+    // Coinbase response stalled the whole report. Each lookup is itself cached (see
+    // `crate::price_cache`) and falls back to the last cached price - flagged stale -
+    // rather than silently defaulting a missing price to the stale `entry_price`.
+    let price_fetches = join_all(state.positions.iter().map(|position| {
+        let symbol = position.symbol.clone();
+        let client = &client;
+        async move {
+            let result = price_cache::get_or_fetch(env, "price", &symbol, PRICE_CACHE_TTL_SECONDS, || client.get_price(&symbol)).await;
+            (symbol, result)
+        }
+    }))
+    .await;
+    let prices: std::collections::HashMap<String, price_cache::Cached<f64>> = price_fetches
+        .into_iter()
+        .filter_map(|(symbol, result)| result.ok().map(|cached| (symbol, cached)))
+        .collect();
+
+    let mut price_freshness = serde_json::Map::new();
 
     for position in &state.positions {
-        // Get current price
-        let current_price = match client.get_price(&position.symbol).await {
-            Ok(p) => p,
-            Err(_) => position.entry_price, // fallback
+        // Live mark for this position - falls back to `entry_price` (stale by
+        // definition) only if the cached lookup itself failed with nothing to fall
+        // back to, matching the prior no-cache fallback behavior.
+        let price_lookup = prices.get(&position.symbol);
+        let current_price = price_lookup.map_or(position.entry_price, |p| p.value);
+        price_freshness.insert(
+            position.symbol.clone(),
+            serde_json::json!({
+                "age_seconds": price_lookup.map_or(0, |p| p.age_seconds),
+                "stale": price_lookup.map_or(true, |p| p.stale),
+            }),
+        );
+
+        // Weighted cost basis from the symbol's lot ledger (see `crate::lots`) rather
+        // than the raw `entry_price`, so a position that's been DCA'd into across
+        // multiple fills reports its actual blended cost. Falls back to
+        // `entry_price`/`quantity` for a position with no recorded lots (e.g. state
+        // persisted before lot accounting existed).
+        let ledger = state.lot_ledgers.get(&position.symbol);
+        let basis = ledger.map(crate::lots::LotLedger::open_cost_basis);
+        let (basis_quantity, weighted_cost) = match basis {
+            Some(b) if b.quantity > 0.0 => (b.quantity, b.weighted_cost),
+            _ => (position.quantity, position.entry_price),
         };
+        let realized_pnl_to_date = ledger.map_or(0.0, |l| l.realized_pnl);
+        total_realized_pnl_lots += realized_pnl_to_date;
+
+        // Partial-close breakdown (see `Position::open_quantity`/`closed_quantity`):
+        // a position scaled out of via `Config::tp_levels` has already banked realized
+        // P&L on the closed portion (`position.realized_pnl`) while the rest stays open
+        // at the original entry basis, so report both instead of one all-or-nothing view.
+        let open_qty = position.open_quantity();
+        let closed_qty = position.closed_quantity();
+        let unrealized_on_open = position.unrealized_pnl(current_price);
+
+        // Exact money math via `Decimal` rather than `f64`, so summing many positions'
+        // entry/current value can't drift from the real accounting.
+        let quantity = decimal_from_f64(basis_quantity).unwrap_or_default();
+        let entry_price = decimal_from_f64(weighted_cost).unwrap_or_default();
+        let current_price_decimal = decimal_from_f64(current_price).unwrap_or_default();
 
-        let entry_value = position.quantity * position.entry_price;
-        let current_value = position.quantity * current_price;
+        let entry_value = quantity * entry_price;
+        let current_value = quantity * current_price_decimal;
         let unrealized_pnl = current_value - entry_value;
-        let pnl_percent = (current_price - position.entry_price) / position.entry_price * 100.0;
+        let pnl_percent = (current_price - weighted_cost) / weighted_cost * 100.0;
 
         // Calculate time held
         let entry_time = chrono::DateTime::parse_from_rfc3339(&position.entry_time)
@@ -583,15 +896,56 @@ async fn get_portfolio_with_pnl(env: &Env) -> std::result::Result<serde_json::Va
         total_current_value += current_value;
         total_unrealized_pnl += unrealized_pnl;
 
+        // Slippage-adjusted liquidation estimate (see `crate::liquidation`): walks the
+        // live bid side of the order book instead of pricing the whole position at the
+        // mark/last price, which overstates what a large or illiquid position could
+        // actually be closed at. Falls back to the mark price with zero slippage if the
+        // book fetch fails, matching `get_price`'s own fallback above.
+        let liquidation_estimate = match client.get_product_book(&position.symbol, 50).await {
+            Ok(book) => liquidation::liquidation_price(&book.bids, basis_quantity, current_price, config.max_liquidation_slippage_percent),
+            Err(_) => liquidation::LiquidationEstimate { weighted_price: current_price, slippage_percent: 0.0 },
+        };
+        let liquidation_value = decimal_from_f64(basis_quantity * liquidation_estimate.weighted_price).unwrap_or_default();
+        total_liquidation_value += liquidation_value;
+
+        // Normalize this position's dollar-value figures into `config.base_currency`
+        // (see `crate::fx`) - per-unit prices below stay USD since that's the quote
+        // currency of the trading pair itself, not a reportable cash balance.
+        let base = config.base_currency.as_str();
+        let entry_value_base = exchange_rates.convert(entry_value.to_f64().unwrap_or(0.0), "USD", base);
+        let current_value_base = exchange_rates.convert(current_value.to_f64().unwrap_or(0.0), "USD", base);
+        let unrealized_pnl_base = exchange_rates.convert(unrealized_pnl.to_f64().unwrap_or(0.0), "USD", base);
+        let realized_pnl_to_date_base = exchange_rates.convert(realized_pnl_to_date, "USD", base);
+        let realized_on_closed_base = exchange_rates.convert(position.realized_pnl, "USD", base);
+        let unrealized_on_open_base = exchange_rates.convert(unrealized_on_open, "USD", base);
+        let liquidation_value_base = exchange_rates.convert(liquidation_value.to_f64().unwrap_or(0.0), "USD", base);
+
         positions_with_pnl.push(serde_json::json!({
             "symbol": position.symbol,
             "quantity": format!("{:.4}", position.quantity),
             "entry_price": format!("${:.4}", position.entry_price),
             "current_price": format!("${:.4}", current_price),
-            "entry_value": format!("${:.2}", entry_value),
-            "current_value": format!("${:.2}", current_value),
-            "unrealized_pnl": format!("{}{:.2}", if unrealized_pnl >= 0.0 { "+$" } else { "-$" }, unrealized_pnl.abs()),
+            "entry_value": format_currency(entry_value_base, base),
+            "current_value": format_currency(current_value_base, base),
+            "unrealized_pnl": format!("{}{}", if unrealized_pnl_base >= 0.0 { "+" } else { "" }, format_currency(unrealized_pnl_base, base)),
             "pnl_percent": format!("{}{:.2}%", if pnl_percent >= 0.0 { "+" } else { "" }, pnl_percent),
+            // Lot-ledger audit trail (see `crate::lots::LotLedger`): the weighted cost
+            // basis remaining open lots were actually bought at, and realized P&L this
+            // symbol has booked to date - both derived from recorded fills rather than
+            // a single blended `total_invested`/`total_pnl` figure.
+            "cost_basis": format!("${weighted_cost:.4}"),
+            "realized_pnl_to_date": format!("{}{}", if realized_pnl_to_date_base >= 0.0 { "+" } else { "" }, format_currency(realized_pnl_to_date_base, base)),
+            // Partial-close breakdown (see `Position::open_quantity`): how much of this
+            // position is still open vs. already scaled out of, and the booked gain on
+            // the closed portion vs. the live exposure on what's left.
+            "open_qty": format!("{:.4}", open_qty),
+            "closed_qty": format!("{:.4}", closed_qty),
+            "realized_on_closed": format!("{}{}", if realized_on_closed_base >= 0.0 { "+" } else { "" }, format_currency(realized_on_closed_base, base)),
+            "unrealized_on_open": format!("{}{}", if unrealized_on_open_base >= 0.0 { "+" } else { "" }, format_currency(unrealized_on_open_base, base)),
+            // Slippage-adjusted exit estimate (see `crate::liquidation`), alongside the
+            // mark-to-market `current_value` above.
+            "liquidation_value": format_currency(liquidation_value_base, base),
+            "liquidation_slippage_percent": format!("{:.2}%", liquidation_estimate.slippage_percent),
             "status": if pnl_percent > 0.0 { "🟢 PROFIT" } else if pnl_percent < -0.5 { "🔴 LOSS" } else { "⚪ FLAT" },
             "hours_held": format!("{:.1}h", hours_held),
             "max_hold": format!("{}h", config.max_position_age_hours),
@@ -602,20 +956,33 @@ async fn get_portfolio_with_pnl(env: &Env) -> std::result::Result<serde_json::Va
         }));
     }
 
-    // Get USD + USDC balance (both count as available cash)
-    let usd_balance = match client.get_accounts().await {
-        Ok(accounts) => accounts
-            .accounts
-            .iter()
-            .filter(|a| a.currency == "USD" || a.currency == "USDC")
-            .filter_map(|a| a.available_balance.value.parse::<f64>().ok())
-            .sum(),
-        Err(_) => 0.0,
-    };
+    // Every non-zero-balance account (see `get_all_balances`), not just USD/USDC -
+    // a EUR, GBP, or BTC-quoted cash balance now counts toward `total_portfolio`
+    // instead of being silently dropped. Each is converted into `config.base_currency`
+    // via the rate table fetched above before summing. Cached (see
+    // `crate::price_cache`) and, on a failed live fetch, served from the last cached
+    // snapshot - flagged stale - rather than collapsing to a `0.0` balance.
+    let base = config.base_currency.as_str();
+    let balances_cached = price_cache::get_or_fetch(env, "balances", "all", BALANCE_CACHE_TTL_SECONDS, || client.get_all_balances())
+        .await
+        .unwrap_or(price_cache::Cached { value: Vec::new(), age_seconds: 0, stale: true });
+    let cash_balance_base: f64 = balances_cached
+        .value
+        .iter()
+        .map(|(currency, amount)| exchange_rates.convert(*amount, currency, base))
+        .sum();
+
+    let total_current_value_base = exchange_rates.convert(total_current_value.to_f64().unwrap_or(0.0), "USD", base);
+    let total_invested_base = exchange_rates.convert(total_invested.to_f64().unwrap_or(0.0), "USD", base);
+    let total_unrealized_pnl_base = exchange_rates.convert(total_unrealized_pnl.to_f64().unwrap_or(0.0), "USD", base);
+    let total_liquidation_value_base = exchange_rates.convert(total_liquidation_value.to_f64().unwrap_or(0.0), "USD", base);
+    let realized_pnl_base = exchange_rates.convert(state.total_pnl.to_f64().unwrap_or(0.0), "USD", base);
+    let total_realized_pnl_lots_base = exchange_rates.convert(total_realized_pnl_lots, "USD", base);
 
-    let total_portfolio = usd_balance + total_current_value;
-    let total_pnl_percent = if total_invested > 0.0 {
-        (total_unrealized_pnl / total_invested) * 100.0
+    let total_portfolio = cash_balance_base + total_current_value_base;
+    let total_portfolio_liquidation = cash_balance_base + total_liquidation_value_base;
+    let total_pnl_percent = if total_invested_base > 0.0 {
+        (total_unrealized_pnl_base / total_invested_base) * 100.0
     } else {
         0.0
     };
@@ -623,15 +990,39 @@ async fn get_portfolio_with_pnl(env: &Env) -> std::result::Result<serde_json::Va
     Ok(serde_json::json!({
         "timestamp": chrono::Utc::now().to_rfc3339(),
         "summary": {
-            "usd_balance": format!("${:.2}", usd_balance),
-            "positions_value": format!("${:.2}", total_current_value),
-            "total_portfolio": format!("${:.2}", total_portfolio),
-            "total_invested": format!("${:.2}", total_invested),
-            "unrealized_pnl": format!("{}{:.2}", if total_unrealized_pnl >= 0.0 { "+$" } else { "-$" }, total_unrealized_pnl.abs()),
+            "base_currency": config.base_currency,
+            "cash_balance": format_currency(cash_balance_base, base),
+            "positions_value": format_currency(total_current_value_base, base),
+            "total_portfolio": format_currency(total_portfolio, base),
+            "total_invested": format_currency(total_invested_base, base),
+            "unrealized_pnl": format!("{}{}", if total_unrealized_pnl_base >= 0.0 { "+" } else { "" }, format_currency(total_unrealized_pnl_base, base)),
             "pnl_percent": format!("{}{:.2}%", if total_pnl_percent >= 0.0 { "+" } else { "" }, total_pnl_percent),
-            "realized_pnl": format!("${:.2}", state.total_pnl),
+            "realized_pnl": format_currency(realized_pnl_base, base),
+            // Sum of every symbol's lot-ledger `realized_pnl` (see `crate::lots`),
+            // covering currently-open and fully-closed symbols alike - an auditable
+            // cross-check against the blended `realized_pnl` above.
+            "realized_pnl_lots": format!("{}{}", if total_realized_pnl_lots_base >= 0.0 { "+" } else { "" }, format_currency(total_realized_pnl_lots_base, base)),
             "total_trades": state.total_trades,
+            // Slippage-adjusted liquidation value of the open positions (see
+            // `crate::liquidation`), and the portfolio total it implies - the downside
+            // case next to `positions_value`/`total_portfolio`'s optimistic mark price.
+            "liquidation_value": format_currency(total_liquidation_value_base, base),
+            "total_portfolio_liquidation": format_currency(total_portfolio_liquidation, base),
         },
         "positions": positions_with_pnl,
+        // Per-field cache age (see `crate::price_cache`), so a consumer can tell a
+        // live number from one served from the last cached snapshot after a Coinbase
+        // fetch failed, instead of having every figure look equally authoritative.
+        "data_freshness": {
+            "prices": price_freshness,
+            "balances": {
+                "age_seconds": balances_cached.age_seconds,
+                "stale": balances_cached.stale,
+            },
+            "exchange_rates": {
+                "age_seconds": exchange_rates_cached.age_seconds,
+                "stale": exchange_rates_cached.stale,
+            },
+        },
     }))
 }
@@ -0,0 +1,111 @@
+//! Slippage-adjusted liquidation value
+//!
+//! `/api/portfolio`'s `positions_value`/`total_portfolio` price every open position
+//! at the last/mid price (`get_price`), which is what the book could close at only
+//! for a trade small enough not to move it. `liquidation_price` instead walks a
+//! position's symbol's live bid levels (`CoinbaseClient::get_product_book`) top-down,
+//! consuming size until the full position quantity is accounted for, to get a
+//! volume-weighted exit price. On a thin book - not enough depth, or a price so far
+//! down the book it implies more slippage than `Config::max_liquidation_slippage_percent`
+//! allows - the remaining unfilled quantity is priced at that bound instead of the raw
+//! book-implied price, so a single illiquid position can't report an unbounded loss.
+
+use crate::client::BookLevel;
+
+/// Volume-weighted liquidation estimate for one position.
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidationEstimate {
+    /// Volume-weighted exit price across the full position quantity
+    pub weighted_price: f64,
+    /// Implied slippage vs `reference_price`, as a percent (always `<= max_slippage_percent`)
+    pub slippage_percent: f64,
+}
+
+/// Walk `bids` (best-first) top-down to fill `quantity`, capping the price any unit
+/// can be sold at to `reference_price * (1 - max_slippage_percent / 100)`. Depth the
+/// book doesn't have (or levels worse than the cap) are treated as filled at that
+/// floor price, so the result is always defined even against an empty or thin book.
+pub fn liquidation_price(bids: &[BookLevel], quantity: f64, reference_price: f64, max_slippage_percent: f64) -> LiquidationEstimate {
+    if quantity <= 0.0 || reference_price <= 0.0 {
+        return LiquidationEstimate { weighted_price: reference_price, slippage_percent: 0.0 };
+    }
+
+    let floor_price = reference_price * (1.0 - max_slippage_percent / 100.0);
+    let mut remaining = quantity;
+    let mut proceeds = 0.0;
+
+    for level in bids {
+        if remaining <= 0.0 {
+            break;
+        }
+        let effective_price = level.price.max(floor_price);
+        let take = remaining.min(level.size);
+        proceeds += take * effective_price;
+        remaining -= take;
+    }
+    if remaining > 0.0 {
+        proceeds += remaining * floor_price;
+    }
+
+    let weighted_price = proceeds / quantity;
+    let slippage_percent = (reference_price - weighted_price) / reference_price * 100.0;
+    LiquidationEstimate { weighted_price, slippage_percent }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: f64, size: f64) -> BookLevel {
+        BookLevel { price, size }
+    }
+
+    #[test]
+    fn test_fills_entirely_within_top_of_book() {
+        let bids = vec![level(100.0, 5.0)];
+        let estimate = liquidation_price(&bids, 2.0, 100.0, 5.0);
+        assert_eq!(estimate.weighted_price, 100.0);
+        assert_eq!(estimate.slippage_percent, 0.0);
+    }
+
+    #[test]
+    fn test_walks_multiple_levels() {
+        let bids = vec![level(100.0, 1.0), level(99.0, 1.0)];
+        let estimate = liquidation_price(&bids, 2.0, 100.0, 5.0);
+        // 1 @ 100 + 1 @ 99 = 199 / 2 = 99.5
+        assert_eq!(estimate.weighted_price, 99.5);
+        assert!((estimate.slippage_percent - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_thin_book_caps_at_max_slippage() {
+        let bids = vec![level(100.0, 1.0)];
+        // Selling 2.0 against only 1.0 of depth at 100 - the rest floors at 5% below
+        let estimate = liquidation_price(&bids, 2.0, 100.0, 5.0);
+        // 1 @ 100 + 1 @ 95 = 195 / 2 = 97.5
+        assert_eq!(estimate.weighted_price, 97.5);
+        assert!(estimate.slippage_percent <= 5.0 + 1e-9);
+    }
+
+    #[test]
+    fn test_empty_book_fills_entirely_at_floor() {
+        let estimate = liquidation_price(&[], 1.0, 100.0, 5.0);
+        assert_eq!(estimate.weighted_price, 95.0);
+        assert!((estimate.slippage_percent - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_book_levels_worse_than_bound_are_capped_not_used_raw() {
+        let bids = vec![level(80.0, 5.0)];
+        let estimate = liquidation_price(&bids, 1.0, 100.0, 5.0);
+        // Raw book price (80) implies 20% slippage, far past the 5% bound - floored at 95
+        assert_eq!(estimate.weighted_price, 95.0);
+    }
+
+    #[test]
+    fn test_zero_quantity_returns_reference_price() {
+        let estimate = liquidation_price(&[level(100.0, 1.0)], 0.0, 100.0, 5.0);
+        assert_eq!(estimate.weighted_price, 100.0);
+        assert_eq!(estimate.slippage_percent, 0.0);
+    }
+}
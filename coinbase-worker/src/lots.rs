@@ -0,0 +1,310 @@
+//! Per-asset lot accounting (FIFO / average-cost) for realized and unrealized PnL
+//!
+//! The portfolio summary used to derive `unrealized_pnl` from a single blended
+//! `total_invested` figure and report `realized_pnl` straight from
+//! `TradingStateData::total_pnl`, which collapses every buy into one number and
+//! can't explain where gains came from. `LotLedger` keeps a per-symbol queue of
+//! open lots (or, in average-cost mode, a single blended lot) recording every
+//! fill's quantity/price/fee/timestamp, so both the realized breakdown on a sell
+//! and the remaining open-lot cost basis can be reconstructed and audited instead
+//! of trusting an opaque running total.
+//!
+//! Lots are keyed off *position* side, not raw order side: opening a short means
+//! the opening fill is a Sell and the covering fill is a Buy, the reverse of a
+//! long. `record_open`/`record_close` take the position's economic role (open vs
+//! close) rather than `OrderSide` directly, so callers (see `run_trading_cycle` in
+//! lib.rs) don't need to special-case which raw order side means what per
+//! `PositionSide` - they just report what the fill did to the position.
+
+use crate::types::PositionSide;
+use serde::{Deserialize, Serialize};
+
+/// Cost-basis method for matching sells against open lots (see
+/// `Config::cost_basis_method`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    /// Pop open lots oldest-first when matching a sell.
+    #[default]
+    Fifo,
+    /// Collapse all open lots into one running `(total_qty, total_cost)` average.
+    AverageCost,
+}
+
+/// One open (partially or fully unmatched) opening fill - a buy for a long, a sell
+/// for a short.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub quantity: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub opened_at: String,
+}
+
+/// Result of matching a closing fill against open lots: the realized P&L (fees already
+/// netted out) for the matched quantity.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RealizedSale {
+    pub quantity: f64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub fee: f64,
+    pub realized_pnl: f64,
+}
+
+/// Remaining open-lot position: total quantity held and its weighted cost basis,
+/// for computing unrealized PnL and surfacing an auditable basis.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct OpenCostBasis {
+    pub quantity: f64,
+    pub weighted_cost: f64,
+}
+
+/// Per-symbol queue of open lots, the method used to match sells against them,
+/// the side those open lots belong to (`None` when flat), and a running total of
+/// realized P&L booked so far.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotLedger {
+    pub method: CostBasisMethod,
+    pub lots: Vec<Lot>,
+    pub realized_pnl: f64,
+    /// Side of the currently open lots, set by the fill that opens them and
+    /// cleared once the book empties back out. `#[serde(default)]` so ledgers
+    /// persisted before shorts were tracked deserialize as `None` (flat) rather
+    /// than failing.
+    #[serde(default)]
+    pub side: Option<PositionSide>,
+}
+
+impl LotLedger {
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self { method, lots: Vec::new(), realized_pnl: 0.0, side: None }
+    }
+
+    /// Record a fill that opens (or adds to) a position, as either side. In
+    /// `AverageCost` mode this collapses the book into a single blended lot so a
+    /// later close still has just one lot to draw down proportionally.
+    pub fn record_open(&mut self, side: PositionSide, quantity: f64, price: f64, fee: f64, opened_at: &str) {
+        if quantity <= 0.0 {
+            return;
+        }
+        if self.lots.is_empty() {
+            self.side = Some(side);
+        }
+
+        match self.method {
+            CostBasisMethod::Fifo => {
+                self.lots.push(Lot { quantity, price, fee, opened_at: opened_at.to_string() });
+            }
+            CostBasisMethod::AverageCost => {
+                let existing_qty: f64 = self.lots.iter().map(|l| l.quantity).sum();
+                let existing_cost: f64 = self.lots.iter().map(|l| l.quantity * l.price).sum();
+                let existing_fee: f64 = self.lots.iter().map(|l| l.fee).sum();
+
+                let total_qty = existing_qty + quantity;
+                let total_cost = existing_cost + quantity * price;
+                let total_fee = existing_fee + fee;
+
+                self.lots.clear();
+                self.lots.push(Lot {
+                    quantity: total_qty,
+                    price: total_cost / total_qty,
+                    fee: total_fee,
+                    opened_at: opened_at.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Match a fill that closes (or trims) the open position against its lots -
+    /// FIFO pops oldest-first; average-cost draws down the single blended lot
+    /// proportionally. Returns `None` if there are no open lots to close against.
+    /// Updates `realized_pnl` with the result. Realized P&L is `exit - entry` for
+    /// a long close but `entry - exit` for a short cover, per the side recorded by
+    /// the fill that opened these lots - a short only profits when it's covered
+    /// below where it was opened.
+    pub fn record_close(&mut self, quantity: f64, price: f64, fee: f64) -> Option<RealizedSale> {
+        if quantity <= 0.0 || self.lots.is_empty() {
+            return None;
+        }
+        let side = self.side.unwrap_or_default();
+
+        let mut remaining = quantity;
+        let mut cost_basis = 0.0;
+        let mut lot_fee = 0.0;
+
+        while remaining > 1e-12 && !self.lots.is_empty() {
+            let lot = &mut self.lots[0];
+            let matched = remaining.min(lot.quantity);
+            let lot_fee_share = lot.fee * (matched / lot.quantity);
+
+            cost_basis += matched * lot.price;
+            lot_fee += lot_fee_share;
+
+            lot.quantity -= matched;
+            lot.fee -= lot_fee_share;
+            remaining -= matched;
+
+            if lot.quantity <= 1e-12 {
+                self.lots.remove(0);
+            }
+        }
+
+        if self.lots.is_empty() {
+            self.side = None;
+        }
+
+        let matched_quantity = quantity - remaining;
+        let proceeds = matched_quantity * price;
+        let total_fee = lot_fee + fee;
+        let realized_pnl = match side {
+            PositionSide::Long => proceeds - cost_basis - total_fee,
+            PositionSide::Short => cost_basis - proceeds - total_fee,
+        };
+
+        self.realized_pnl += realized_pnl;
+
+        Some(RealizedSale { quantity: matched_quantity, proceeds, cost_basis, fee: total_fee, realized_pnl })
+    }
+
+    /// Remaining open quantity and its weighted average cost (price only - fees
+    /// are netted into `realized_pnl` at close time, not folded into the basis).
+    pub fn open_cost_basis(&self) -> OpenCostBasis {
+        let quantity: f64 = self.lots.iter().map(|l| l.quantity).sum();
+        if quantity <= 0.0 {
+            return OpenCostBasis::default();
+        }
+        let cost: f64 = self.lots.iter().map(|l| l.quantity * l.price).sum();
+        OpenCostBasis { quantity, weighted_cost: cost / quantity }
+    }
+
+    /// Unrealized PnL for the remaining open lots at `current_price` - a short's
+    /// unrealized gain runs the opposite direction of a long's.
+    pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
+        let basis = self.open_cost_basis();
+        match self.side.unwrap_or_default() {
+            PositionSide::Long => basis.quantity * (current_price - basis.weighted_cost),
+            PositionSide::Short => basis.quantity * (basis.weighted_cost - current_price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_realizes_oldest_lot_first() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Long, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        ledger.record_open(PositionSide::Long, 1.0, 150.0, 0.0, "2024-01-02T00:00:00Z");
+
+        let sale = ledger.record_close(1.0, 200.0, 0.0).expect("open lots to close against");
+        assert_eq!(sale.cost_basis, 100.0);
+        assert_eq!(sale.realized_pnl, 100.0);
+        assert_eq!(ledger.lots.len(), 1);
+        assert_eq!(ledger.lots[0].price, 150.0);
+    }
+
+    #[test]
+    fn test_fifo_partial_fill_splits_a_lot() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Long, 2.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+
+        let sale = ledger.record_close(0.5, 200.0, 0.0).expect("open lot to close against");
+        assert_eq!(sale.cost_basis, 50.0);
+        assert_eq!(ledger.lots[0].quantity, 1.5);
+    }
+
+    #[test]
+    fn test_fifo_matches_across_multiple_lots() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Long, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        ledger.record_open(PositionSide::Long, 1.0, 120.0, 0.0, "2024-01-02T00:00:00Z");
+
+        let sale = ledger.record_close(1.5, 150.0, 0.0).expect("open lots to close against");
+        // 1 lot @ 100 + 0.5 @ 120 = 160 cost basis
+        assert_eq!(sale.cost_basis, 160.0);
+        assert_eq!(ledger.lots.len(), 1);
+        assert_eq!(ledger.lots[0].quantity, 0.5);
+    }
+
+    #[test]
+    fn test_average_cost_blends_lots_and_decrements_proportionally() {
+        let mut ledger = LotLedger::new(CostBasisMethod::AverageCost);
+        ledger.record_open(PositionSide::Long, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        ledger.record_open(PositionSide::Long, 1.0, 200.0, 0.0, "2024-01-02T00:00:00Z");
+        // Average cost now 150.0 over 2.0 units
+        assert_eq!(ledger.lots.len(), 1);
+        assert_eq!(ledger.open_cost_basis().weighted_cost, 150.0);
+
+        let sale = ledger.record_close(1.0, 250.0, 0.0).expect("open lot to close against");
+        assert_eq!(sale.cost_basis, 150.0);
+        assert_eq!(sale.realized_pnl, 100.0);
+        assert_eq!(ledger.open_cost_basis().quantity, 1.0);
+        assert_eq!(ledger.open_cost_basis().weighted_cost, 150.0);
+    }
+
+    #[test]
+    fn test_record_close_with_no_open_lots_returns_none() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        assert!(ledger.record_close(1.0, 100.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_fees_reduce_realized_pnl_and_accumulate() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Long, 1.0, 100.0, 1.0, "2024-01-01T00:00:00Z");
+
+        let sale = ledger.record_close(1.0, 110.0, 1.0).expect("open lot to close against");
+        // proceeds 110 - cost 100 - (lot fee 1 + close fee 1) = 8
+        assert_eq!(sale.realized_pnl, 8.0);
+        assert_eq!(ledger.realized_pnl, 8.0);
+    }
+
+    #[test]
+    fn test_unrealized_pnl_uses_weighted_open_cost() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Long, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        ledger.record_open(PositionSide::Long, 1.0, 200.0, 0.0, "2024-01-02T00:00:00Z");
+
+        // Weighted cost = 150.0 over 2.0 units; at 180 current price, unrealized = 2*(180-150)=60
+        assert_eq!(ledger.unrealized_pnl(180.0), 60.0);
+    }
+
+    #[test]
+    fn test_short_open_is_a_sell_and_close_is_a_covering_buy() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        // Opening a short is a Sell fill at the entry price.
+        ledger.record_open(PositionSide::Short, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        assert_eq!(ledger.side, Some(PositionSide::Short));
+
+        // Covering it is a Buy fill; profit is entry (100) - exit (80), not the
+        // other way around like a long's close would be.
+        let sale = ledger.record_close(1.0, 80.0, 0.0).expect("open short lot to cover");
+        assert_eq!(sale.realized_pnl, 20.0);
+        assert_eq!(ledger.realized_pnl, 20.0);
+        assert!(ledger.lots.is_empty());
+        assert_eq!(ledger.side, None);
+    }
+
+    #[test]
+    fn test_short_unrealized_pnl_runs_opposite_a_long() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Short, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+
+        // Price dropped to 80 since entry: a short is up 20 here, where a long
+        // would be down 20.
+        assert_eq!(ledger.unrealized_pnl(80.0), 20.0);
+    }
+
+    #[test]
+    fn test_record_open_on_empty_ledger_after_short_closed_tracks_new_side() {
+        let mut ledger = LotLedger::new(CostBasisMethod::Fifo);
+        ledger.record_open(PositionSide::Short, 1.0, 100.0, 0.0, "2024-01-01T00:00:00Z");
+        ledger.record_close(1.0, 80.0, 0.0);
+
+        // Ledger is flat again; the next open fill can be either side.
+        ledger.record_open(PositionSide::Long, 1.0, 90.0, 0.0, "2024-01-02T00:00:00Z");
+        assert_eq!(ledger.side, Some(PositionSide::Long));
+    }
+}
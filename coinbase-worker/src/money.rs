@@ -0,0 +1,105 @@
+//! Fixed-point money handling
+//!
+//! Every monetary value Coinbase sends over the wire (`Balance.value`, `Product.price`,
+//! candle OHLC, increments) is a `String`, and this crate's internal math is `f64` for
+//! everything derived from it. `f64` is fine for strategy math (it's all relative
+//! comparisons), but round-tripping it back into an order size can silently lose enough
+//! precision to violate a product's `base_increment`/`quote_increment` and get the order
+//! rejected. `rust_decimal::Decimal` parses the API's strings losslessly and rounds
+//! order sizes exactly, so that's what order placement uses right before serialization;
+//! wire structs stay `String` for serde compatibility and everywhere else keeps using
+//! `f64` as before.
+
+use crate::error::{Result, TradingError};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// Parse a Coinbase API money string (e.g. a `Balance.value` or `Product.price`) into
+/// a `Decimal`, losslessly
+pub fn parse_money(raw: &str) -> Result<Decimal> {
+    Decimal::from_str(raw).map_err(|e| TradingError::CoinbaseApi(format!("Invalid money value {raw:?}: {e}")))
+}
+
+/// Bridge an `f64` (strategy/trading math) into a `Decimal` (order sizing,
+/// accumulator fields), via its string representation rather than `Decimal::from_f64`
+/// so the exact digits shown, not the binary float's nearest rational, are what get
+/// rounded/accumulated.
+pub fn decimal_from_f64(value: f64) -> Result<Decimal> {
+    Decimal::from_str(&value.to_string()).map_err(|e| TradingError::CoinbaseApi(format!("Invalid amount {value}: {e}")))
+}
+
+/// Serializes a `Decimal` as an exact decimal string (e.g. `"50000.00000001"`) instead
+/// of a JSON number, so fields that accumulate over many additions - like
+/// `TradingStateData::total_pnl`, persisted to KV across thousands of trading cycles -
+/// round-trip without the float-precision artifacts a numeric JSON encoding would
+/// reintroduce.
+pub mod decimal_str {
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        value.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Decimal, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Decimal::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Round `value` down to the nearest multiple of `increment` (a product's
+/// `base_increment`/`quote_increment`). Rounds toward zero rather than to nearest,
+/// since rounding up could size an order past what the account can cover.
+pub fn round_to_increment(value: Decimal, increment: Decimal) -> Decimal {
+    if increment.is_zero() {
+        return value;
+    }
+    (value / increment).trunc() * increment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_money() {
+        assert_eq!(parse_money("123.456").unwrap(), Decimal::new(123456, 3));
+        assert!(parse_money("not-a-number").is_err());
+    }
+
+    #[test]
+    fn test_round_to_increment_truncates_down() {
+        let value = Decimal::from_str("1.23456789").unwrap();
+        let increment = Decimal::from_str("0.00000001").unwrap();
+        assert_eq!(round_to_increment(value, increment), value);
+
+        let increment = Decimal::from_str("0.01").unwrap();
+        assert_eq!(round_to_increment(value, increment), Decimal::from_str("1.23").unwrap());
+    }
+
+    #[test]
+    fn test_round_to_increment_zero_increment_is_noop() {
+        let value = Decimal::from_str("1.23456789").unwrap();
+        assert_eq!(round_to_increment(value, Decimal::ZERO), value);
+    }
+
+    #[test]
+    fn test_decimal_from_f64_matches_displayed_digits() {
+        assert_eq!(decimal_from_f64(60.0).unwrap(), Decimal::from(60));
+        assert_eq!(decimal_from_f64(-30.5).unwrap(), Decimal::from_str("-30.5").unwrap());
+    }
+
+    #[test]
+    fn test_decimal_str_round_trips_as_exact_string() {
+        let value = Decimal::from_str("50000.00000001").unwrap();
+        let json = serde_json::to_string(&SerdeWrapper(value)).unwrap();
+        assert_eq!(json, "\"50000.00000001\"");
+
+        let round_tripped: SerdeWrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.0, value);
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct SerdeWrapper(#[serde(with = "decimal_str")] Decimal);
+}
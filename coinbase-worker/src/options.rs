@@ -0,0 +1,173 @@
+//! Options legs and portfolio delta-hedging
+//!
+//! Coinbase's own order book is spot-only, so this module doesn't place option
+//! orders - it models the Greeks of option legs assumed to be held elsewhere (or
+//! added here for internal risk tracking) so `TradingStateData::net_delta` can fold
+//! them into the same portfolio-delta number as ordinary spot positions, and
+//! `hedge_order` can say how much spot to buy/sell to flatten it.
+//!
+//! Greeks are priced directly off Black-Scholes rather than pulled from an exchange,
+//! using the Abramowitz-Stegun approximation for the standard normal CDF since this
+//! crate has no `erf` in its dependency set.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A single option leg: enough contract metadata to price its Black-Scholes Greeks
+/// against a spot price fed in at evaluation time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionLeg {
+    /// Underlying spot symbol (e.g. "BTC-USD"), used to look up a spot price in
+    /// `TradingStateData::net_delta`'s `spot_prices` map.
+    pub symbol: String,
+    /// Contracts held, signed: positive is long the leg, negative is short it.
+    pub quantity: f64,
+    pub strike: f64,
+    /// RFC 3339 expiry instant.
+    pub expiry: String,
+    pub is_call: bool,
+    /// Implied volatility, annualized (e.g. `0.6` for 60%).
+    pub implied_vol: f64,
+}
+
+impl OptionLeg {
+    /// Year fraction from `now` to this leg's expiry, floored just above zero so an
+    /// expired-but-not-yet-settled leg still prices instead of dividing by zero.
+    fn year_fraction(&self, now: DateTime<Utc>) -> f64 {
+        let expiry = DateTime::parse_from_rfc3339(&self.expiry)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or(now);
+        let seconds = (expiry - now).num_seconds() as f64;
+        (seconds / (365.25 * 24.0 * 3600.0)).max(1.0 / (365.25 * 24.0))
+    }
+
+    fn d1_d2(&self, spot: f64, now: DateTime<Utc>, risk_free_rate: f64) -> (f64, f64) {
+        let t = self.year_fraction(now);
+        let sigma_sqrt_t = self.implied_vol * t.sqrt();
+        let d1 = ((spot / self.strike).ln() + (risk_free_rate + self.implied_vol.powi(2) / 2.0) * t) / sigma_sqrt_t;
+        let d2 = d1 - sigma_sqrt_t;
+        (d1, d2)
+    }
+
+    /// Black-Scholes delta: `N(d1)` for a call, `N(d1) - 1` for a put.
+    pub fn delta(&self, spot: f64, now: DateTime<Utc>, risk_free_rate: f64) -> f64 {
+        let (d1, _) = self.d1_d2(spot, now, risk_free_rate);
+        if self.is_call {
+            norm_cdf(d1)
+        } else {
+            norm_cdf(d1) - 1.0
+        }
+    }
+
+    /// Black-Scholes gamma: identical for calls and puts.
+    pub fn gamma(&self, spot: f64, now: DateTime<Utc>, risk_free_rate: f64) -> f64 {
+        let (d1, _) = self.d1_d2(spot, now, risk_free_rate);
+        let t = self.year_fraction(now);
+        norm_pdf(d1) / (spot * self.implied_vol * t.sqrt())
+    }
+
+    /// Black-Scholes vega (sensitivity to a 1.0 = 100-point absolute move in implied
+    /// vol): identical for calls and puts.
+    pub fn vega(&self, spot: f64, now: DateTime<Utc>, risk_free_rate: f64) -> f64 {
+        let (d1, _) = self.d1_d2(spot, now, risk_free_rate);
+        let t = self.year_fraction(now);
+        spot * norm_pdf(d1) * t.sqrt()
+    }
+
+    /// Black-Scholes theta (sensitivity to one year of time decay; divide by 365 for
+    /// a per-day figure).
+    pub fn theta(&self, spot: f64, now: DateTime<Utc>, risk_free_rate: f64) -> f64 {
+        let (d1, d2) = self.d1_d2(spot, now, risk_free_rate);
+        let t = self.year_fraction(now);
+        let decay = -(spot * norm_pdf(d1) * self.implied_vol) / (2.0 * t.sqrt());
+        let carry = risk_free_rate * self.strike * (-risk_free_rate * t).exp();
+        if self.is_call {
+            decay - carry * norm_cdf(d2)
+        } else {
+            decay + carry * norm_cdf(-d2)
+        }
+    }
+}
+
+/// Standard normal CDF via the Abramowitz-Stegun erf approximation (formula 7.1.26,
+/// max error ~1.5e-7).
+fn norm_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Standard normal PDF.
+fn norm_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+fn erf(x: f64) -> f64 {
+    const A1: f64 = 0.254829592;
+    const A2: f64 = -0.284496736;
+    const A3: f64 = 1.421413741;
+    const A4: f64 = -1.453152027;
+    const A5: f64 = 1.061405429;
+    const P: f64 = 0.3275911;
+
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + P * x);
+    let poly = ((((A5 * t + A4) * t + A3) * t + A2) * t + A1) * t;
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(is_call: bool) -> OptionLeg {
+        OptionLeg {
+            symbol: "BTC-USD".to_string(),
+            quantity: 1.0,
+            strike: 50000.0,
+            expiry: "2024-02-01T00:00:00Z".to_string(),
+            is_call,
+            implied_vol: 0.6,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2024-01-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_norm_cdf_matches_known_values() {
+        assert!((norm_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((norm_cdf(1.959964) - 0.975).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_call_delta_between_zero_and_one() {
+        let delta = leg(true).delta(50000.0, now(), 0.0);
+        assert!(delta > 0.0 && delta < 1.0);
+    }
+
+    #[test]
+    fn test_put_delta_between_minus_one_and_zero() {
+        let delta = leg(false).delta(50000.0, now(), 0.0);
+        assert!(delta > -1.0 && delta < 0.0);
+    }
+
+    #[test]
+    fn test_deep_itm_call_delta_approaches_one() {
+        let delta = leg(true).delta(500000.0, now(), 0.0);
+        assert!(delta > 0.99);
+    }
+
+    #[test]
+    fn test_deep_otm_call_delta_approaches_zero() {
+        let delta = leg(true).delta(5000.0, now(), 0.0);
+        assert!(delta < 0.01);
+    }
+
+    #[test]
+    fn test_gamma_and_vega_are_positive() {
+        let l = leg(true);
+        assert!(l.gamma(50000.0, now(), 0.0) > 0.0);
+        assert!(l.vega(50000.0, now(), 0.0) > 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Pairlist pipeline - ranks/filters tradable symbols for entry scanning
+//!
+//! Modeled on freqtrade's pairlist handlers: a cycle's candidate symbols flow through
+//! a configurable chain of stages (volume ranking, price/spread bounds, blacklist)
+//! instead of always being the fixed `Config::symbols` watchlist. `StaticPairlist`
+//! keeps the original fixed-list behavior available as just another stage.
+
+use crate::config::Config;
+
+/// A tradable symbol and the raw stats a pairlist stage filters/ranks on.
+#[derive(Debug, Clone)]
+pub struct PairlistCandidate {
+    pub symbol: String,
+    pub price: f64,
+    pub volume_24h: f64,
+    /// Approximate spread, as a percent of price. Coinbase's public product list
+    /// doesn't expose live bid/ask, so this proxies off `quote_increment` (tighter
+    /// tick sizes generally track more liquid, tighter-spread pairs).
+    pub spread_percent: f64,
+}
+
+/// One stage of the pairlist pipeline: takes the candidates surviving prior stages
+/// and returns the ones that survive this one (filtering, ranking, or both).
+pub trait PairlistStage {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate>;
+}
+
+/// Restricts candidates to a fixed, hand-picked symbol list - the pre-dynamic-pairlist
+/// behavior of always scanning exactly `Config::symbols`.
+pub struct StaticPairlist {
+    pub symbols: Vec<String>,
+}
+
+impl PairlistStage for StaticPairlist {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate> {
+        candidates
+            .into_iter()
+            .filter(|c| self.symbols.contains(&c.symbol))
+            .collect()
+    }
+}
+
+/// Drops candidates below `min_volume_24h`, then ranks the rest by 24h quote volume
+/// (descending) and keeps only the top N.
+pub struct VolumeRanked {
+    pub min_volume_24h: f64,
+    pub top_n: usize,
+}
+
+impl PairlistStage for VolumeRanked {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate> {
+        let mut candidates: Vec<_> = candidates
+            .into_iter()
+            .filter(|c| c.volume_24h >= self.min_volume_24h)
+            .collect();
+        candidates.sort_by(|a, b| b.volume_24h.partial_cmp(&a.volume_24h).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.truncate(self.top_n);
+        candidates
+    }
+}
+
+/// Drops candidates whose price falls outside `[min_price, max_price]`.
+pub struct PriceFilter {
+    pub min_price: f64,
+    pub max_price: f64,
+}
+
+impl PairlistStage for PriceFilter {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate> {
+        candidates
+            .into_iter()
+            .filter(|c| c.price >= self.min_price && c.price <= self.max_price)
+            .collect()
+    }
+}
+
+/// Drops candidates whose spread exceeds `max_spread_percent`.
+pub struct SpreadFilter {
+    pub max_spread_percent: f64,
+}
+
+impl PairlistStage for SpreadFilter {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate> {
+        candidates
+            .into_iter()
+            .filter(|c| c.spread_percent <= self.max_spread_percent)
+            .collect()
+    }
+}
+
+/// Drops candidates on a fixed exclude list, regardless of how they rank.
+pub struct Blacklist {
+    pub symbols: Vec<String>,
+}
+
+impl PairlistStage for Blacklist {
+    fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<PairlistCandidate> {
+        candidates
+            .into_iter()
+            .filter(|c| !self.symbols.contains(&c.symbol))
+            .collect()
+    }
+}
+
+/// Runs candidates through an ordered chain of stages, returning the surviving
+/// symbols. Stage order matters: ranking before filtering changes which pairs a
+/// count-bounded stage (`VolumeRanked`) keeps.
+pub struct Pairlist {
+    stages: Vec<Box<dyn PairlistStage>>,
+}
+
+impl Pairlist {
+    pub fn new(stages: Vec<Box<dyn PairlistStage>>) -> Self {
+        Self { stages }
+    }
+
+    /// Build the dynamic chain from `Config`: `VolumeRanked` → `PriceFilter` →
+    /// `SpreadFilter` → `Blacklist`, in that order (see `Config::enable_dynamic_pairlist`).
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(vec![
+            Box::new(VolumeRanked {
+                min_volume_24h: config.pairlist_min_volume_usd,
+                top_n: config.pairlist_top_n,
+            }),
+            Box::new(PriceFilter {
+                min_price: config.pairlist_min_price,
+                max_price: config.pairlist_max_price,
+            }),
+            Box::new(SpreadFilter {
+                max_spread_percent: config.pairlist_max_spread_percent,
+            }),
+            Box::new(Blacklist {
+                symbols: config.pairlist_blacklist.clone(),
+            }),
+        ])
+    }
+
+    pub fn apply(&self, candidates: Vec<PairlistCandidate>) -> Vec<String> {
+        let mut candidates = candidates;
+        for stage in &self.stages {
+            candidates = stage.apply(candidates);
+        }
+        candidates.into_iter().map(|c| c.symbol).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(symbol: &str, price: f64, volume: f64, spread: f64) -> PairlistCandidate {
+        PairlistCandidate {
+            symbol: symbol.to_string(),
+            price,
+            volume_24h: volume,
+            spread_percent: spread,
+        }
+    }
+
+    #[test]
+    fn volume_ranked_keeps_top_n_descending() {
+        let candidates = vec![
+            candidate("A-USD", 10.0, 100.0, 0.1),
+            candidate("B-USD", 10.0, 500.0, 0.1),
+            candidate("C-USD", 10.0, 300.0, 0.1),
+        ];
+        let stage = VolumeRanked { min_volume_24h: 0.0, top_n: 2 };
+        let result = stage.apply(candidates);
+        assert_eq!(
+            result.iter().map(|c| c.symbol.as_str()).collect::<Vec<_>>(),
+            vec!["B-USD", "C-USD"]
+        );
+    }
+
+    #[test]
+    fn volume_ranked_drops_below_minimum() {
+        let candidates = vec![
+            candidate("THIN-USD", 10.0, 1_000.0, 0.1),
+            candidate("DEEP-USD", 10.0, 5_000_000.0, 0.1),
+        ];
+        let stage = VolumeRanked { min_volume_24h: 1_000_000.0, top_n: 10 };
+        let result = stage.apply(candidates);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].symbol, "DEEP-USD");
+    }
+
+    #[test]
+    fn price_filter_drops_out_of_band() {
+        let candidates = vec![
+            candidate("CHEAP-USD", 0.001, 100.0, 0.1),
+            candidate("MID-USD", 50.0, 100.0, 0.1),
+            candidate("EXPENSIVE-USD", 100_000.0, 100.0, 0.1),
+        ];
+        let stage = PriceFilter { min_price: 1.0, max_price: 10_000.0 };
+        let result = stage.apply(candidates);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].symbol, "MID-USD");
+    }
+
+    #[test]
+    fn spread_filter_drops_wide_spreads() {
+        let candidates = vec![
+            candidate("TIGHT-USD", 10.0, 100.0, 0.05),
+            candidate("WIDE-USD", 10.0, 100.0, 5.0),
+        ];
+        let stage = SpreadFilter { max_spread_percent: 1.0 };
+        let result = stage.apply(candidates);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].symbol, "TIGHT-USD");
+    }
+
+    #[test]
+    fn blacklist_excludes_named_symbols() {
+        let candidates = vec![
+            candidate("GOOD-USD", 10.0, 100.0, 0.1),
+            candidate("BANNED-USD", 10.0, 100.0, 0.1),
+        ];
+        let stage = Blacklist { symbols: vec!["BANNED-USD".to_string()] };
+        let result = stage.apply(candidates);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].symbol, "GOOD-USD");
+    }
+
+    #[test]
+    fn static_pairlist_restricts_to_fixed_symbols() {
+        let candidates = vec![
+            candidate("BTC-USD", 50_000.0, 1_000_000.0, 0.1),
+            candidate("RANDOM-USD", 1.0, 1_000_000.0, 0.1),
+        ];
+        let stage = StaticPairlist { symbols: vec!["BTC-USD".to_string()] };
+        let result = stage.apply(candidates);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].symbol, "BTC-USD");
+    }
+
+    #[test]
+    fn chain_applies_stages_in_order() {
+        let candidates = vec![
+            candidate("A-USD", 10.0, 1000.0, 0.1),
+            candidate("B-USD", 10.0, 900.0, 0.1),
+            candidate("BANNED-USD", 10.0, 800.0, 0.1),
+            candidate("LOW-USD", 10.0, 1.0, 0.1),
+        ];
+        let pairlist = Pairlist::new(vec![
+            Box::new(VolumeRanked { min_volume_24h: 0.0, top_n: 3 }),
+            Box::new(Blacklist { symbols: vec!["BANNED-USD".to_string()] }),
+        ]);
+        let result = pairlist.apply(candidates);
+        assert_eq!(result, vec!["A-USD".to_string(), "B-USD".to_string()]);
+    }
+}
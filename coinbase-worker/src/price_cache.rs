@@ -0,0 +1,100 @@
+//! Short-TTL cache for the price/balance lookups behind `/api/portfolio`
+//!
+//! Building the portfolio summary used to call `get_accounts()` and a `get_price` per
+//! position serially, with no cache at all - latency scaled with position count, and a
+//! single slow or failed Coinbase response either stalled the whole report or (for the
+//! balance branch) silently collapsed to `0.0`. `get_or_fetch` wraps any such lookup in
+//! a KV-backed cache (the same `STATE` binding `crate::ledger` and the trading state use):
+//! a value younger than its TTL is served straight from KV with no network call at
+//! all, and a failed live fetch falls back to the last cached value instead of an
+//! error or a zeroed-out balance - the caller just gets `stale: true` to say so.
+//!
+//! Cache entries are written with no KV-native expiration, since a "fall back to the
+//! last known value" design needs that value to still be there *after* it's gone
+//! stale - freshness here is judged purely from the stored `fetched_at` timestamp, not
+//! by whether KV has already deleted the key.
+
+use crate::error::{Result, TradingError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use worker::{kv::KvStore, Env};
+
+fn cache_key(prefix: &str, id: &str) -> String {
+    format!("price_cache_{prefix}_{id}")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry<T> {
+    value: T,
+    fetched_at: String,
+}
+
+fn age_seconds(fetched_at: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(fetched_at)
+        .map(|dt| (chrono::Utc::now() - dt.with_timezone(&chrono::Utc)).num_seconds())
+        .unwrap_or(i64::MAX)
+}
+
+/// A cached lookup's value plus how old it is and whether it came from a live fetch
+/// (`stale: false`, `age_seconds: 0`), a cached copy still within its TTL
+/// (`stale: false`, its real age), or a fallback to the last cached copy after a
+/// live fetch failed (`stale: true`).
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub value: T,
+    pub age_seconds: i64,
+    pub stale: bool,
+}
+
+fn open_kv(env: &Env) -> Result<KvStore> {
+    env.kv("STATE").map_err(|e| TradingError::Storage(format!("KV unavailable: {e}")))
+}
+
+/// Fetch `id`'s value under `prefix` (e.g. `("price", "BTC-USD")`), serving a cached
+/// copy if it's younger than `ttl_seconds` and falling back to the last cached value
+/// (flagged `stale`) if `fetch` fails. Only propagates `fetch`'s error when there is no
+/// cached value at all to fall back to.
+pub async fn get_or_fetch<T, F, Fut>(
+    env: &Env,
+    prefix: &str,
+    id: &str,
+    ttl_seconds: i64,
+    fetch: F,
+) -> Result<Cached<T>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let kv = open_kv(env)?;
+    let key = cache_key(prefix, id);
+
+    let cached: Option<CacheEntry<T>> = kv
+        .get(&key)
+        .json()
+        .await
+        .map_err(|e| TradingError::Storage(format!("Failed to read cache entry {key}: {e}")))?;
+
+    if let Some(entry) = &cached {
+        let age = age_seconds(&entry.fetched_at);
+        if age < ttl_seconds {
+            return Ok(Cached { value: entry.value.clone(), age_seconds: age, stale: false });
+        }
+    }
+
+    match fetch().await {
+        Ok(value) => {
+            let entry = CacheEntry { value: value.clone(), fetched_at: chrono::Utc::now().to_rfc3339() };
+            // Best-effort write-through - a cache write failure shouldn't fail a
+            // request that already has a live value to return.
+            if let Ok(builder) = kv.put(&key, &entry) {
+                let _ = builder.execute().await;
+            }
+            Ok(Cached { value, age_seconds: 0, stale: false })
+        }
+        Err(err) => match cached {
+            Some(entry) => Ok(Cached { value: entry.value, age_seconds: age_seconds(&entry.fetched_at), stale: true }),
+            None => Err(err),
+        },
+    }
+}
@@ -0,0 +1,397 @@
+//! Protection subsystem: automatic entry circuit-breakers
+//!
+//! Three independently-toggleable rules, each reading its parameters from
+//! `Config`, that `TradingEngine` consults before sizing a new position
+//! alongside the existing static `Config::daily_trade_limit`:
+//! - Cooldown: no re-entry on a symbol for `Config::cooldown_minutes` after it
+//!   last closed.
+//! - Stoploss guard: pause entries on every symbol once
+//!   `Config::stoploss_guard_trades` stop-losses land within a rolling
+//!   `Config::stoploss_guard_lookback_minutes` window.
+//! - Max-drawdown protection: halt entries on every symbol while the
+//!   portfolio's peak-to-trough decline over `Config::drawdown_protection_lookback_minutes`
+//!   exceeds `Config::max_drawdown_protection_percent`.
+
+use crate::config::Config;
+use crate::strategy::ExitReason;
+use crate::types::ClosedTrade;
+use chrono::{DateTime, Duration, Utc};
+
+/// Why `Protections::is_entry_allowed` refused a new entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtectionReason {
+    /// `symbol` closed a trade too recently; blocked until `until`.
+    Cooldown { symbol: String, until: DateTime<Utc> },
+    /// Too many stop-losses landed in the lookback window; all entries blocked
+    /// until `until`.
+    StoplossGuard { until: DateTime<Utc> },
+    /// Portfolio drawdown over the lookback window exceeds the configured cap.
+    MaxDrawdown { drawdown_percent: f64 },
+}
+
+impl std::fmt::Display for ProtectionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cooldown { symbol, until } => {
+                write!(f, "Cooldown: {symbol} re-entry blocked until {until}")
+            }
+            Self::StoplossGuard { until } => {
+                write!(f, "Stoploss guard: all entries blocked until {until}")
+            }
+            Self::MaxDrawdown { drawdown_percent } => {
+                write!(f, "Max-drawdown protection: portfolio down {drawdown_percent:.1}% over lookback window")
+            }
+        }
+    }
+}
+
+/// Evaluates `Config`'s protection rules against a `closed_trades` history.
+pub struct Protections<'a> {
+    config: &'a Config,
+}
+
+impl<'a> Protections<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self { config }
+    }
+
+    /// `Ok(())` if a new entry on `symbol` is allowed at `now`, else the first
+    /// rule that blocked it. `closed_trades` should be the bot's full (bounded)
+    /// closed-trade history; `portfolio_value` is the current total portfolio
+    /// USD value, used as the denominator for the drawdown-percent check.
+    pub fn is_entry_allowed(
+        &self,
+        symbol: &str,
+        now: DateTime<Utc>,
+        closed_trades: &[ClosedTrade],
+        portfolio_value: f64,
+    ) -> Result<(), ProtectionReason> {
+        self.check_cooldown(symbol, now, closed_trades)?;
+        self.check_stoploss_guard(now, closed_trades)?;
+        self.check_max_drawdown(now, closed_trades, portfolio_value)?;
+        Ok(())
+    }
+
+    fn check_cooldown(
+        &self,
+        symbol: &str,
+        now: DateTime<Utc>,
+        closed_trades: &[ClosedTrade],
+    ) -> Result<(), ProtectionReason> {
+        if !self.config.enable_cooldown_protection {
+            return Ok(());
+        }
+
+        let last_close = closed_trades
+            .iter()
+            .filter(|t| t.symbol == symbol)
+            .filter_map(|t| DateTime::parse_from_rfc3339(&t.closed_at).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .max();
+
+        if let Some(last_close) = last_close {
+            let until = last_close + Duration::minutes(self.config.cooldown_minutes as i64);
+            if now < until {
+                return Err(ProtectionReason::Cooldown { symbol: symbol.to_string(), until });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_stoploss_guard(
+        &self,
+        now: DateTime<Utc>,
+        closed_trades: &[ClosedTrade],
+    ) -> Result<(), ProtectionReason> {
+        if !self.config.enable_stoploss_guard {
+            return Ok(());
+        }
+
+        let lookback_start = now - Duration::minutes(self.config.stoploss_guard_lookback_minutes as i64);
+        let stop_loss_reason = ExitReason::StopLoss.to_string();
+
+        let recent_stop_losses: Vec<DateTime<Utc>> = closed_trades
+            .iter()
+            .filter(|t| t.reason.as_deref() == Some(stop_loss_reason.as_str()))
+            .filter_map(|t| DateTime::parse_from_rfc3339(&t.closed_at).ok())
+            .map(|t| t.with_timezone(&Utc))
+            .filter(|closed_at| *closed_at >= lookback_start && *closed_at <= now)
+            .collect();
+
+        if recent_stop_losses.len() >= self.config.stoploss_guard_trades as usize {
+            if let Some(&latest) = recent_stop_losses.iter().max() {
+                let until = latest + Duration::minutes(self.config.stoploss_guard_stop_minutes as i64);
+                if now < until {
+                    return Err(ProtectionReason::StoplossGuard { until });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_max_drawdown(
+        &self,
+        now: DateTime<Utc>,
+        closed_trades: &[ClosedTrade],
+        portfolio_value: f64,
+    ) -> Result<(), ProtectionReason> {
+        if !self.config.enable_drawdown_protection || portfolio_value <= 0.0 {
+            return Ok(());
+        }
+
+        let lookback_start = now - Duration::minutes(self.config.drawdown_protection_lookback_minutes as i64);
+
+        let mut window_trades: Vec<&ClosedTrade> = closed_trades
+            .iter()
+            .filter(|t| {
+                DateTime::parse_from_rfc3339(&t.closed_at)
+                    .map(|closed_at| {
+                        let closed_at = closed_at.with_timezone(&Utc);
+                        closed_at >= lookback_start && closed_at <= now
+                    })
+                    .unwrap_or(false)
+            })
+            .collect();
+        window_trades.sort_by_key(|t| t.closed_at.clone());
+
+        let mut equity = 0.0_f64;
+        let mut peak = 0.0_f64;
+        let mut max_drawdown_usd = 0.0_f64;
+        for trade in window_trades {
+            equity += trade.pnl;
+            peak = peak.max(equity);
+            max_drawdown_usd = max_drawdown_usd.max(peak - equity);
+        }
+
+        let drawdown_percent = max_drawdown_usd / portfolio_value * 100.0;
+        if drawdown_percent > self.config.max_drawdown_protection_percent {
+            return Err(ProtectionReason::MaxDrawdown { drawdown_percent });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TradingMode;
+    use crate::lots::CostBasisMethod;
+    use crate::types::PositionSide;
+    use std::collections::HashMap;
+
+    fn test_config() -> Config {
+        Config {
+            environment: "test".to_string(),
+            log_level: "debug".to_string(),
+            take_profit_percent: 1.5,
+            stop_loss_percent: 1.0,
+            trailing_stop_percent: 0.5,
+            atr_sl_multiplier: 1.0,
+            atr_tp_multiplier: 2.0,
+            min_sl_percent: 0.5,
+            max_sl_percent: 5.0,
+            min_tp_percent: 1.0,
+            max_tp_percent: 10.0,
+            atr_trail_multiplier: 1.5,
+            max_risk_per_trade_percent: 2.0,
+            max_portfolio_per_position: 25.0,
+            min_position_usd: 10.0,
+            cash_reserve_percent: 15.0,
+            max_total_positions: 8,
+            base_fee_percent: 0.60,
+            base_entry_threshold: 60.0,
+            min_entry_threshold: 40.0,
+            max_entry_threshold: 85.0,
+            cycle_interval_seconds: 15,
+            symbols: vec!["BTC-USD".to_string()],
+            daily_trade_limit: 30,
+            max_consecutive_errors: 5,
+            enable_trend_filter: false,
+            enable_volume_filter: false,
+            enable_market_regime_filter: false,
+            min_volume_usd: 1_000_000.0,
+            max_position_age_hours: 48.0,
+            enable_shorts: false,
+            enable_sr_filter: false,
+            sr_pivot_window: 2,
+            sr_tolerance_percent: 0.5,
+            sr_min_cluster_volume: 0.0,
+            sr_proximity_percent: 1.0,
+            dca_step_percent: 2.0,
+            max_entry_adjustments: 0,
+            enable_edge_sizing: false,
+            edge_min_trades: 20,
+            edge_kelly_cap: 0.5,
+            kelly_win_probability_estimate: 0.5,
+            tp_levels: vec![],
+            move_stop_to_breakeven_after: None,
+            minimal_roi: vec![],
+            trading_mode: TradingMode::Spot,
+            target_leverage: 1.0,
+            leverage_tiers: vec![],
+            funding_rate_per_hour: 0.0,
+            max_funding_drag_fraction: None,
+            unfilled_order_timeout_seconds: 30,
+            max_order_retries: 1,
+            enable_dynamic_pairlist: false,
+            pairlist_top_n: 10,
+            pairlist_min_volume_usd: 1_000_000.0,
+            pairlist_min_price: 0.01,
+            pairlist_max_price: 100_000.0,
+            pairlist_max_spread_percent: 1.0,
+            pairlist_blacklist: vec![],
+            enable_cooldown_protection: false,
+            cooldown_minutes: 60,
+            enable_stoploss_guard: false,
+            stoploss_guard_trades: 3,
+            stoploss_guard_lookback_minutes: 60,
+            stoploss_guard_stop_minutes: 120,
+            enable_drawdown_protection: false,
+            max_drawdown_protection_percent: 10.0,
+            drawdown_protection_lookback_minutes: 1440,
+            cost_basis_method: CostBasisMethod::Fifo,
+            max_liquidation_slippage_percent: 5.0,
+            base_currency: "USD".to_string(),
+            tier_hysteresis_percent: 5.0,
+            tier_transition_cycles: 5,
+            pair_overrides: HashMap::new(),
+        }
+    }
+
+    fn trade(symbol: &str, pnl: f64, reason: ExitReason, closed_at: DateTime<Utc>) -> ClosedTrade {
+        ClosedTrade {
+            symbol: symbol.to_string(),
+            side: PositionSide::Long,
+            entry_price: 50_000.0,
+            exit_price: 50_000.0 + pnl,
+            quantity: 1.0,
+            pnl,
+            closed_at: closed_at.to_rfc3339(),
+            reason: Some(reason.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_cooldown_blocks_reentry_until_window_elapses() {
+        let mut config = test_config();
+        config.enable_cooldown_protection = true;
+        config.cooldown_minutes = 30;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![trade("BTC-USD", -10.0, ExitReason::StopLoss, now - Duration::minutes(10))];
+
+        assert!(protections.is_entry_allowed("BTC-USD", now, &trades, 10_000.0).is_err());
+        assert!(protections.is_entry_allowed("ETH-USD", now, &trades, 10_000.0).is_ok());
+        assert!(protections
+            .is_entry_allowed("BTC-USD", now + Duration::minutes(25), &trades, 10_000.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_cooldown_disabled_never_blocks() {
+        let config = test_config();
+        let protections = Protections::new(&config);
+        let now = Utc::now();
+        let trades = vec![trade("BTC-USD", -10.0, ExitReason::StopLoss, now)];
+
+        assert!(protections.is_entry_allowed("BTC-USD", now, &trades, 10_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_stoploss_guard_trips_after_threshold_within_lookback() {
+        let mut config = test_config();
+        config.enable_stoploss_guard = true;
+        config.stoploss_guard_trades = 2;
+        config.stoploss_guard_lookback_minutes = 60;
+        config.stoploss_guard_stop_minutes = 30;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![
+            trade("BTC-USD", -10.0, ExitReason::StopLoss, now - Duration::minutes(50)),
+            trade("ETH-USD", -5.0, ExitReason::StopLoss, now - Duration::minutes(20)),
+        ];
+
+        let err = protections.is_entry_allowed("SOL-USD", now, &trades, 10_000.0).unwrap_err();
+        assert!(matches!(err, ProtectionReason::StoplossGuard { .. }));
+    }
+
+    #[test]
+    fn test_stoploss_guard_ignores_trades_outside_lookback() {
+        let mut config = test_config();
+        config.enable_stoploss_guard = true;
+        config.stoploss_guard_trades = 2;
+        config.stoploss_guard_lookback_minutes = 30;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![
+            trade("BTC-USD", -10.0, ExitReason::StopLoss, now - Duration::minutes(90)),
+            trade("ETH-USD", -5.0, ExitReason::StopLoss, now - Duration::minutes(80)),
+        ];
+
+        assert!(protections.is_entry_allowed("SOL-USD", now, &trades, 10_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_stoploss_guard_ignores_non_stoploss_exits() {
+        let mut config = test_config();
+        config.enable_stoploss_guard = true;
+        config.stoploss_guard_trades = 2;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![
+            trade("BTC-USD", 10.0, ExitReason::TakeProfit, now - Duration::minutes(10)),
+            trade("ETH-USD", 10.0, ExitReason::TakeProfit, now - Duration::minutes(5)),
+        ];
+
+        assert!(protections.is_entry_allowed("SOL-USD", now, &trades, 10_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_max_drawdown_blocks_when_window_decline_exceeds_cap() {
+        let mut config = test_config();
+        config.enable_drawdown_protection = true;
+        config.max_drawdown_protection_percent = 5.0;
+        config.drawdown_protection_lookback_minutes = 1440;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![
+            trade("BTC-USD", 100.0, ExitReason::TakeProfit, now - Duration::minutes(120)),
+            trade("BTC-USD", -700.0, ExitReason::StopLoss, now - Duration::minutes(60)),
+        ];
+
+        let err = protections.is_entry_allowed("ETH-USD", now, &trades, 10_000.0).unwrap_err();
+        assert!(matches!(err, ProtectionReason::MaxDrawdown { .. }));
+    }
+
+    #[test]
+    fn test_max_drawdown_allows_when_within_cap() {
+        let mut config = test_config();
+        config.enable_drawdown_protection = true;
+        config.max_drawdown_protection_percent = 50.0;
+        let protections = Protections::new(&config);
+
+        let now = Utc::now();
+        let trades = vec![trade("BTC-USD", -100.0, ExitReason::StopLoss, now - Duration::minutes(60))];
+
+        assert!(protections.is_entry_allowed("ETH-USD", now, &trades, 10_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_all_rules_disabled_by_default() {
+        let config = test_config();
+        let protections = Protections::new(&config);
+        let now = Utc::now();
+        let trades = vec![
+            trade("BTC-USD", -1000.0, ExitReason::StopLoss, now - Duration::minutes(1)),
+            trade("BTC-USD", -1000.0, ExitReason::StopLoss, now - Duration::minutes(2)),
+            trade("BTC-USD", -1000.0, ExitReason::StopLoss, now - Duration::minutes(3)),
+        ];
+
+        assert!(protections.is_entry_allowed("BTC-USD", now, &trades, 10_000.0).is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+//! Volume-weighted support/resistance detection
+//!
+//! Replaces the crude "24h high/low range position" heuristic with real
+//! swing-pivot clustering: find local highs/lows, group nearby pivots into
+//! price clusters, and score each cluster by how much volume traded at its
+//! touches. `analyze` uses the nearest strong cluster below/above price
+//! instead of just the raw 24h extremes.
+
+/// A single OHLCV bar, independent of any particular exchange client so this
+/// module can be unit tested without pulling in `client::Candle`.
+#[derive(Debug, Clone, Copy)]
+pub struct OhlcvBar {
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// A cluster of nearby swing pivots, i.e. one support or resistance level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    /// Representative price for the cluster (volume-weighted average of its touches)
+    pub price: f64,
+    /// Number of pivots that fell within the cluster's tolerance band
+    pub touches: usize,
+    /// Total volume traded at the clustered touches - the raw strength score
+    pub volume: f64,
+}
+
+/// Detected support/resistance structure for a symbol at a point in time
+#[derive(Debug, Clone, Default)]
+pub struct SupportResistance {
+    /// Levels below the current price at detection time, nearest first
+    pub supports: Vec<Level>,
+    /// Levels above the current price at detection time, nearest first
+    pub resistances: Vec<Level>,
+}
+
+impl SupportResistance {
+    /// Detect support/resistance from recent OHLCV bars.
+    ///
+    /// - `pivot_window`: how many neighbors on each side a bar's high/low must
+    ///   exceed to count as a swing pivot (typically 2-3).
+    /// - `tolerance_percent`: cluster pivots whose prices are within this % of
+    ///   each other into the same level (typically ~0.5%).
+    /// - `min_cluster_volume`: drop clusters whose total touch volume is below
+    ///   this threshold - filters out noise pivots nobody traded at.
+    pub fn detect(
+        bars: &[OhlcvBar],
+        current_price: f64,
+        pivot_window: usize,
+        tolerance_percent: f64,
+        min_cluster_volume: f64,
+    ) -> Self {
+        let pivots = find_pivots(bars, pivot_window);
+        let clusters = cluster_pivots(&pivots, tolerance_percent);
+
+        let mut supports: Vec<Level> = clusters
+            .iter()
+            .filter(|c| c.price < current_price && c.volume >= min_cluster_volume)
+            .cloned()
+            .collect();
+        let mut resistances: Vec<Level> = clusters
+            .iter()
+            .filter(|c| c.price > current_price && c.volume >= min_cluster_volume)
+            .cloned()
+            .collect();
+
+        // Nearest first: supports descending from price, resistances ascending
+        supports.sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap_or(std::cmp::Ordering::Equal));
+        resistances.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal));
+
+        Self { supports, resistances }
+    }
+
+    /// Nearest support level below price, if any were detected
+    pub fn nearest_support(&self) -> Option<&Level> {
+        self.supports.first()
+    }
+
+    /// Nearest resistance level above price, if any were detected
+    pub fn nearest_resistance(&self) -> Option<&Level> {
+        self.resistances.first()
+    }
+
+    /// Normalize a level's volume into a 0-1 strength score relative to the
+    /// strongest level on either side, for blending into signal confidence.
+    pub fn strength(&self, level: &Level) -> f64 {
+        let max_volume = self
+            .supports
+            .iter()
+            .chain(self.resistances.iter())
+            .map(|l| l.volume)
+            .fold(0.0, f64::max);
+        if max_volume > 0.0 {
+            (level.volume / max_volume).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+struct Pivot {
+    price: f64,
+    volume: f64,
+}
+
+/// Find swing highs and swing lows: a bar whose high (low) exceeds the high
+/// (low) of `window` neighbors on both sides.
+fn find_pivots(bars: &[OhlcvBar], window: usize) -> Vec<Pivot> {
+    let mut pivots = Vec::new();
+    if bars.len() < window * 2 + 1 {
+        return pivots;
+    }
+
+    for i in window..bars.len() - window {
+        let bar = &bars[i];
+        let neighbors = &bars[i - window..=i + window];
+
+        let is_swing_high = neighbors.iter().all(|n| bar.high >= n.high);
+        if is_swing_high {
+            pivots.push(Pivot { price: bar.high, volume: bar.volume });
+        }
+
+        let is_swing_low = neighbors.iter().all(|n| bar.low <= n.low);
+        if is_swing_low {
+            pivots.push(Pivot { price: bar.low, volume: bar.volume });
+        }
+    }
+
+    pivots
+}
+
+/// Group pivots whose prices fall within `tolerance_percent` of each other
+/// into clusters, scored by summed touch volume.
+fn cluster_pivots(pivots: &[Pivot], tolerance_percent: f64) -> Vec<Level> {
+    let mut sorted: Vec<&Pivot> = pivots.iter().collect();
+    sorted.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut clusters: Vec<Level> = Vec::new();
+    for pivot in sorted {
+        match clusters.last_mut() {
+            Some(last) if last.price > 0.0 && (pivot.price - last.price).abs() / last.price * 100.0 <= tolerance_percent => {
+                // Fold into the existing cluster, re-deriving the volume-weighted price
+                let total_volume = last.volume + pivot.volume;
+                if total_volume > 0.0 {
+                    last.price = (last.price * last.volume + pivot.price * pivot.volume) / total_volume;
+                }
+                last.touches += 1;
+                last.volume = total_volume;
+            }
+            _ => clusters.push(Level { price: pivot.price, touches: 1, volume: pivot.volume }),
+        }
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64) -> OhlcvBar {
+        OhlcvBar { high, low, close, volume }
+    }
+
+    #[test]
+    fn test_detects_simple_swing_pivots() {
+        // Clear V-shape: low pivot in the middle
+        let bars = vec![
+            bar(101.0, 100.0, 100.5, 10.0),
+            bar(100.0, 99.0, 99.5, 10.0),
+            bar(99.0, 95.0, 96.0, 50.0), // swing low
+            bar(100.0, 99.0, 99.5, 10.0),
+            bar(101.0, 100.0, 100.5, 10.0),
+        ];
+        let pivots = find_pivots(&bars, 2);
+        assert!(pivots.iter().any(|p| (p.price - 95.0).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_clusters_nearby_pivots_and_sums_volume() {
+        let pivots = vec![
+            Pivot { price: 100.0, volume: 50.0 },
+            Pivot { price: 100.3, volume: 30.0 }, // within 0.5% of 100.0
+            Pivot { price: 110.0, volume: 20.0 }, // far away - separate cluster
+        ];
+        let clusters = cluster_pivots(&pivots, 0.5);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].touches, 2);
+        assert!((clusters[0].volume - 80.0).abs() < 0.01);
+        assert_eq!(clusters[1].touches, 1);
+    }
+
+    #[test]
+    fn test_detect_splits_supports_and_resistances_by_current_price() {
+        let bars = vec![
+            bar(110.0, 109.0, 109.5, 100.0),
+            bar(109.0, 108.0, 108.5, 10.0),
+            bar(108.0, 90.0, 91.0, 200.0), // strong swing low - support
+            bar(109.0, 108.0, 108.5, 10.0),
+            bar(110.0, 109.0, 109.5, 100.0),
+            bar(111.0, 110.0, 110.5, 10.0),
+            bar(130.0, 120.0, 129.0, 300.0), // strong swing high - resistance
+            bar(111.0, 110.0, 110.5, 10.0),
+            bar(110.0, 109.0, 109.5, 100.0),
+        ];
+
+        let sr = SupportResistance::detect(&bars, 100.0, 2, 0.5, 50.0);
+        assert!(sr.nearest_support().is_some());
+        assert!(sr.nearest_resistance().is_some());
+        assert!(sr.nearest_support().expect("support").price < 100.0);
+        assert!(sr.nearest_resistance().expect("resistance").price > 100.0);
+    }
+
+    #[test]
+    fn test_min_cluster_volume_filters_noise() {
+        let bars = vec![
+            bar(101.0, 100.0, 100.5, 1.0),
+            bar(100.0, 99.0, 99.5, 1.0),
+            bar(99.0, 95.0, 96.0, 1.0), // low-volume pivot, should be filtered
+            bar(100.0, 99.0, 99.5, 1.0),
+            bar(101.0, 100.0, 100.5, 1.0),
+        ];
+        let sr = SupportResistance::detect(&bars, 100.0, 2, 0.5, 50.0);
+        assert!(sr.nearest_support().is_none(), "Low-volume cluster should be filtered out");
+    }
+
+    #[test]
+    fn test_empty_bars_does_not_panic() {
+        let sr = SupportResistance::detect(&[], 100.0, 2, 0.5, 50.0);
+        assert!(sr.nearest_support().is_none());
+        assert!(sr.nearest_resistance().is_none());
+    }
+}
@@ -0,0 +1,88 @@
+//! Exchange symbol filters (tick size, lot size, minimum notional)
+//!
+//! Mirrors the PRICE_FILTER/LOT_SIZE/MIN_NOTIONAL model real spot venues enforce:
+//! an order whose price/quantity don't land on the exchange's tick/step grid, or
+//! whose size is below a minimum, gets rejected outright. `money.rs`'s
+//! `round_to_increment` already does the rounding math for a single value (an
+//! order's own price or base size against its own increment); `SymbolFilters` just
+//! carries the per-symbol grid plus the minimums and applies both at once.
+
+use crate::error::{Result, TradingError};
+use crate::money::round_to_increment;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A symbol's tick/lot-size metadata, as an exchange-info endpoint would report it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SymbolFilters {
+    /// Smallest price increment the exchange will accept
+    pub price_tick: Decimal,
+    /// Smallest quantity increment the exchange will accept
+    pub qty_step: Decimal,
+    /// Minimum order quantity, post-rounding
+    pub min_qty: Decimal,
+    /// Minimum `price * qty` notional, post-rounding
+    pub min_notional: Decimal,
+}
+
+impl SymbolFilters {
+    /// Round `price`/`qty` down to this symbol's tick/step grid, then verify the
+    /// rounded order still clears `min_qty` and `min_notional`. Returns the
+    /// exchange-legal `(price, qty)` to actually place, or the filter that rejected it.
+    pub fn round_order(&self, price: Decimal, qty: Decimal) -> Result<(Decimal, Decimal)> {
+        let rounded_price = round_to_increment(price, self.price_tick);
+        let rounded_qty = round_to_increment(qty, self.qty_step);
+
+        if rounded_qty < self.min_qty {
+            return Err(TradingError::OrderValidation(format!(
+                "Quantity {rounded_qty} below minimum {} after rounding to step {}",
+                self.min_qty, self.qty_step
+            )));
+        }
+
+        let notional = rounded_price * rounded_qty;
+        if notional < self.min_notional {
+            return Err(TradingError::OrderValidation(format!(
+                "Notional {notional} below minimum {} after rounding", self.min_notional
+            )));
+        }
+
+        Ok((rounded_price, rounded_qty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn filters() -> SymbolFilters {
+        SymbolFilters {
+            price_tick: Decimal::from_str("0.01").unwrap(),
+            qty_step: Decimal::from_str("0.001").unwrap(),
+            min_qty: Decimal::from_str("0.001").unwrap(),
+            min_notional: Decimal::from_str("10").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_round_order_rounds_to_tick_and_step() {
+        let (price, qty) = filters()
+            .round_order(Decimal::from_str("50000.0049").unwrap(), Decimal::from_str("0.0019").unwrap())
+            .unwrap();
+        assert_eq!(price, Decimal::from_str("50000.00").unwrap());
+        assert_eq!(qty, Decimal::from_str("0.001").unwrap());
+    }
+
+    #[test]
+    fn test_round_order_rejects_below_min_qty() {
+        let result = filters().round_order(Decimal::from_str("50000").unwrap(), Decimal::from_str("0.0001").unwrap());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_order_rejects_below_min_notional() {
+        let result = filters().round_order(Decimal::from_str("1").unwrap(), Decimal::from_str("0.001").unwrap());
+        assert!(result.is_err());
+    }
+}
@@ -2,26 +2,36 @@
 //!
 //! Coordinates strategy, API client, and state management.
 
-use crate::client::CoinbaseClient;
+use crate::amount::Amount;
+use crate::backtest::{Backtester, BacktestReport, HistoricalDataSource};
+use crate::capital_tier::TierTransition;
+use crate::client::OrderStatus;
 use crate::config::Config;
-use crate::error::Result;
-use crate::strategy::TradingStrategy;
-use crate::types::{TradingStateData, TradingCycleResult, Position, Trade, OrderSide, PositionsResponse, PositionWithPnl, StatusResponse};
+use crate::error::{Result, TradingError};
+use crate::exchange::Exchange;
+use crate::pairlist::Pairlist;
+use crate::strategy::{ExitReason, TradingSignal, TradingStrategy};
+use crate::support_resistance::{OhlcvBar, SupportResistance};
+use crate::protections::Protections;
+use crate::types::{TradingStateData, TradingCycleResult, Position, PositionSide, PendingOrder, PendingOrderPurpose, Trade, OrderSide, OrderType, PositionsResponse, PositionWithPnl, StatusResponse, ClosedTrade, PerformanceResponse};
 use chrono::Utc;
+use std::collections::HashMap;
 
-/// Trading engine coordinating all components
+/// Trading engine coordinating all components. Depends only on the `Exchange`
+/// trait (not `CoinbaseClient` directly) so a different venue can be swapped in
+/// without touching any cycle logic below.
 pub struct TradingEngine {
-    client: CoinbaseClient,
+    exchange: Box<dyn Exchange>,
     strategy: TradingStrategy,
     config: Config,
 }
 
 impl TradingEngine {
     /// Create new trading engine
-    pub fn new(client: CoinbaseClient, config: Config) -> Self {
+    pub fn new(exchange: Box<dyn Exchange>, config: Config) -> Self {
         let strategy = TradingStrategy::new(config.clone());
         Self {
-            client,
+            exchange,
             strategy,
             config,
         }
@@ -55,10 +65,20 @@ impl TradingEngine {
                 return Ok(result);
             }
         
-        // Process existing positions first (check for exits)
+        // Confirm/cancel/retry anything still awaiting fill before acting on positions,
+        // so the rest of the cycle never works from a fill that hasn't actually happened.
+        if let Err(e) = self.reconcile_pending_orders(state, &mut result).await {
+            worker::console_warn!("Error reconciling pending orders: {}", e);
+        }
+
+        // Estimate total portfolio value up front so position adjustments (DCA adds)
+        // can be sized against it without re-fetching balances per position.
+        let total_portfolio = self.estimate_total_portfolio(state).await.unwrap_or(0.0);
+
+        // Process existing positions first (check for exits, then DCA adds)
         let positions = state.positions.clone();
         for position in positions {
-            match self.process_position(&position, state, &mut result).await {
+            match self.process_position(&position, state, &mut result, total_portfolio).await {
                 Ok(()) => {}
                 Err(e) => {
                     worker::console_warn!("Error processing position {}: {}", position.symbol, e);
@@ -91,133 +111,809 @@ impl TradingEngine {
         Ok(result)
     }
     
-    /// Process an existing position - check for exits, update trailing stop
-    async fn process_position(
+    /// Streaming-mode counterpart to the periodic `run_cycle`: evaluate (possibly
+    /// scaled) exits for `symbol` against a freshly-ticked `price` instead of waiting
+    /// for the next polling cycle, so a price-stream consumer can close stopped-out
+    /// or take-profit-hit positions intrabar. DCA adds stay a periodic-cycle concern
+    /// (they need a fresh portfolio estimate, not just a price). No-op if `symbol`
+    /// has no open position.
+    pub async fn on_price_tick(
         &self,
-        position: &Position,
+        symbol: &str,
+        price: f64,
         state: &mut TradingStateData,
         result: &mut TradingCycleResult,
     ) -> Result<()> {
-        // Get current price
-        let current_price = self.client.get_price(&position.symbol).await?;
-        
-        // Update high water mark
+        let Some(position) = state.get_position(symbol).cloned() else {
+            return Ok(());
+        };
+        self.handle_exits_at_price(&position, price, state, result).await?;
+        Ok(())
+    }
+
+    /// Update trailing extremes/funding accrual for `position`, then check (possibly
+    /// scaled) exits at `current_price` and place whatever orders they call for.
+    /// Returns `true` when a scaled-exit event fired (partial or full close), so
+    /// callers know not to fall through to DCA or any other post-exit handling.
+    async fn handle_exits_at_price(
+        &self,
+        position: &Position,
+        current_price: f64,
+        state: &mut TradingStateData,
+        result: &mut TradingCycleResult,
+    ) -> Result<bool> {
+        // A close order for this position is already awaiting fill confirmation -
+        // don't re-evaluate exits (and possibly place a second close) until
+        // `reconcile_pending_orders` resolves it.
+        if position.pending_exit_order_id.is_some() {
+            return Ok(false);
+        }
+
+        // Update trailing extreme (high water mark for longs, low water mark for shorts)
+        // and the cumulative funding accrued since entry (0.0 while funding is disabled).
         if let Some(pos) = state.get_position_mut(&position.symbol) {
-            pos.update_high_water_mark(current_price);
+            pos.update_trailing_extreme(current_price);
+            pos.cumulative_funding = self.strategy.accrued_funding_percent(pos);
         }
-        
-        // Check for exit signals
-        if let Some(exit_reason) = self.strategy.check_exit(position, current_price) {
-            // Close position
-            let order = self.client.market_sell(&position.symbol, position.quantity).await?;
-            
-            let pnl = position.unrealized_pnl(current_price);
-            
-            let trade = Trade {
-                id: order.order_id.unwrap_or_default(),
-                symbol: position.symbol.clone(),
-                side: OrderSide::Sell,
-                quantity: position.quantity,
-                price: current_price,
-                total_value: current_price * position.quantity,
-                timestamp: Utc::now().to_rfc3339(),
-                pnl: Some(pnl),
+
+        // Check for exit signals - partial take-profit rungs first, full close (if any) last
+        let actions = self.strategy.check_scaled_exits(position, current_price);
+
+        if !actions.is_empty() {
+            let mut remaining = position.remaining_quantity.unwrap_or(position.quantity);
+            let mut targets_hit = position.targets_hit;
+            let mut realized_from_partials = 0.0;
+            let mut fully_closed = false;
+
+            for action in &actions {
+                match &action.reason {
+                    ExitReason::PartialTakeProfit { level, fraction } => {
+                        let close_qty = remaining * fraction;
+                        if close_qty <= 0.0 {
+                            continue;
+                        }
+
+                        // If trimming this rung would leave a remainder below the
+                        // exchange's minimum order size, there's no future order that
+                        // could close it - take the whole position now instead of
+                        // stranding dust.
+                        let leaves_dust = state.get_symbol_filters(&position.symbol).is_some_and(|filters| {
+                            let leftover = remaining - close_qty;
+                            leftover > 0.0
+                                && crate::money::decimal_from_f64(leftover).is_ok_and(|d| d < filters.min_qty)
+                        });
+
+                        if leaves_dust {
+                            if self
+                                .place_full_close(position, remaining, current_price, action.reason.to_string(), state)
+                                .await
+                            {
+                                fully_closed = true;
+                            }
+                            break;
+                        }
+
+                        // Close a slice: sell to trim a long, buy back to trim a short
+                        let order_result = match position.side {
+                            PositionSide::Long => self.exchange.market_sell(&position.symbol, close_qty).await,
+                            PositionSide::Short => self.exchange.market_buy(&position.symbol, current_price * close_qty).await,
+                        };
+
+                        let order = match order_result {
+                            Ok(o) => o,
+                            Err(e) => {
+                                worker::console_warn!("Failed to take partial profit on {}: {}", position.symbol, e);
+                                continue;
+                            }
+                        };
+
+                        let slice_pnl = match position.side {
+                            PositionSide::Long => (current_price - position.entry_price) * close_qty,
+                            PositionSide::Short => (position.entry_price - current_price) * close_qty,
+                        };
+
+                        remaining -= close_qty;
+                        realized_from_partials += slice_pnl;
+                        if let Some(idx) = self.config.tp_levels.iter().position(|(lvl, _)| (lvl - level).abs() < f64::EPSILON) {
+                            targets_hit |= 1u8 << idx;
+                        }
+
+                        let trade = Trade {
+                            id: order.order_id.unwrap_or_default(),
+                            symbol: position.symbol.clone(),
+                            side: match position.side {
+                                PositionSide::Long => OrderSide::Sell,
+                                PositionSide::Short => OrderSide::Buy,
+                            },
+                            quantity: close_qty,
+                            price: current_price,
+                            total_value: current_price * close_qty,
+                            timestamp: Utc::now().to_rfc3339(),
+                            pnl: Some(slice_pnl),
+                            order_type: OrderType::Market,
+                            fee: 0.0,
+                            position_side: position.side,
+                        };
+
+                        state.total_trades += 1;
+                        state.record_closed_trade(ClosedTrade {
+                            symbol: position.symbol.clone(),
+                            side: position.side,
+                            entry_price: position.entry_price,
+                            exit_price: current_price,
+                            quantity: close_qty,
+                            pnl: slice_pnl,
+                            closed_at: trade.timestamp.clone(),
+                            reason: Some(action.reason.to_string()),
+                            opened_at: Some(position.entry_time.clone()),
+                        });
+                        result.trades.push(trade);
+
+                        worker::console_log!(
+                            "Partial take-profit on {}: closed {:.6} ({:.0}%) @ {} ({:.1}R)",
+                            position.symbol, close_qty, fraction * 100.0, current_price, level
+                        );
+                    }
+                    _ => {
+                        // Full close: exit whatever quantity is still open after any partials.
+                        // Don't assume the order fills instantly at `current_price` - place it,
+                        // remember it as pending, and let `reconcile_pending_orders` finalize the
+                        // position/trade once Coinbase reports the actual fill.
+                        if self
+                            .place_full_close(position, remaining, current_price, action.reason.to_string(), state)
+                            .await
+                        {
+                            fully_closed = true;
+                        }
+                    }
+                }
+            }
+
+            if !fully_closed {
+                if let Some(pos) = state.get_position_mut(&position.symbol) {
+                    pos.remaining_quantity = Some(remaining);
+                    pos.targets_hit = targets_hit;
+                    pos.realized_pnl += realized_from_partials;
+
+                    // Once enough scaled targets have fired, lock in a risk-free runner
+                    // by moving the hard stop to breakeven.
+                    if let Some(threshold) = self.config.move_stop_to_breakeven_after {
+                        if threshold > 0 && targets_hit.count_ones() as usize >= threshold {
+                            pos.stop_loss_price = Some(position.entry_price);
+                        }
+                    }
+                }
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Poll every entry/full-close order still awaiting fill confirmation, finalizing
+    /// it at its *actual* fill price/quantity once Coinbase reports `FILLED`, and
+    /// cancelling (then retrying up to `Config::max_order_retries` times) anything
+    /// that's sat unfilled past `Config::unfilled_order_timeout_seconds`. Run at the
+    /// top of every cycle so a worker restart mid-fill just picks back up from
+    /// `TradingStateData::pending_orders` instead of losing track of it.
+    async fn reconcile_pending_orders(
+        &self,
+        state: &mut TradingStateData,
+        result: &mut TradingCycleResult,
+    ) -> Result<()> {
+        let pending = state.pending_orders.clone();
+
+        for order in pending {
+            let status = match self.exchange.get_order(&order.order_id).await {
+                Ok(s) => s,
+                Err(e) => {
+                    worker::console_warn!("Failed to check status of order {}: {}", order.order_id, e);
+                    continue;
+                }
             };
-            
-            // Update state
-            state.remove_position(&position.symbol);
-            state.total_trades += 1;
-            state.total_pnl += pnl;
-            state.increment_daily_trades(&Utc::now().format("%Y-%m-%d").to_string());
-            
-            result.positions_closed += 1;
-            result.trades.push(trade);
-            
-            worker::console_log!(
-                "Closed {} position: {} @ {} ({}) P&L: ${:.2}",
-                position.symbol,
-                position.quantity,
-                current_price,
-                exit_reason,
-                pnl
-            );
+
+            if status.is_filled() {
+                self.finalize_pending_order(&order, &status, state, result);
+                state.remove_pending_order(&order.order_id);
+                continue;
+            }
+
+            let elapsed_seconds = chrono::DateTime::parse_from_rfc3339(&order.placed_at)
+                .map(|placed| (Utc::now().timestamp() - placed.timestamp()).max(0) as u64)
+                .unwrap_or(0);
+
+            if elapsed_seconds < self.config.unfilled_order_timeout_seconds {
+                continue; // still within the timeout window, leave it pending
+            }
+
+            if let Err(e) = self.exchange.cancel_order(&order.order_id).await {
+                worker::console_warn!("Failed to cancel unfilled order {}: {}", order.order_id, e);
+                continue;
+            }
+
+            if order.attempt < self.config.max_order_retries {
+                match self.replace_pending_order(&order).await {
+                    Ok(new_order) => {
+                        worker::console_log!(
+                            "Order for {} timed out unfilled, re-placed (attempt {})",
+                            order.symbol,
+                            new_order.attempt
+                        );
+                        state.remove_pending_order(&order.order_id);
+                        state.pending_orders.push(new_order);
+                    }
+                    Err(e) => {
+                        worker::console_warn!("Failed to re-place timed-out order for {}: {}", order.symbol, e);
+                    }
+                }
+            } else {
+                state.remove_pending_order(&order.order_id);
+                if let PendingOrderPurpose::Exit { .. } = order.purpose {
+                    if let Some(pos) = state.get_position_mut(&order.symbol) {
+                        pos.pending_exit_order_id = None; // allow the exit to be re-attempted next cycle
+                    }
+                }
+                worker::console_warn!(
+                    "Order for {} timed out unfilled after {} attempt(s), giving up",
+                    order.symbol,
+                    order.attempt + 1
+                );
+            }
         }
-        
+
         Ok(())
     }
-    
-    /// Scan symbols for new entry opportunities
-    async fn scan_for_entries(
+
+    /// Round a confirmed fill's `(price, qty)` onto `symbol`'s tick/lot-size grid
+    /// before it's persisted into a new `Position`, so what's stored is already
+    /// exchange-legal. Returns `None` (pass the fill through unrounded) when no
+    /// `SymbolFilters` has been refreshed for `symbol` yet, or when the rounded order
+    /// would violate `min_qty`/`min_notional` - a rejection at this point is logged
+    /// rather than failing the whole fill-confirmation step, since Coinbase has
+    /// already filled the order by the time this runs.
+    fn round_fill_to_symbol_filters(
         &self,
+        state: &TradingStateData,
+        symbol: &str,
+        price: f64,
+        qty: f64,
+    ) -> Option<(f64, f64)> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let filters = state.get_symbol_filters(symbol)?;
+        let price_decimal = crate::money::decimal_from_f64(price).ok()?;
+        let qty_decimal = crate::money::decimal_from_f64(qty).ok()?;
+
+        match filters.round_order(price_decimal, qty_decimal) {
+            Ok((rounded_price, rounded_qty)) => Some((rounded_price.to_f64()?, rounded_qty.to_f64()?)),
+            Err(e) => {
+                worker::console_warn!("Fill for {} failed symbol filters, storing unrounded: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    /// Apply the actual fill reported by Coinbase for a confirmed-filled pending order:
+    /// open the position (for an entry) or close it and book realized P&L (for an exit).
+    fn finalize_pending_order(
+        &self,
+        order: &PendingOrder,
+        status: &OrderStatus,
         state: &mut TradingStateData,
         result: &mut TradingCycleResult,
+    ) {
+        let filled_qty: f64 = status.filled_size.parse().unwrap_or(0.0);
+        let fill_price: f64 = status.average_filled_price.parse().unwrap_or(0.0);
+        if filled_qty <= 0.0 || fill_price <= 0.0 {
+            worker::console_warn!(
+                "Order {} reported FILLED with no usable fill data, skipping",
+                order.order_id
+            );
+            return;
+        }
+
+        match &order.purpose {
+            PendingOrderPurpose::Entry { stop_loss_price, take_profit_price, entry_volatility, side } => {
+                let (fill_price, filled_qty) = self
+                    .round_fill_to_symbol_filters(state, &order.symbol, fill_price, filled_qty)
+                    .unwrap_or((fill_price, filled_qty));
+
+                let position = Position {
+                    symbol: order.symbol.clone(),
+                    quantity: filled_qty,
+                    entry_price: fill_price,
+                    entry_time: Utc::now().to_rfc3339(),
+                    high_water_mark: None,
+                    stop_loss_price: Some(*stop_loss_price),
+                    take_profit_price: Some(*take_profit_price),
+                    entry_volatility: Some(*entry_volatility),
+                    targets_hit: 0,
+                    remaining_quantity: None,
+                    side: *side,
+                    low_water_mark: None,
+                    entry_adjustments: 0,
+                    cumulative_funding: 0.0,
+                    pending_exit_order_id: None,
+                    realized_pnl: 0.0,
+                    expiry_time: None,
+                };
+
+                let trade = Trade {
+                    id: order.order_id.clone(),
+                    symbol: order.symbol.clone(),
+                    side: match side {
+                        PositionSide::Long => OrderSide::Buy,
+                        PositionSide::Short => OrderSide::Sell,
+                    },
+                    quantity: filled_qty,
+                    price: fill_price,
+                    total_value: fill_price * filled_qty,
+                    timestamp: Utc::now().to_rfc3339(),
+                    pnl: None,
+                    order_type: OrderType::Market,
+                    fee: 0.0,
+                    position_side: *side,
+                };
+
+                state.add_position(position);
+                state.total_trades += 1;
+                state.increment_daily_trades(&Utc::now().format("%Y-%m-%d").to_string());
+                result.positions_opened += 1;
+                result.trades.push(trade);
+
+                worker::console_log!(
+                    "Confirmed fill: opened {} position: {:.6} @ {}",
+                    order.symbol,
+                    filled_qty,
+                    fill_price
+                );
+            }
+            PendingOrderPurpose::Exit { reason } => {
+                let Some(position) = state.get_position(&order.symbol).cloned() else {
+                    worker::console_warn!(
+                        "Close order {} filled but position {} is already gone",
+                        order.order_id,
+                        order.symbol
+                    );
+                    return;
+                };
+
+                let pnl = match position.side {
+                    PositionSide::Long => (fill_price - position.entry_price) * filled_qty,
+                    PositionSide::Short => (position.entry_price - fill_price) * filled_qty,
+                };
+
+                // Record the outcome in R-multiples (PnL / amount risked at the stop-loss)
+                // so edge/expectancy sizing has realized data to work from.
+                if let Some(sl) = position.stop_loss_price {
+                    let risk_per_unit = (position.entry_price - sl).abs();
+                    if risk_per_unit > 0.0 {
+                        let r_multiple = pnl / (risk_per_unit * filled_qty);
+                        state.trade_history.record(&position.symbol, r_multiple);
+                    }
+                }
+
+                let trade = Trade {
+                    id: order.order_id.clone(),
+                    symbol: order.symbol.clone(),
+                    side: match position.side {
+                        PositionSide::Long => OrderSide::Sell,
+                        PositionSide::Short => OrderSide::Buy,
+                    },
+                    quantity: filled_qty,
+                    price: fill_price,
+                    total_value: fill_price * filled_qty,
+                    timestamp: Utc::now().to_rfc3339(),
+                    pnl: Some(pnl),
+                    order_type: OrderType::Market,
+                    fee: 0.0,
+                    position_side: position.side,
+                };
+
+                state.remove_position(&order.symbol);
+                state.total_trades += 1;
+                state.record_closed_trade(ClosedTrade {
+                    symbol: position.symbol.clone(),
+                    side: position.side,
+                    entry_price: position.entry_price,
+                    exit_price: fill_price,
+                    quantity: filled_qty,
+                    pnl,
+                    closed_at: trade.timestamp.clone(),
+                    reason: Some(reason.clone()),
+                    opened_at: Some(position.entry_time.clone()),
+                });
+                state.increment_daily_trades(&Utc::now().format("%Y-%m-%d").to_string());
+                result.positions_closed += 1;
+                result.trades.push(trade);
+
+                worker::console_log!(
+                    "Confirmed fill: closed {} position: {:.6} @ {} ({}) P&L: ${:.2}",
+                    order.symbol,
+                    filled_qty,
+                    fill_price,
+                    reason,
+                    pnl
+                );
+            }
+        }
+    }
+
+    /// Re-place a timed-out order identically (same side/size) after it's been
+    /// cancelled, for the retry ladder in `reconcile_pending_orders`.
+    async fn replace_pending_order(&self, order: &PendingOrder) -> Result<PendingOrder> {
+        let response = match (order.side, order.requested_usd, order.requested_quantity) {
+            (OrderSide::Buy, Some(usd), _) => self.exchange.market_buy(&order.symbol, usd).await?,
+            (OrderSide::Sell, _, Some(qty)) => self.exchange.market_sell(&order.symbol, qty).await?,
+            _ => {
+                return Err(TradingError::Trading(format!(
+                    "Cannot re-place order for {}: missing original size",
+                    order.symbol
+                )));
+            }
+        };
+
+        let order_id = response.order_id.ok_or_else(|| {
+            TradingError::CoinbaseApi(format!("Re-placed order for {} returned no order_id", order.symbol))
+        })?;
+
+        Ok(PendingOrder {
+            order_id,
+            symbol: order.symbol.clone(),
+            side: order.side,
+            placed_at: Utc::now().to_rfc3339(),
+            purpose: order.purpose.clone(),
+            requested_usd: order.requested_usd,
+            requested_quantity: order.requested_quantity,
+            attempt: order.attempt + 1,
+        })
+    }
+
+    /// Process an existing position - roll it over if its `expiry_time` has passed,
+    /// otherwise check for (possibly scaled) exits, update trailing stop, and (if
+    /// still open) consider a DCA add via `TradingStrategy::adjust_position`.
+    async fn process_position(
+        &self,
+        position: &Position,
+        state: &mut TradingStateData,
+        result: &mut TradingCycleResult,
+        total_portfolio: f64,
     ) -> Result<()> {
-        // Get available balance (USD + USDC, both count as cash)
-        let accounts = self.client.get_accounts().await?;
-        let usd_balance: f64 = accounts.accounts
+        // Get current price
+        let current_price = self.exchange.get_price(&position.symbol).await?;
+
+        if let Some((close_trade, reopen_trade)) = state.rollover_expiring(&position.symbol, current_price, Utc::now()) {
+            worker::console_log!("Rolled over {} @ {} (expiry reached)", position.symbol, current_price);
+            result.positions_closed += 1;
+            result.positions_opened += 1;
+            result.trades.push(close_trade);
+            result.trades.push(reopen_trade);
+            return Ok(());
+        }
+
+        self.refresh_symbol_filters(state, &position.symbol).await;
+
+        if self.handle_exits_at_price(position, current_price, state, result).await? {
+            return Ok(());
+        }
+
+        if let Some(adjustment) = self.strategy.adjust_position(position, current_price, total_portfolio) {
+            // Still open and has dropped far enough against us - average in
+            let side_order = match position.side {
+                PositionSide::Long => self.exchange.market_buy(&position.symbol, adjustment.additional_stake).await,
+                PositionSide::Short => self.exchange.market_sell(&position.symbol, adjustment.additional_quantity).await,
+            };
+
+            match side_order {
+                Ok(order) => {
+                    if let Some(pos) = state.get_position_mut(&position.symbol) {
+                        pos.entry_price = adjustment.new_entry_price;
+                        pos.quantity = adjustment.new_quantity;
+                        pos.stop_loss_price = adjustment.new_stop_loss_price;
+                        pos.take_profit_price = adjustment.new_take_profit_price;
+                        pos.entry_adjustments += 1;
+                    }
+
+                    let trade = Trade {
+                        id: order.order_id.unwrap_or_default(),
+                        symbol: position.symbol.clone(),
+                        side: match position.side {
+                            PositionSide::Long => OrderSide::Buy,
+                            PositionSide::Short => OrderSide::Sell,
+                        },
+                        quantity: adjustment.additional_quantity,
+                        price: current_price,
+                        total_value: adjustment.additional_stake,
+                        timestamp: Utc::now().to_rfc3339(),
+                        pnl: None,
+                        order_type: OrderType::Market,
+                        fee: 0.0,
+                        position_side: position.side,
+                    };
+                    state.total_trades += 1;
+                    result.trades.push(trade);
+
+                    worker::console_log!(
+                        "DCA add on {}: +{:.6} @ {} (new avg entry ${:.2})",
+                        position.symbol,
+                        adjustment.additional_quantity,
+                        current_price,
+                        adjustment.new_entry_price
+                    );
+                }
+                Err(e) => {
+                    worker::console_warn!("Failed to add to {}: {}", position.symbol, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch and cache `symbol`'s exchange trading rules (tick/lot size, minimum order
+    /// size) if they haven't been refreshed yet. Best-effort: a failed fetch just
+    /// leaves the dust/minimum checks as no-ops for this symbol rather than failing
+    /// the cycle, since they're a guardrail on top of order placement, not a
+    /// precondition for it.
+    async fn refresh_symbol_filters(&self, state: &mut TradingStateData, symbol: &str) {
+        if state.get_symbol_filters(symbol).is_some() {
+            return;
+        }
+
+        match self.exchange.get_symbol_filters(symbol).await {
+            Ok(filters) => state.set_symbol_filters(symbol, filters),
+            Err(e) => worker::console_warn!("Failed to refresh symbol filters for {}: {}", symbol, e),
+        }
+    }
+
+    /// Place the exit order for whatever quantity is still open on `position`, track
+    /// it as a pending close awaiting fill confirmation, and return whether it was
+    /// placed successfully. Shared by a full-close exit action and by a partial
+    /// take-profit that would otherwise leave an un-closeable dust remainder.
+    async fn place_full_close(
+        &self,
+        position: &Position,
+        remaining: f64,
+        current_price: f64,
+        reason: String,
+        state: &mut TradingStateData,
+    ) -> bool {
+        let (order_result, side, usd, qty) = match position.side {
+            PositionSide::Long => (
+                self.exchange.market_sell(&position.symbol, remaining).await,
+                OrderSide::Sell,
+                None,
+                Some(remaining),
+            ),
+            PositionSide::Short => (
+                self.exchange.market_buy(&position.symbol, current_price * remaining).await,
+                OrderSide::Buy,
+                Some(current_price * remaining),
+                None,
+            ),
+        };
+
+        let order = match order_result {
+            Ok(o) => o,
+            Err(e) => {
+                worker::console_warn!("Failed to close {}: {}", position.symbol, e);
+                return false;
+            }
+        };
+
+        let Some(order_id) = order.order_id else {
+            worker::console_warn!(
+                "Close order for {} returned no order_id, cannot track fill",
+                position.symbol
+            );
+            return false;
+        };
+
+        if let Some(pos) = state.get_position_mut(&position.symbol) {
+            pos.pending_exit_order_id = Some(order_id.clone());
+        }
+
+        state.pending_orders.push(PendingOrder {
+            order_id,
+            symbol: position.symbol.clone(),
+            side,
+            placed_at: Utc::now().to_rfc3339(),
+            purpose: PendingOrderPurpose::Exit { reason: reason.clone() },
+            requested_usd: usd,
+            requested_quantity: qty,
+            attempt: 0,
+        });
+
+        worker::console_log!(
+            "Close order placed for {} ({}), awaiting fill confirmation",
+            position.symbol,
+            reason
+        );
+
+        true
+    }
+
+    /// Fetch USD/USDC balance plus current positions value (total portfolio estimate)
+    async fn portfolio_snapshot(&self, state: &TradingStateData) -> Result<(f64, f64)> {
+        let accounts = self.exchange.get_accounts().await?;
+        let usd_balance: f64 = accounts
             .iter()
             .filter(|a| a.currency == "USD" || a.currency == "USDC")
-            .filter_map(|a| a.available_balance.value.parse::<f64>().ok())
+            .map(|a| a.available)
             .sum();
-        
-        // Calculate total portfolio value (USD + positions value)
+
         let mut positions_value = 0.0;
         for pos in &state.positions {
-            if let Ok(price) = self.client.get_price(&pos.symbol).await {
+            if let Ok(price) = self.exchange.get_price(&pos.symbol).await {
                 positions_value += pos.quantity * price;
             }
         }
-        let total_portfolio = usd_balance + positions_value;
-        
-        worker::console_log!("Portfolio: ${:.2} (${:.2} USD + ${:.2} positions)", 
-            total_portfolio, usd_balance, positions_value);
-        
+
+        Ok((usd_balance, usd_balance + positions_value))
+    }
+
+    /// Total portfolio value estimate. A failed balance fetch is returned as an error;
+    /// `run_cycle` treats that as $0 so sizing against it simply skips rather than
+    /// failing the cycle
+    async fn estimate_total_portfolio(&self, state: &TradingStateData) -> Result<f64> {
+        self.portfolio_snapshot(state).await.map(|(_, total)| total)
+    }
+
+    /// Symbols to scan for entries this cycle: the fixed `Config::symbols` watchlist,
+    /// or (if `Config::enable_dynamic_pairlist`) the current top-N by volume after
+    /// price/spread/blacklist filtering (see `pairlist::Pairlist`). Falls back to the
+    /// static watchlist if the product fetch fails or the pipeline empties out.
+    async fn resolve_scan_symbols(&self) -> Vec<String> {
+        if !self.config.enable_dynamic_pairlist {
+            return self.config.symbols.clone();
+        }
+
+        let candidates = match self.exchange.get_tradable_products().await {
+            Ok(c) => c,
+            Err(e) => {
+                worker::console_warn!("Failed to fetch tradable products for pairlist: {}", e);
+                return self.config.symbols.clone();
+            }
+        };
+
+        let symbols = Pairlist::from_config(&self.config).apply(candidates);
+        if symbols.is_empty() {
+            worker::console_warn!("Dynamic pairlist produced no symbols, falling back to static watchlist");
+            return self.config.symbols.clone();
+        }
+
+        symbols
+    }
+
+    /// Scan symbols for new entry opportunities
+    async fn scan_for_entries(
+        &self,
+        state: &mut TradingStateData,
+        result: &mut TradingCycleResult,
+    ) -> Result<()> {
+        // Get available balance (USD + USDC, both count as cash) and total portfolio value
+        let (usd_balance, total_portfolio) = self.portfolio_snapshot(state).await?;
+
+        worker::console_log!("Portfolio: ${:.2} (${:.2} USD + ${:.2} positions)",
+            total_portfolio, usd_balance, total_portfolio - usd_balance);
+
+        // Re-classify the capital tier through the hysteresis/ramp manager (see
+        // `capital_tier::TierTransition`) rather than snapping instantly, so a
+        // portfolio oscillating near a tier boundary doesn't thrash the position cap.
+        let tier_transition = state
+            .tier_transition
+            .get_or_insert_with(|| TierTransition::new(Amount::from_dollars(total_portfolio)));
+        let tier_params = tier_transition.evaluate(
+            Amount::from_dollars(total_portfolio),
+            self.config.tier_hysteresis_percent,
+            self.config.tier_transition_cycles,
+        );
+
         // Check if we can open more positions
-        let max_new = self.strategy.max_new_positions(total_portfolio, state.positions.len());
+        let max_new = self.strategy.max_new_positions_with_tier(total_portfolio, state.positions.len(), &tier_params);
         if max_new == 0 {
-            worker::console_log!("At max positions ({}/{})", 
+            worker::console_log!("At max positions ({}/{})",
                 state.positions.len(), self.config.max_total_positions);
             return Ok(());
         }
-        
-        // Scan each configured symbol
-        for symbol in &self.config.symbols {
-            // Skip if already have position
-            if state.get_position(symbol).is_some() {
+
+        let scan_symbols = self.resolve_scan_symbols().await;
+
+        // Scan each candidate symbol
+        for symbol in &scan_symbols {
+            // Skip if already have a position, or an entry order for it is still awaiting fill
+            if state.get_position(symbol).is_some()
+                || state.pending_orders.iter().any(|o| {
+                    o.symbol == *symbol && matches!(o.purpose, PendingOrderPurpose::Entry { .. })
+                })
+            {
                 continue;
             }
-            
+
             // Get real product stats with 24h high/low
-            let stats = match self.client.get_product_stats(symbol).await {
+            let stats = match self.exchange.get_product_stats(symbol).await {
                 Ok(s) => s,
                 Err(e) => {
                     worker::console_warn!("Failed to get stats for {}: {}", symbol, e);
                     continue;
                 }
             };
-            
-            let analysis = self.strategy.analyze(
-                symbol, 
-                stats.price, 
-                stats.change_24h, 
-                stats.high_24h, 
+
+            // Layer this symbol's `pair_overrides` (if any) on top of the base config -
+            // see `Config::resolved_for` - so TP/SL/entry threshold/sizing/filters can
+            // differ per symbol instead of every pair sharing one global scalar.
+            let symbol_config = self.config.resolved_for(symbol);
+            let strategy = TradingStrategy::new(symbol_config.clone());
+
+            // Only pay for the extra candle fetch + pivot scan when S/R-driven entries are enabled
+            let sr = if symbol_config.enable_sr_filter {
+                let candles = self.exchange.get_recent_candles(symbol, 48).await;
+                let bars: Vec<OhlcvBar> = candles
+                    .iter()
+                    .filter_map(|c| {
+                        Some(OhlcvBar {
+                            high: c.high.parse().ok()?,
+                            low: c.low.parse().ok()?,
+                            close: c.close.parse().ok()?,
+                            volume: c.volume.parse().unwrap_or(0.0),
+                        })
+                    })
+                    .collect();
+                Some(SupportResistance::detect(
+                    &bars,
+                    stats.price,
+                    symbol_config.sr_pivot_window,
+                    symbol_config.sr_tolerance_percent,
+                    symbol_config.sr_min_cluster_volume,
+                ))
+            } else {
+                None
+            };
+
+            let analysis = strategy.analyze(
+                symbol,
+                stats.price,
+                stats.change_24h,
+                stats.high_24h,
                 stats.low_24h,
                 stats.is_uptrend,
                 stats.volume_24h,
+                sr.as_ref(),
             );
-            
+
             // Log rejection reason for debugging
             if let Some(reason) = &analysis.rejection_reason {
                 worker::console_log!("{}: Skipped - {}", symbol, reason);
             }
-            
-            if self.strategy.should_enter(&analysis, state.positions.len(), total_portfolio) {
+
+            if strategy.should_enter(&analysis, state.positions.len(), total_portfolio) {
+                if let Err(reason) = Protections::new(&symbol_config).is_entry_allowed(
+                    symbol,
+                    Utc::now(),
+                    &state.closed_trades,
+                    total_portfolio,
+                ) {
+                    worker::console_log!("{}: Blocked by protection - {}", symbol, reason);
+                    continue;
+                }
+
                 // Calculate volatility factor from 24h range
                 let range_percent = ((stats.high_24h - stats.low_24h) / stats.low_24h) * 100.0;
                 let volatility_factor = (range_percent / 3.0).max(0.5).min(2.0);  // Normalize around 3% range
-                
-                // Calculate dynamic position size
-                let sizing = self.strategy.calculate_position_size(total_portfolio, usd_balance, volatility_factor);
+
+                self.refresh_symbol_filters(state, symbol).await;
+
+                // Calculate dynamic position size (edge-scaled when enabled, using this symbol's realized history)
+                let sizing = strategy.calculate_position_size(
+                    total_portfolio,
+                    usd_balance,
+                    volatility_factor,
+                    stats.price,
+                    symbol,
+                    Some(&state.trade_history),
+                    state.get_symbol_filters(symbol),
+                );
                 
                 if !sizing.can_trade {
                     worker::console_log!("{}: Can't trade - {}", symbol, sizing.reason.unwrap_or_default());
@@ -226,59 +922,73 @@ impl TradingEngine {
                 
                 let position_size = sizing.size;
                 let quantity = position_size / stats.price;
-                
-                // Calculate dynamic TP/SL based on current volatility
-                let (stop_loss_price, take_profit_price, sl_pct, tp_pct) = 
-                    self.strategy.calculate_dynamic_tp_sl(stats.price, range_percent);
-                
-                worker::console_log!("{}: Opening ${:.2} position | SL: ${:.2} (-{:.1}%) | TP: ${:.2} (+{:.1}%)",
-                    symbol, position_size, stop_loss_price, sl_pct, take_profit_price, tp_pct);
-                
-                // Place buy order
-                let order = match self.client.market_buy(symbol, position_size).await {
+
+                let side = if analysis.signal == TradingSignal::Short {
+                    PositionSide::Short
+                } else {
+                    PositionSide::Long
+                };
+
+                // Calculate dynamic TP/SL based on current volatility (mirrored for shorts)
+                let (stop_loss_price, take_profit_price, sl_pct, tp_pct) =
+                    strategy.calculate_dynamic_tp_sl(stats.price, range_percent, side);
+
+                worker::console_log!("{}: Opening {:?} ${:.2} position | SL: ${:.2} ({:.1}%) | TP: ${:.2} ({:.1}%)",
+                    symbol, side, position_size, stop_loss_price, sl_pct, take_profit_price, tp_pct);
+
+                // Place entry order: buy to open a long, sell to open a short. Don't assume an
+                // instant fill at `stats.price` - track it as pending and let
+                // `reconcile_pending_orders` open the position at the actual fill.
+                let (order_result, order_side, usd, qty) = match side {
+                    PositionSide::Long => (
+                        self.exchange.market_buy(symbol, position_size).await,
+                        OrderSide::Buy,
+                        Some(position_size),
+                        None,
+                    ),
+                    PositionSide::Short => (
+                        self.exchange.market_sell(symbol, quantity).await,
+                        OrderSide::Sell,
+                        None,
+                        Some(quantity),
+                    ),
+                };
+
+                let order = match order_result {
                     Ok(o) => o,
                     Err(e) => {
-                        worker::console_warn!("Failed to buy {}: {}", symbol, e);
+                        worker::console_warn!("Failed to place entry order for {}: {}", symbol, e);
                         continue;
                     }
                 };
-                
-                // Create position with dynamic TP/SL
-                let position = Position {
-                    symbol: symbol.clone(),
-                    quantity,
-                    entry_price: stats.price,
-                    entry_time: Utc::now().to_rfc3339(),
-                    high_water_mark: None,
-                    stop_loss_price: Some(stop_loss_price),
-                    take_profit_price: Some(take_profit_price),
-                    entry_volatility: Some(range_percent),
+
+                let Some(order_id) = order.order_id else {
+                    worker::console_warn!(
+                        "Entry order for {} returned no order_id, cannot track fill",
+                        symbol
+                    );
+                    continue;
                 };
-                
-                let trade = Trade {
-                    id: order.order_id.unwrap_or_default(),
+
+                state.pending_orders.push(PendingOrder {
+                    order_id,
                     symbol: symbol.clone(),
-                    side: OrderSide::Buy,
-                    quantity,
-                    price: stats.price,
-                    total_value: position_size,
-                    timestamp: Utc::now().to_rfc3339(),
-                    pnl: None,
-                };
-                
-                // Update state
-                state.add_position(position);
-                state.total_trades += 1;
-                state.increment_daily_trades(&Utc::now().format("%Y-%m-%d").to_string());
-                
-                result.positions_opened += 1;
-                result.trades.push(trade);
-                
+                    side: order_side,
+                    placed_at: Utc::now().to_rfc3339(),
+                    purpose: PendingOrderPurpose::Entry {
+                        stop_loss_price,
+                        take_profit_price,
+                        entry_volatility: range_percent,
+                        side,
+                    },
+                    requested_usd: usd,
+                    requested_quantity: qty,
+                    attempt: 0,
+                });
+
                 worker::console_log!(
-                    "Opened {} position: {} @ {} (confidence: {:.0}%)",
+                    "Entry order placed for {} (confidence: {:.0}%), awaiting fill confirmation",
                     symbol,
-                    quantity,
-                    stats.price,
                     analysis.confidence * 100.0
                 );
             }
@@ -294,13 +1004,14 @@ impl TradingEngine {
         let mut total_pnl = 0.0;
         
         for position in &state.positions {
-            let current_price = self.client.get_price(&position.symbol).await
+            let current_price = self.exchange.get_price(&position.symbol).await
                 .unwrap_or(position.entry_price);
             
             let pnl = position.unrealized_pnl(current_price);
             let pnl_percent = position.unrealized_pnl_percent(current_price);
+            let net_pnl_percent = pnl_percent - self.strategy.accrued_funding_percent(position);
             let value = current_price * position.quantity;
-            
+
             positions_with_pnl.push(PositionWithPnl {
                 symbol: position.symbol.clone(),
                 quantity: position.quantity,
@@ -308,6 +1019,8 @@ impl TradingEngine {
                 current_price,
                 unrealized_pnl: pnl,
                 unrealized_pnl_percent: pnl_percent,
+                net_pnl_percent,
+                realized_pnl: position.realized_pnl,
             });
             
             total_value += value;
@@ -321,16 +1034,43 @@ impl TradingEngine {
         })
     }
     
+    /// Replay `Config::symbols` against historical bars from `source`, one
+    /// `BacktestReport` per symbol. Reuses the exact analyze/should_enter/check_exit/
+    /// sizing pipeline `run_cycle` drives live (via `Backtester`), so strategy/config
+    /// changes can be validated offline before they touch real capital.
+    pub fn backtest(&self, source: &dyn HistoricalDataSource) -> HashMap<String, BacktestReport> {
+        self.config
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let candles = source.bars_for(symbol);
+                (symbol.clone(), Backtester::run(&candles, &self.config))
+            })
+            .collect()
+    }
+
     /// Get trading status
     pub fn get_status(&self, state: &TradingStateData) -> StatusResponse {
+        use rust_decimal::prelude::ToPrimitive;
         StatusResponse {
             enabled: state.enabled,
             positions_count: state.positions.len(),
             total_trades: state.total_trades,
-            total_pnl: state.total_pnl,
+            total_pnl: state.total_pnl.to_f64().unwrap_or(0.0),
             daily_trades: state.daily_trades,
             consecutive_errors: state.consecutive_errors,
             last_cycle: state.last_cycle_time.clone(),
+            win_rate: state.win_rate(),
+            profit_factor: state.profit_factor(),
+            max_drawdown: state.max_drawdown.to_f64().unwrap_or(0.0),
+        }
+    }
+
+    /// Get closed-trade performance analytics
+    pub fn get_performance(&self, state: &TradingStateData) -> PerformanceResponse {
+        PerformanceResponse {
+            closed_trade_count: state.closed_trades.len(),
+            stats: state.performance_stats(),
         }
     }
 }
@@ -338,7 +1078,9 @@ impl TradingEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     // Integration tests would go here with mocked client
     // For now, unit tests cover strategy and types modules
+    // (TradingEngine::backtest is covered indirectly via backtest::Backtester's tests,
+    // which it delegates to per-symbol)
 }
@@ -1,7 +1,22 @@
 //! Common types for the trading system
 //!
 //! All shared data structures used across modules.
+//!
+//! `TradingStateData`'s running P&L accumulators (`total_pnl`, `gross_profit`,
+//! `gross_loss`, `equity_peak`, `max_drawdown`) are `Decimal`, not `f64`: they're
+//! persisted in KV and added to every cycle for the life of the bot,
+//! so `f64` rounding error compounds indefinitely and eventually shows up as
+//! `50000.00000001` in a JSON response. Per-event fields (`Position`/`Trade` prices,
+//! quantities, and single-trade P&L) stay `f64` - they're computed fresh from a market
+//! price or order fill each time rather than accumulated onto, so they don't carry the
+//! same drift risk, and leaving them as `f64` avoids rewriting the strategy/sizing math
+//! that already treats them as plain floats.
 
+use crate::capital_tier::TierTransition;
+use crate::edge::TradeHistory;
+use crate::strategy::ExitReason;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc, Weekday};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 /// A trading position
@@ -22,20 +37,87 @@ pub struct Position {
     /// Volatility (ATR%) at entry time for reference
     #[serde(default)]
     pub entry_volatility: Option<f64>,
+    /// Bitmask of which scaled take-profit targets (by index into `Config::tp_levels`)
+    /// have already fired, so re-evaluation at the same price doesn't double-close.
+    #[serde(default)]
+    pub targets_hit: u8,
+    /// Quantity remaining open after any partial take-profits (starts at full `quantity`).
+    #[serde(default)]
+    pub remaining_quantity: Option<f64>,
+    /// Long or short. Defaults to `Long` for positions persisted before shorting existed.
+    #[serde(default)]
+    pub side: PositionSide,
+    /// Low water mark for short trailing stops (mirror of `high_water_mark`).
+    #[serde(default)]
+    pub low_water_mark: Option<f64>,
+    /// Number of DCA (dollar-cost-average) adds applied to this position so far,
+    /// capped by `Config::max_entry_adjustments`.
+    #[serde(default)]
+    pub entry_adjustments: u8,
+    /// Cost-of-carry accrued since entry, as a percent of entry notional. Tracked
+    /// for reporting; always `0.0` while `Config::funding_rate_per_hour` is `0.0`.
+    #[serde(default)]
+    pub cumulative_funding: f64,
+    /// Order id of an in-flight full-close order for this position, set while
+    /// awaiting fill confirmation (see `TradingEngine::reconcile_pending_orders`) so
+    /// the same exit isn't re-placed every cycle while Coinbase is still filling it.
+    #[serde(default)]
+    pub pending_exit_order_id: Option<String>,
+    /// Dollar P&L already banked from partial take-profit closes on this position
+    /// (see `Config::tp_levels`). Kept separate from the unrealized P&L helpers below,
+    /// which only reflect the quantity still open.
+    #[serde(default)]
+    pub realized_pnl: f64,
+    /// RFC 3339 instant this position must roll over by, for dated/perpetual-style
+    /// instruments. `None` for ordinary spot positions, which never expire. See
+    /// `needs_rollover`/`TradingStateData::rollover_expiring`.
+    #[serde(default)]
+    pub expiry_time: Option<String>,
+}
+
+/// Direction of a position
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PositionSide {
+    #[default]
+    Long,
+    Short,
 }
 
 impl Position {
-    /// Calculate unrealized P&L at current price
+    /// Quantity still open after any partial take-profit closes (see
+    /// `Config::tp_levels`/`remaining_quantity`) - the full entry `quantity` for a
+    /// position that's never been partially closed.
+    pub fn open_quantity(&self) -> f64 {
+        self.remaining_quantity.unwrap_or(self.quantity)
+    }
+
+    /// Quantity already closed out via partial take-profits - `0.0` unless
+    /// `remaining_quantity` has been trimmed below the original entry `quantity`.
+    pub fn closed_quantity(&self) -> f64 {
+        self.quantity - self.open_quantity()
+    }
+
+    /// Calculate unrealized P&L at current price, on the quantity still open - a
+    /// partially closed position's booked gain on the closed portion lives in
+    /// `realized_pnl` instead, so it isn't double-counted here.
     pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
-        (current_price - self.entry_price) * self.quantity
+        let open_quantity = self.open_quantity();
+        match self.side {
+            PositionSide::Long => (current_price - self.entry_price) * open_quantity,
+            PositionSide::Short => (self.entry_price - current_price) * open_quantity,
+        }
     }
 
     /// Calculate unrealized P&L as percentage
     pub fn unrealized_pnl_percent(&self, current_price: f64) -> f64 {
-        (current_price - self.entry_price) / self.entry_price * 100.0
+        match self.side {
+            PositionSide::Long => (current_price - self.entry_price) / self.entry_price * 100.0,
+            PositionSide::Short => (self.entry_price - current_price) / self.entry_price * 100.0,
+        }
     }
 
-    /// Update high water mark for trailing stop
+    /// Update high water mark for trailing stop (long positions)
     pub fn update_high_water_mark(&mut self, current_price: f64) {
         match self.high_water_mark {
             Some(hwm) if current_price > hwm => {
@@ -47,6 +129,138 @@ impl Position {
             _ => {}
         }
     }
+
+    /// Update low water mark for trailing stop (short positions)
+    pub fn update_low_water_mark(&mut self, current_price: f64) {
+        match self.low_water_mark {
+            Some(lwm) if current_price < lwm => {
+                self.low_water_mark = Some(current_price);
+            }
+            None if current_price < self.entry_price => {
+                self.low_water_mark = Some(current_price);
+            }
+            _ => {}
+        }
+    }
+
+    /// Update whichever trailing extreme applies to this position's side
+    pub fn update_trailing_extreme(&mut self, current_price: f64) {
+        match self.side {
+            PositionSide::Long => self.update_high_water_mark(current_price),
+            PositionSide::Short => self.update_low_water_mark(current_price),
+        }
+    }
+
+    /// Whether `order_type` would trigger at `current_price` this cycle. Trailing
+    /// variants recompute their effective trigger off `high_water_mark`/
+    /// `low_water_mark` every call, so a `TrailingStop` rides up with the position
+    /// instead of freezing at the level it had when the order was placed.
+    pub fn conditional_trigger(&self, order_type: &OrderType, current_price: f64) -> bool {
+        match order_type {
+            OrderType::Market | OrderType::Limit { .. } => false,
+            OrderType::StopMarket { trigger }
+            | OrderType::MarketIfTouched { trigger }
+            | OrderType::StopLimit { trigger, .. }
+            | OrderType::LimitIfTouched { trigger, .. } => self.crosses(*trigger, current_price),
+            OrderType::TrailingStop { amount_or_pct } => {
+                self.crosses(self.trailing_trigger(*amount_or_pct), current_price)
+            }
+            OrderType::TrailingLimit { amount_or_pct, .. } => {
+                self.crosses(self.trailing_trigger(*amount_or_pct), current_price)
+            }
+        }
+    }
+
+    /// A stop/trigger fires when price moves against the position past `trigger`:
+    /// at or below it for a long, at or above it for a short.
+    fn crosses(&self, trigger: f64, current_price: f64) -> bool {
+        match self.side {
+            PositionSide::Long => current_price <= trigger,
+            PositionSide::Short => current_price >= trigger,
+        }
+    }
+
+    /// Effective stop price for a trailing order, computed off the current
+    /// high/low-water-mark (falling back to `entry_price` before one is set) rather
+    /// than a level fixed at entry.
+    fn trailing_trigger(&self, amount_or_pct: TrailingAmount) -> f64 {
+        let extreme = match self.side {
+            PositionSide::Long => self.high_water_mark.unwrap_or(self.entry_price),
+            PositionSide::Short => self.low_water_mark.unwrap_or(self.entry_price),
+        };
+        let offset = match amount_or_pct {
+            TrailingAmount::Amount(amount) => amount,
+            TrailingAmount::Percent(pct) => extreme * pct / 100.0,
+        };
+        match self.side {
+            PositionSide::Long => extreme - offset,
+            PositionSide::Short => extreme + offset,
+        }
+    }
+
+    /// Whether this position's `expiry_time` has passed as of `now`. Always `false`
+    /// for ordinary spot positions (`expiry_time` is `None`); an unparseable
+    /// `expiry_time` is treated as not-yet-due rather than rolling over on bad data.
+    pub fn needs_rollover(&self, now: DateTime<Utc>) -> bool {
+        match &self.expiry_time {
+            Some(expiry) => DateTime::parse_from_rfc3339(expiry)
+                .map(|dt| dt.with_timezone(&Utc) <= now)
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+}
+
+/// Next scheduled rollover boundary at or after `now`, for a dated position whose
+/// `expiry_time` just elapsed: the coming Sunday at 15:00:00 UTC, or the Sunday after
+/// if `now` already sits at or past that instant (so a position rolling over exactly
+/// on the boundary still gets a full week rather than expiring immediately again).
+pub fn next_rollover_boundary(now: DateTime<Utc>) -> DateTime<Utc> {
+    let days_until_sunday =
+        (Weekday::Sun.num_days_from_monday() + 7 - now.weekday().num_days_from_monday()) % 7;
+    let candidate_date = now.date_naive() + Duration::days(days_until_sunday as i64);
+    let candidate = Utc.from_utc_datetime(&candidate_date.and_hms_opt(15, 0, 0).unwrap());
+
+    if candidate <= now {
+        candidate + Duration::days(7)
+    } else {
+        candidate
+    }
+}
+
+/// Either a fixed-price or fixed-percent offset for a trailing order, recomputed off
+/// the position's running high/low-water-mark rather than a price fixed at entry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TrailingAmount {
+    Amount(f64),
+    Percent(f64),
+}
+
+/// Conditional/trailing order semantics beyond a naked market fill, modeled on the
+/// order types mature broker SDKs expose. `Position::conditional_trigger` evaluates
+/// these against a position's stored `stop_loss_price`/`take_profit_price`/
+/// `high_water_mark`, extending those ATR-based fields instead of leaving trigger
+/// logic scattered through the cycle code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    Market,
+    Limit { price: f64 },
+    StopMarket { trigger: f64 },
+    StopLimit { trigger: f64, limit: f64 },
+    LimitIfTouched { trigger: f64, limit: f64 },
+    MarketIfTouched { trigger: f64 },
+    /// Trails the position's high-water-mark (longs) or low-water-mark (shorts), so
+    /// e.g. `Percent(5.0)` keeps the stop 5% below the peak as the peak rises.
+    TrailingStop { amount_or_pct: TrailingAmount },
+    /// Like `TrailingStop`, but once triggered rests as a limit order `offset` away
+    /// from the trailing trigger price rather than a market order.
+    TrailingLimit { amount_or_pct: TrailingAmount, offset: f64 },
+}
+
+impl Default for OrderType {
+    fn default() -> Self {
+        OrderType::Market
+    }
 }
 
 /// Order side (buy or sell)
@@ -66,6 +280,80 @@ impl std::fmt::Display for OrderSide {
     }
 }
 
+/// What a `PendingOrder` will do once its fill is confirmed (see
+/// `TradingEngine::reconcile_pending_orders`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PendingOrderPurpose {
+    /// Opening a new position. Carries the TP/SL/volatility context computed at
+    /// signal time so the position can be built once the real fill is known.
+    Entry {
+        stop_loss_price: f64,
+        take_profit_price: f64,
+        entry_volatility: f64,
+        side: PositionSide,
+    },
+    /// Fully closing an existing position. `reason` is kept only for logging.
+    Exit { reason: String },
+}
+
+/// An order placed but not yet confirmed filled. Coinbase orders can be delayed or
+/// partially filled rather than executing instantly at the quoted price, so entries
+/// and full closes are tracked here until `TradingEngine::reconcile_pending_orders`
+/// confirms the actual fill price/quantity. Persisted in `TradingStateData` so a
+/// worker restart mid-fill doesn't lose track of it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingOrder {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub placed_at: String,
+    pub purpose: PendingOrderPurpose,
+    /// USD quote size, for a buy.
+    #[serde(default)]
+    pub requested_usd: Option<f64>,
+    /// Base-asset quantity, for a sell.
+    #[serde(default)]
+    pub requested_quantity: Option<f64>,
+    /// How many times this order has been re-placed after timing out unfilled,
+    /// capped by `Config::max_order_retries`.
+    #[serde(default)]
+    pub attempt: u32,
+}
+
+/// Ring-buffer cap for `TradingStateData::closed_trades` - enough history for
+/// `PerformanceStats` to be meaningful without the persisted KV state growing
+/// unbounded over the life of the bot (unlike `total_pnl`/`winning_trades`/etc, which
+/// are running sums and stay unbounded on purpose).
+const MAX_CLOSED_TRADES: usize = 500;
+
+/// A fully realized close or partial take-profit, recorded by `record_closed_trade`
+/// alongside the running `total_pnl`/`winning_trades`/`gross_profit` accumulators.
+/// Kept as its own bounded series so `PerformanceStats` can walk the trade-by-trade
+/// sequence (for `max_drawdown`'s equity curve and `sharpe`'s per-trade returns)
+/// instead of only having access to lifetime running totals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedTrade {
+    pub symbol: String,
+    pub side: PositionSide,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub quantity: f64,
+    pub pnl: f64,
+    pub closed_at: String,
+    /// `ExitReason`'s `Display` string (e.g. "Stop Loss", "Take Profit") - kept as a
+    /// plain string rather than the enum itself, matching `PendingOrderPurpose::Exit`'s
+    /// `reason` field, since nothing here needs to match on the specific variant, only
+    /// bucket/count by it. `None` only for trades recorded before this field existed.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// The position's `entry_time`, carried along so hold duration can be computed
+    /// against `closed_at` (see `history::record_closed_trade`). `#[serde(default)]`
+    /// so trades recorded before this field existed deserialize as `None` rather than
+    /// failing.
+    #[serde(default)]
+    pub opened_at: Option<String>,
+}
+
 /// A completed trade
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -78,6 +366,26 @@ pub struct Trade {
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pnl: Option<f64>,
+    /// Order type this trade was executed as. `#[serde(default)]` so trades persisted
+    /// before `OrderType` existed deserialize as `Market` (the only kind placed then).
+    #[serde(default)]
+    pub order_type: OrderType,
+    /// Exchange fee charged for this fill, in quote currency. `#[serde(default)]` so
+    /// trades persisted before fee tracking existed deserialize as `0.0` rather than
+    /// failing; not yet populated from a real fill, since Coinbase's fill response
+    /// isn't threaded through here - see `ledger::append_trade`, which hashes this
+    /// field into the audit log leaf alongside the rest of `Trade`.
+    #[serde(default)]
+    pub fee: f64,
+    /// Side of the *position* this fill belongs to - not to be confused with `side`
+    /// above, which is the order's buy/sell direction and flips between a short's
+    /// entry (Sell) and exit (Buy). `LotLedger::record_open`/`record_close` need
+    /// this to tell an opening Sell (short entry) apart from a closing Sell (long
+    /// exit) - see `run_trading_cycle` in lib.rs. `#[serde(default)]` so trades
+    /// persisted before this field existed deserialize as `Long`, the only side
+    /// the bot traded at the time.
+    #[serde(default)]
+    pub position_side: PositionSide,
 }
 
 /// Result of a trading cycle
@@ -104,14 +412,88 @@ impl Default for TradingCycleResult {
     }
 }
 
+/// Keyed storage for `TradingStateData::positions`, indexed by `Position::symbol`
+/// so `get_position`/`get_position_mut`/`remove_position` are O(1) instead of the
+/// O(n) `Vec` scan that got quadratic as the traded universe grew (and repeated once
+/// per symbol per cycle). Serializes/deserializes as a plain JSON array rather than
+/// an object keyed by symbol, so state already persisted in KV - and anyone
+/// reading `/api/positions` - round-trips exactly as before.
+#[derive(Debug, Clone, Default)]
+pub struct PositionStore(std::collections::HashMap<String, Position>);
+
+impl PositionStore {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&Position> {
+        self.0.get(symbol)
+    }
+
+    pub fn get_mut(&mut self, symbol: &str) -> Option<&mut Position> {
+        self.0.get_mut(symbol)
+    }
+
+    /// Insert (or replace) a position, keyed by its own `symbol`.
+    pub fn insert(&mut self, position: Position) {
+        self.0.insert(position.symbol.clone(), position);
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> Option<Position> {
+        self.0.remove(symbol)
+    }
+
+    pub fn iter(&self) -> std::collections::hash_map::Values<'_, String, Position> {
+        self.0.values()
+    }
+}
+
+impl<'a> IntoIterator for &'a PositionStore {
+    type Item = &'a Position;
+    type IntoIter = std::collections::hash_map::Values<'a, String, Position>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.values()
+    }
+}
+
+impl IntoIterator for PositionStore {
+    type Item = Position;
+    type IntoIter = std::collections::hash_map::IntoValues<String, Position>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_values()
+    }
+}
+
+impl Serialize for PositionStore {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut positions: Vec<&Position> = self.0.values().collect();
+        positions.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        positions.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionStore {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let positions = Vec::<Position>::deserialize(deserializer)?;
+        Ok(PositionStore(positions.into_iter().map(|p| (p.symbol.clone(), p)).collect()))
+    }
+}
+
 /// Persistent trading state stored in Durable Object
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TradingStateData {
     /// Trading enabled flag
     pub enabled: bool,
 
-    /// Current open positions
-    pub positions: Vec<Position>,
+    /// Current open positions, keyed by symbol (see `PositionStore`)
+    #[serde(default)]
+    pub positions: PositionStore,
 
     /// Last trading cycle timestamp
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -120,8 +502,12 @@ pub struct TradingStateData {
     /// Total number of trades executed
     pub total_trades: u64,
 
-    /// Total realized P&L (USD)
-    pub total_pnl: f64,
+    /// Total realized P&L (USD). `Decimal`, serialized as an exact string (see the
+    /// module doc comment) rather than a JSON number - a breaking format change for
+    /// any already-persisted state, traded deliberately for no more drift in a value
+    /// that accumulates for the life of the bot.
+    #[serde(default, with = "crate::money::decimal_str")]
+    pub total_pnl: Decimal,
 
     /// Consecutive errors counter
     pub consecutive_errors: u32,
@@ -132,6 +518,73 @@ pub struct TradingStateData {
     /// Day of last trade (for daily reset)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_trade_day: Option<String>,
+
+    /// Realized per-symbol trade outcomes, feeding edge/expectancy-based sizing
+    /// (see `edge::TradeHistory`)
+    #[serde(default)]
+    pub trade_history: TradeHistory,
+
+    /// Entry/full-close orders awaiting fill confirmation (see `PendingOrder` and
+    /// `TradingEngine::reconcile_pending_orders`).
+    #[serde(default)]
+    pub pending_orders: Vec<PendingOrder>,
+
+    /// Count of closed trades with positive realized P&L, for `StatusResponse::win_rate`
+    #[serde(default)]
+    pub winning_trades: u64,
+    /// Count of closed trades with non-positive realized P&L
+    #[serde(default)]
+    pub losing_trades: u64,
+    /// Sum of realized P&L across winning trades, for `StatusResponse::profit_factor`
+    #[serde(default, with = "crate::money::decimal_str")]
+    pub gross_profit: Decimal,
+    /// Sum of |realized P&L| across losing trades
+    #[serde(default, with = "crate::money::decimal_str")]
+    pub gross_loss: Decimal,
+    /// Highest `total_pnl` has ever reached, tracked so `max_drawdown` survives
+    /// worker restarts instead of resetting to the current value
+    #[serde(default, with = "crate::money::decimal_str")]
+    pub equity_peak: Decimal,
+    /// Largest peak-to-current drop in `total_pnl` observed so far (USD)
+    #[serde(default, with = "crate::money::decimal_str")]
+    pub max_drawdown: Decimal,
+
+    /// Per-symbol tick/lot-size metadata, refreshed from an exchange-info endpoint.
+    /// Consulted by `TradingEngine` to round a fill's price/quantity onto the
+    /// exchange's grid before a `Position` is constructed from it (see
+    /// `SymbolFilters::round_order`). Symbols with no entry here are passed through
+    /// unrounded - the Coinbase API still applies its own rejection/rounding.
+    #[serde(default)]
+    pub symbol_filters: std::collections::HashMap<String, crate::symbol_filters::SymbolFilters>,
+
+    /// Option legs held for their risk exposure rather than traded directly on
+    /// Coinbase's spot book (see `crate::options`). Folded into `net_delta` alongside
+    /// `positions` so a single hedge trigger covers both.
+    #[serde(default)]
+    pub option_legs: Vec<crate::options::OptionLeg>,
+
+    /// Bounded history of realized closes/partial-closes, capped at `MAX_CLOSED_TRADES`
+    /// (oldest dropped first). Feeds `performance_stats`; the `winning_trades`/
+    /// `gross_profit`/`max_drawdown` fields above stay the unbounded source of truth
+    /// for `StatusResponse`.
+    #[serde(default)]
+    pub closed_trades: Vec<ClosedTrade>,
+
+    /// Per-symbol open-lot ledgers (see `crate::lots::LotLedger`), recording every
+    /// buy/sell fill so `/api/portfolio` can report a per-position realized
+    /// breakdown and weighted cost basis instead of a single blended
+    /// `total_invested` figure. `#[serde(default)]` so state persisted before lot
+    /// accounting existed deserializes with no ledgers rather than failing.
+    #[serde(default)]
+    pub lot_ledgers: std::collections::HashMap<String, crate::lots::LotLedger>,
+
+    /// Hysteresis/ramp state for `CapitalTier` transitions (see
+    /// `crate::capital_tier::TierTransition`). `None` until the first cycle that
+    /// computes a portfolio value seeds it - `#[serde(default)]` so state persisted
+    /// before this feature existed deserializes with no transition in progress
+    /// rather than failing.
+    #[serde(default)]
+    pub tier_transition: Option<TierTransition>,
 }
 
 impl TradingStateData {
@@ -161,27 +614,297 @@ impl TradingStateData {
 
     /// Get position by symbol
     pub fn get_position(&self, symbol: &str) -> Option<&Position> {
-        self.positions.iter().find(|p| p.symbol == symbol)
+        self.positions.get(symbol)
     }
 
     /// Get mutable position by symbol
     pub fn get_position_mut(&mut self, symbol: &str) -> Option<&mut Position> {
-        self.positions.iter_mut().find(|p| p.symbol == symbol)
+        self.positions.get_mut(symbol)
     }
 
     /// Add a new position
     pub fn add_position(&mut self, position: Position) {
-        self.positions.push(position);
+        self.positions.insert(position);
     }
 
     /// Remove a position by symbol
     pub fn remove_position(&mut self, symbol: &str) -> Option<Position> {
-        if let Some(idx) = self.positions.iter().position(|p| p.symbol == symbol) {
-            Some(self.positions.remove(idx))
+        self.positions.remove(symbol)
+    }
+
+    /// Remove a pending order by id once it's been confirmed filled or given up on
+    pub fn remove_pending_order(&mut self, order_id: &str) {
+        self.pending_orders.retain(|o| o.order_id != order_id);
+    }
+
+    /// Tick/lot-size filters for `symbol`, if an exchange-info refresh has populated them
+    pub fn get_symbol_filters(&self, symbol: &str) -> Option<&crate::symbol_filters::SymbolFilters> {
+        self.symbol_filters.get(symbol)
+    }
+
+    /// Store (or replace) `symbol`'s tick/lot-size filters
+    pub fn set_symbol_filters(&mut self, symbol: &str, filters: crate::symbol_filters::SymbolFilters) {
+        self.symbol_filters.insert(symbol.to_string(), filters);
+    }
+
+    /// Book a closed trade's (or partial close's) realized P&L into `total_pnl` and
+    /// the running win/loss/drawdown stats behind `StatusResponse::win_rate`,
+    /// `profit_factor`, and `max_drawdown`, and append it to `closed_trades` (capped
+    /// at `MAX_CLOSED_TRADES`, oldest first out) for `performance_stats`. Called once
+    /// per full close or partial take-profit, so it's also where `total_pnl` itself
+    /// gets updated. `trade.pnl` is `f64` (callers compute it from `f64` prices/
+    /// quantities) but accumulates into the `Decimal` fields - see the module doc
+    /// comment on why those aren't `f64`.
+    pub fn record_closed_trade(&mut self, trade: ClosedTrade) {
+        let pnl = crate::money::decimal_from_f64(trade.pnl).unwrap_or_default();
+        self.total_pnl += pnl;
+
+        if pnl > Decimal::ZERO {
+            self.winning_trades += 1;
+            self.gross_profit += pnl;
+        } else {
+            self.losing_trades += 1;
+            self.gross_loss += pnl.abs();
+        }
+
+        if self.total_pnl > self.equity_peak {
+            self.equity_peak = self.total_pnl;
+        }
+        let drawdown = self.equity_peak - self.total_pnl;
+        if drawdown > self.max_drawdown {
+            self.max_drawdown = drawdown;
+        }
+
+        self.closed_trades.push(trade);
+        if self.closed_trades.len() > MAX_CLOSED_TRADES {
+            self.closed_trades.remove(0);
+        }
+    }
+
+    /// Roll `symbol`'s position over if its `expiry_time` has passed as of `now`:
+    /// close it at `current_price` (booking realized P&L via `record_closed_trade`)
+    /// and reopen an equivalent position at the same price with `expiry_time`
+    /// advanced to `next_rollover_boundary`. Returns the `(close, reopen)` trade pair
+    /// on a rollover, `None` if `symbol` has no position or it isn't due yet.
+    pub fn rollover_expiring(&mut self, symbol: &str, current_price: f64, now: DateTime<Utc>) -> Option<(Trade, Trade)> {
+        if !self.get_position(symbol).is_some_and(|p| p.needs_rollover(now)) {
+            return None;
+        }
+        let position = self.remove_position(symbol)?;
+
+        let pnl = position.unrealized_pnl(current_price);
+        let timestamp = now.to_rfc3339();
+        self.record_closed_trade(ClosedTrade {
+            symbol: symbol.to_string(),
+            side: position.side,
+            entry_price: position.entry_price,
+            exit_price: current_price,
+            quantity: position.quantity,
+            pnl,
+            closed_at: timestamp.clone(),
+            reason: Some(ExitReason::TimeExpired.to_string()),
+            opened_at: Some(position.entry_time.clone()),
+        });
+
+        let (close_side, reopen_side) = match position.side {
+            PositionSide::Long => (OrderSide::Sell, OrderSide::Buy),
+            PositionSide::Short => (OrderSide::Buy, OrderSide::Sell),
+        };
+        let total_value = current_price * position.quantity;
+
+        let close_trade = Trade {
+            id: format!("rollover-close-{symbol}-{timestamp}"),
+            symbol: symbol.to_string(),
+            side: close_side,
+            quantity: position.quantity,
+            price: current_price,
+            total_value,
+            timestamp: timestamp.clone(),
+            pnl: Some(pnl),
+            order_type: OrderType::Market,
+            fee: 0.0,
+            position_side: position.side,
+        };
+        let reopen_trade = Trade {
+            id: format!("rollover-open-{symbol}-{timestamp}"),
+            symbol: symbol.to_string(),
+            side: reopen_side,
+            quantity: position.quantity,
+            price: current_price,
+            total_value,
+            timestamp: timestamp.clone(),
+            pnl: None,
+            order_type: OrderType::Market,
+            fee: 0.0,
+            position_side: position.side,
+        };
+        self.total_trades += 2;
+
+        self.add_position(Position {
+            entry_price: current_price,
+            entry_time: timestamp,
+            expiry_time: Some(next_rollover_boundary(now).to_rfc3339()),
+            high_water_mark: None,
+            low_water_mark: None,
+            targets_hit: 0,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            ..position
+        });
+
+        Some((close_trade, reopen_trade))
+    }
+
+    /// Fraction of closed trades that were winners, for `StatusResponse::win_rate`.
+    /// `None` until at least one trade has closed.
+    pub fn win_rate(&self) -> Option<f64> {
+        let total = self.winning_trades + self.losing_trades;
+        if total == 0 {
+            None
         } else {
+            Some(self.winning_trades as f64 / total as f64)
+        }
+    }
+
+    /// Gross profit / |gross loss|, for `StatusResponse::profit_factor`. `None` when
+    /// there's no loss to divide by yet (avoids a misleading infinite ratio).
+    pub fn profit_factor(&self) -> Option<f64> {
+        if self.gross_loss.is_zero() {
             None
+        } else {
+            use rust_decimal::prelude::ToPrimitive;
+            (self.gross_profit / self.gross_loss).to_f64()
         }
     }
+
+    /// Portfolio delta: `quantity * delta` summed across `option_legs` (priced
+    /// Black-Scholes off `spot_prices`, looked up by each leg's `symbol`), plus
+    /// signed `quantity` for spot/linear `positions` (long +1 delta/unit, short -1).
+    /// A leg or position whose symbol is missing from `spot_prices` is skipped - there's
+    /// no price to compute its delta against.
+    pub fn net_delta(&self, spot_prices: &std::collections::HashMap<String, f64>, now: DateTime<Utc>, risk_free_rate: f64) -> f64 {
+        let options_delta: f64 = self
+            .option_legs
+            .iter()
+            .filter_map(|leg| spot_prices.get(&leg.symbol).map(|&spot| leg.quantity * leg.delta(spot, now, risk_free_rate)))
+            .sum();
+
+        let positions_delta: f64 = self
+            .positions
+            .iter()
+            .filter(|p| spot_prices.contains_key(&p.symbol))
+            .map(|p| match p.side {
+                PositionSide::Long => p.quantity,
+                PositionSide::Short => -p.quantity,
+            })
+            .sum();
+
+        options_delta + positions_delta
+    }
+
+    /// When `|net_delta|` exceeds `threshold`, the spot `OrderSide`/quantity needed
+    /// to flatten it back toward zero: sell if net delta is too long, buy if too
+    /// short. `None` if the portfolio is already within `threshold`.
+    pub fn hedge_order(
+        &self,
+        spot_prices: &std::collections::HashMap<String, f64>,
+        now: DateTime<Utc>,
+        risk_free_rate: f64,
+        threshold: f64,
+    ) -> Option<(OrderSide, f64)> {
+        let net_delta = self.net_delta(spot_prices, now, risk_free_rate);
+        if net_delta.abs() <= threshold {
+            return None;
+        }
+        if net_delta > 0.0 {
+            Some((OrderSide::Sell, net_delta))
+        } else {
+            Some((OrderSide::Buy, -net_delta))
+        }
+    }
+
+    /// Closed-trade analytics computed over `closed_trades`, for `/api/performance`.
+    /// Independent of the `winning_trades`/`gross_profit`/`max_drawdown` fields above:
+    /// those are unbounded running totals for the bot's full lifetime, this walks
+    /// whatever's still in the bounded ring buffer.
+    pub fn performance_stats(&self) -> PerformanceStats {
+        let trades = &self.closed_trades;
+        if trades.is_empty() {
+            return PerformanceStats::default();
+        }
+
+        let wins: Vec<f64> = trades.iter().map(|t| t.pnl).filter(|pnl| *pnl > 0.0).collect();
+        let losses: Vec<f64> = trades.iter().map(|t| t.pnl).filter(|pnl| *pnl <= 0.0).collect();
+
+        let win_rate = Some(wins.len() as f64 / trades.len() as f64);
+        let avg_win = (!wins.is_empty()).then(|| wins.iter().sum::<f64>() / wins.len() as f64);
+        let avg_loss = (!losses.is_empty()).then(|| losses.iter().map(|pnl| pnl.abs()).sum::<f64>() / losses.len() as f64);
+
+        let gross_profit: f64 = wins.iter().sum();
+        let gross_loss: f64 = losses.iter().map(|pnl| pnl.abs()).sum();
+        let profit_factor = (gross_loss != 0.0).then_some(gross_profit / gross_loss);
+
+        // Walk the cumulative realized-P&L equity curve, tracking the running peak
+        // and the largest peak-to-trough decline - same shape as the `equity_peak`/
+        // `max_drawdown` fields above, but scoped to this bounded trade series.
+        let mut peak = 0.0_f64;
+        let mut equity = 0.0_f64;
+        let mut max_drawdown = 0.0_f64;
+        for trade in trades {
+            equity += trade.pnl;
+            peak = peak.max(equity);
+            max_drawdown = max_drawdown.max(peak - equity);
+        }
+
+        let returns: Vec<f64> = trades.iter().map(|t| t.pnl).collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let sharpe = if returns.len() < 2 {
+            None
+        } else {
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+            let stddev = variance.sqrt();
+            (stddev > 0.0).then_some(mean / stddev)
+        };
+
+        PerformanceStats {
+            win_rate,
+            avg_win,
+            avg_loss,
+            profit_factor,
+            max_drawdown,
+            sharpe,
+        }
+    }
+}
+
+/// Server-computed closed-trade analytics (see `TradingStateData::performance_stats`),
+/// replacing the dashboard's old approach of inferring win rate by substring-matching
+/// rendered P&L strings off currently-open positions - these are computed straight off
+/// the realized `closed_trades` series instead.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PerformanceStats {
+    /// Fraction of closed trades that were winners. `None` until a trade has closed.
+    pub win_rate: Option<f64>,
+    /// Average dollar P&L across winning trades. `None` if there are no wins yet.
+    pub avg_win: Option<f64>,
+    /// Average |dollar P&L| across losing trades. `None` if there are no losses yet.
+    pub avg_loss: Option<f64>,
+    /// Gross profit / |gross loss|. `None` while there's no loss yet to divide by.
+    pub profit_factor: Option<f64>,
+    /// Largest peak-to-trough decline in the cumulative realized-P&L equity curve.
+    pub max_drawdown: f64,
+    /// Mean / stddev of per-trade P&L. `None` with fewer than two trades or zero
+    /// variance (would otherwise divide by zero).
+    pub sharpe: Option<f64>,
+}
+
+/// API response for `/api/performance`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PerformanceResponse {
+    pub closed_trade_count: usize,
+    pub stats: PerformanceStats,
 }
 
 /// API response for positions endpoint
@@ -201,6 +924,11 @@ pub struct PositionWithPnl {
     pub current_price: f64,
     pub unrealized_pnl: f64,
     pub unrealized_pnl_percent: f64,
+    /// `unrealized_pnl_percent` minus accrued funding (see `Config::funding_rate_per_hour`).
+    /// Equal to `unrealized_pnl_percent` while funding is disabled.
+    pub net_pnl_percent: f64,
+    /// Dollar P&L already banked from partial take-profit closes (see `Position::realized_pnl`).
+    pub realized_pnl: f64,
 }
 
 /// API response for status endpoint
@@ -213,6 +941,12 @@ pub struct StatusResponse {
     pub daily_trades: u32,
     pub consecutive_errors: u32,
     pub last_cycle: Option<String>,
+    /// Fraction of closed trades that were winners. `None` until a trade has closed.
+    pub win_rate: Option<f64>,
+    /// Gross profit / |gross loss|. `None` while there's no loss yet to divide by.
+    pub profit_factor: Option<f64>,
+    /// Largest peak-to-current drop in `total_pnl` observed so far (USD)
+    pub max_drawdown: f64,
 }
 
 /// Health check response
@@ -239,6 +973,15 @@ mod tests {
             stop_loss_price: None,
             take_profit_price: None,
             entry_volatility: None,
+            targets_hit: 0,
+            remaining_quantity: None,
+            side: PositionSide::Long,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
         };
 
         // Price up to 51000
@@ -246,6 +989,72 @@ mod tests {
         assert!((pos.unrealized_pnl_percent(51000.0) - 2.0).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_unrealized_pnl_uses_open_quantity_after_a_partial_close() {
+        let mut pos = Position {
+            symbol: "BTC-USD".to_string(),
+            quantity: 0.1,
+            entry_price: 50000.0,
+            entry_time: "2024-01-01T00:00:00Z".to_string(),
+            high_water_mark: None,
+            stop_loss_price: None,
+            take_profit_price: None,
+            entry_volatility: None,
+            targets_hit: 0,
+            remaining_quantity: None,
+            side: PositionSide::Long,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
+        };
+
+        assert_eq!(pos.open_quantity(), 0.1);
+        assert_eq!(pos.closed_quantity(), 0.0);
+
+        // Half the position was trimmed via a scaled take-profit
+        pos.remaining_quantity = Some(0.05);
+        pos.realized_pnl = 50.0;
+
+        assert_eq!(pos.open_quantity(), 0.05);
+        assert_eq!(pos.closed_quantity(), 0.05);
+        // Unrealized P&L only reflects the 0.05 still open, not the full 0.1 entry size
+        assert!((pos.unrealized_pnl(51000.0) - 50.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_short_position_pnl() {
+        let pos = Position {
+            symbol: "BTC-USD".to_string(),
+            quantity: 0.001,
+            entry_price: 50000.0,
+            entry_time: "2024-01-01T00:00:00Z".to_string(),
+            high_water_mark: None,
+            stop_loss_price: None,
+            take_profit_price: None,
+            entry_volatility: None,
+            targets_hit: 0,
+            remaining_quantity: None,
+            side: PositionSide::Short,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
+        };
+
+        // Price drops to 49000 - a short profits as price falls
+        assert!((pos.unrealized_pnl(49000.0) - 1.0).abs() < 0.0001);
+        assert!((pos.unrealized_pnl_percent(49000.0) - 2.0).abs() < 0.0001);
+
+        // Price rises to 51000 - a short loses as price rises
+        assert!((pos.unrealized_pnl(51000.0) - (-1.0)).abs() < 0.0001);
+        assert!((pos.unrealized_pnl_percent(51000.0) - (-2.0)).abs() < 0.0001);
+    }
+
     #[test]
     fn test_high_water_mark() {
         let mut pos = Position {
@@ -257,6 +1066,15 @@ mod tests {
             stop_loss_price: None,
             take_profit_price: None,
             entry_volatility: None,
+            targets_hit: 0,
+            remaining_quantity: None,
+            side: PositionSide::Long,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
         };
 
         // Below entry - no HWM
@@ -289,6 +1107,15 @@ mod tests {
             stop_loss_price: Some(49250.0),
             take_profit_price: Some(51000.0),
             entry_volatility: Some(3.5),
+            targets_hit: 0,
+            remaining_quantity: None,
+            side: PositionSide::Long,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
         };
 
         state.add_position(pos);
@@ -302,6 +1129,33 @@ mod tests {
         assert!(state.positions.is_empty());
     }
 
+    #[test]
+    fn test_position_store_round_trips_as_json_array() {
+        let mut store = PositionStore::default();
+        store.insert(test_position(PositionSide::Long));
+
+        let json = serde_json::to_value(&store).unwrap();
+        assert!(json.is_array());
+        assert_eq!(json.as_array().unwrap().len(), 1);
+
+        let round_tripped: PositionStore = serde_json::from_value(json).unwrap();
+        assert_eq!(round_tripped.len(), 1);
+        assert!(round_tripped.get("BTC-USD").is_some());
+    }
+
+    #[test]
+    fn test_position_store_get_by_symbol() {
+        let mut store = PositionStore::default();
+        store.insert(test_position(PositionSide::Long));
+
+        assert!(store.get("BTC-USD").is_some());
+        assert!(store.get("ETH-USD").is_none());
+
+        let removed = store.remove("BTC-USD");
+        assert!(removed.is_some());
+        assert!(store.is_empty());
+    }
+
     #[test]
     fn test_error_tracking() {
         let mut state = TradingStateData::default();
@@ -318,4 +1172,264 @@ mod tests {
         state.record_success();
         assert!(!state.should_pause(5)); // Reset after success
     }
+
+    fn closed_trade(pnl: f64) -> ClosedTrade {
+        ClosedTrade {
+            symbol: "BTC-USD".to_string(),
+            side: PositionSide::Long,
+            entry_price: 50000.0,
+            exit_price: 50000.0 + pnl,
+            quantity: 1.0,
+            pnl,
+            closed_at: "2024-01-01T00:00:00Z".to_string(),
+            reason: Some(ExitReason::TakeProfit.to_string()),
+            opened_at: None,
+        }
+    }
+
+    #[test]
+    fn test_record_closed_trade_win_loss_counts() {
+        let mut state = TradingStateData::default();
+
+        state.record_closed_trade(closed_trade(100.0));
+        state.record_closed_trade(closed_trade(-40.0));
+
+        assert_eq!(state.winning_trades, 1);
+        assert_eq!(state.losing_trades, 1);
+        assert_eq!(state.gross_profit, Decimal::from(100));
+        assert_eq!(state.gross_loss, Decimal::from(40));
+        assert_eq!(state.total_pnl, Decimal::from(60));
+        assert_eq!(state.closed_trades.len(), 2);
+        assert!((state.win_rate().unwrap() - 0.5).abs() < 0.0001);
+        assert!((state.profit_factor().unwrap() - 2.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_record_closed_trade_tracks_drawdown() {
+        let mut state = TradingStateData::default();
+
+        state.record_closed_trade(closed_trade(100.0)); // equity_peak 100, drawdown 0
+        state.record_closed_trade(closed_trade(-30.0)); // total_pnl 70, drawdown 30 off the peak
+        state.record_closed_trade(closed_trade(10.0)); // total_pnl 80, still below peak
+
+        assert_eq!(state.equity_peak, Decimal::from(100));
+        assert_eq!(state.max_drawdown, Decimal::from(30));
+    }
+
+    #[test]
+    fn test_record_closed_trade_caps_ring_buffer() {
+        let mut state = TradingStateData::default();
+
+        for _ in 0..MAX_CLOSED_TRADES + 10 {
+            state.record_closed_trade(closed_trade(1.0));
+        }
+
+        assert_eq!(state.closed_trades.len(), MAX_CLOSED_TRADES);
+    }
+
+    #[test]
+    fn test_performance_stats_empty() {
+        let state = TradingStateData::default();
+        assert_eq!(state.performance_stats(), PerformanceStats::default());
+    }
+
+    #[test]
+    fn test_performance_stats_computes_win_rate_and_profit_factor() {
+        let mut state = TradingStateData::default();
+        state.record_closed_trade(closed_trade(100.0));
+        state.record_closed_trade(closed_trade(-40.0));
+
+        let stats = state.performance_stats();
+        assert!((stats.win_rate.unwrap() - 0.5).abs() < 0.0001);
+        assert!((stats.avg_win.unwrap() - 100.0).abs() < 0.0001);
+        assert!((stats.avg_loss.unwrap() - 40.0).abs() < 0.0001);
+        assert!((stats.profit_factor.unwrap() - 2.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_performance_stats_max_drawdown_walks_equity_curve() {
+        let mut state = TradingStateData::default();
+        state.record_closed_trade(closed_trade(100.0)); // equity 100, peak 100
+        state.record_closed_trade(closed_trade(-30.0)); // equity 70, drawdown 30
+        state.record_closed_trade(closed_trade(10.0)); // equity 80, still below peak
+
+        assert_eq!(state.performance_stats().max_drawdown, 30.0);
+    }
+
+    #[test]
+    fn test_performance_stats_sharpe_none_with_zero_variance() {
+        let mut state = TradingStateData::default();
+        state.record_closed_trade(closed_trade(10.0));
+        state.record_closed_trade(closed_trade(10.0));
+
+        assert!(state.performance_stats().sharpe.is_none());
+    }
+
+    #[test]
+    fn test_win_rate_and_profit_factor_undefined_before_trades() {
+        let state = TradingStateData::default();
+        assert!(state.win_rate().is_none());
+        assert!(state.profit_factor().is_none());
+    }
+
+    fn test_position(side: PositionSide) -> Position {
+        Position {
+            symbol: "BTC-USD".to_string(),
+            quantity: 0.001,
+            entry_price: 50000.0,
+            entry_time: "2024-01-01T00:00:00Z".to_string(),
+            high_water_mark: None,
+            stop_loss_price: None,
+            take_profit_price: None,
+            entry_volatility: None,
+            targets_hit: 0,
+            remaining_quantity: None,
+            side,
+            low_water_mark: None,
+            entry_adjustments: 0,
+            cumulative_funding: 0.0,
+            pending_exit_order_id: None,
+            realized_pnl: 0.0,
+            expiry_time: None,
+        }
+    }
+
+    #[test]
+    fn test_stop_market_triggers_on_cross() {
+        let pos = test_position(PositionSide::Long);
+        let order = OrderType::StopMarket { trigger: 49000.0 };
+
+        assert!(!pos.conditional_trigger(&order, 49500.0));
+        assert!(pos.conditional_trigger(&order, 49000.0));
+        assert!(pos.conditional_trigger(&order, 48000.0));
+    }
+
+    #[test]
+    fn test_trailing_stop_rides_up_with_high_water_mark() {
+        let mut pos = test_position(PositionSide::Long);
+        let order = OrderType::TrailingStop {
+            amount_or_pct: TrailingAmount::Percent(5.0),
+        };
+
+        // No high water mark yet - trails off entry_price (50000), trigger at 47500
+        assert!(!pos.conditional_trigger(&order, 48000.0));
+        assert!(pos.conditional_trigger(&order, 47500.0));
+
+        // Price runs to 60000 - high water mark advances, trigger rides up to 57000
+        pos.update_high_water_mark(60000.0);
+        assert!(!pos.conditional_trigger(&order, 58000.0));
+        assert!(pos.conditional_trigger(&order, 57000.0));
+    }
+
+    #[test]
+    fn test_trailing_stop_short_trails_low_water_mark() {
+        let mut pos = test_position(PositionSide::Short);
+        pos.update_low_water_mark(40000.0);
+        let order = OrderType::TrailingStop {
+            amount_or_pct: TrailingAmount::Amount(1000.0),
+        };
+
+        // Trigger is 1000 above the low water mark of 40000
+        assert!(!pos.conditional_trigger(&order, 40500.0));
+        assert!(pos.conditional_trigger(&order, 41000.0));
+    }
+
+    #[test]
+    fn test_needs_rollover() {
+        let mut pos = test_position(PositionSide::Long);
+        assert!(!pos.needs_rollover(Utc::now())); // no expiry set
+
+        pos.expiry_time = Some("2024-01-07T15:00:00Z".to_string());
+        assert!(!pos.needs_rollover("2024-01-07T14:59:59Z".parse().unwrap()));
+        assert!(pos.needs_rollover("2024-01-07T15:00:00Z".parse().unwrap()));
+        assert!(pos.needs_rollover("2024-01-08T00:00:00Z".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_next_rollover_boundary_advances_to_coming_sunday() {
+        // Monday 2024-01-01 -> Sunday 2024-01-07 15:00 UTC
+        let now: DateTime<Utc> = "2024-01-01T12:00:00Z".parse().unwrap();
+        assert_eq!(next_rollover_boundary(now), "2024-01-07T15:00:00Z".parse::<DateTime<Utc>>().unwrap());
+
+        // Already Sunday but before 15:00 -> rolls later the same day
+        let same_day: DateTime<Utc> = "2024-01-07T10:00:00Z".parse().unwrap();
+        assert_eq!(next_rollover_boundary(same_day), "2024-01-07T15:00:00Z".parse::<DateTime<Utc>>().unwrap());
+
+        // Already past this week's boundary -> rolls to the following Sunday
+        let past_boundary: DateTime<Utc> = "2024-01-07T16:00:00Z".parse().unwrap();
+        assert_eq!(next_rollover_boundary(past_boundary), "2024-01-14T15:00:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+
+    #[test]
+    fn test_rollover_expiring_closes_and_reopens() {
+        let mut state = TradingStateData::default();
+        let mut pos = test_position(PositionSide::Long);
+        pos.expiry_time = Some("2024-01-07T15:00:00Z".to_string());
+        state.add_position(pos);
+
+        let now: DateTime<Utc> = "2024-01-07T15:00:00Z".parse().unwrap();
+        let (close_trade, reopen_trade) = state.rollover_expiring("BTC-USD", 51000.0, now).unwrap();
+
+        assert_eq!(close_trade.side, OrderSide::Sell);
+        assert_eq!(close_trade.pnl, Some(1.0));
+        assert_eq!(reopen_trade.side, OrderSide::Buy);
+        assert_eq!(reopen_trade.pnl, None);
+        assert_eq!(state.total_trades, 2);
+        assert_eq!(state.total_pnl, Decimal::from(1));
+
+        let rolled = state.get_position("BTC-USD").unwrap();
+        assert_eq!(rolled.entry_price, 51000.0);
+        assert_eq!(rolled.expiry_time, Some("2024-01-14T15:00:00Z".to_string()));
+
+        // Not due yet - no-op
+        assert!(state.rollover_expiring("BTC-USD", 52000.0, now).is_none());
+    }
+
+    #[test]
+    fn test_net_delta_combines_spot_and_options() {
+        let mut state = TradingStateData::default();
+        state.add_position(test_position(PositionSide::Long)); // +0.001 BTC-USD delta
+        state.option_legs.push(crate::options::OptionLeg {
+            symbol: "BTC-USD".to_string(),
+            quantity: -1.0, // short a call: negative delta contribution
+            strike: 50000.0,
+            expiry: "2024-02-01T00:00:00Z".to_string(),
+            is_call: true,
+            implied_vol: 0.6,
+        });
+
+        let mut spot_prices = std::collections::HashMap::new();
+        spot_prices.insert("BTC-USD".to_string(), 50000.0);
+        let now: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+
+        let net_delta = state.net_delta(&spot_prices, now, 0.0);
+        // A call's delta is positive (N(d1) ~ 0.5 at the money), so the short leg
+        // contributes about -0.5; net should sit below the +0.001 spot-only delta.
+        assert!(net_delta < 0.001);
+    }
+
+    #[test]
+    fn test_hedge_order_flattens_long_delta() {
+        let mut state = TradingStateData::default();
+        state.option_legs.push(crate::options::OptionLeg {
+            symbol: "BTC-USD".to_string(),
+            quantity: 10.0,
+            strike: 50000.0,
+            expiry: "2024-02-01T00:00:00Z".to_string(),
+            is_call: true,
+            implied_vol: 0.6,
+        });
+
+        let mut spot_prices = std::collections::HashMap::new();
+        spot_prices.insert("BTC-USD".to_string(), 50000.0);
+        let now: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+
+        let net_delta = state.net_delta(&spot_prices, now, 0.0);
+        let (side, qty) = state.hedge_order(&spot_prices, now, 0.0, 0.1).unwrap();
+        assert_eq!(side, OrderSide::Sell);
+        assert!((qty - net_delta).abs() < 1e-9);
+
+        // Within threshold - no hedge needed
+        assert!(state.hedge_order(&spot_prices, now, 0.0, net_delta + 1.0).is_none());
+    }
 }
@@ -0,0 +1,122 @@
+//! Webhook alerting for dead man's switch and cortex rejection events
+//!
+//! Before this module, the only record of a dead man's switch trip or an emergency
+//! close failure was a `console_error!` line nobody watches. `send` POSTs a
+//! structured JSON payload to every URL in the `WEBHOOK_URLS` secret
+//! (comma-separated) - generic enough to target Slack/Discord-style incoming
+//! webhooks - and reuses `crate::retry::with_retry` so a flaky webhook endpoint
+//! doesn't swallow a critical notification. Delivery failures are logged, never
+//! propagated: a broken webhook must not take down the scheduled handler reporting
+//! through it.
+
+use serde::Serialize;
+use worker::{console_error, console_log, Env};
+
+use crate::retry::{with_retry, RetryPolicy};
+
+/// Severity of an alert event. Declared in ascending order so the derived `Ord` lets
+/// `send` filter against a configured minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    /// Minimum severity worth delivering, from the `ALERT_MIN_SEVERITY` env var
+    /// (`"info"`/`"warning"`/`"critical"`, case-insensitive). Defaults to `Warning`
+    /// so routine info-level events don't page anyone by default.
+    fn configured_minimum(env: &Env) -> Self {
+        match env.var("ALERT_MIN_SEVERITY").map(|v| v.to_string().to_lowercase()) {
+            Ok(s) if s == "info" => Severity::Info,
+            Ok(s) if s == "critical" => Severity::Critical,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// A structured alert payload, generic enough to post straight to a Slack/Discord
+/// incoming webhook or any other JSON-accepting endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub event: String,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub reason: String,
+    pub timestamp_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_since_heartbeat_ms: Option<u64>,
+}
+
+impl Alert {
+    pub fn dead_mans_switch_triggered(elapsed_ms: u64, timestamp_ms: u64) -> Self {
+        Self {
+            event: "DeadMansSwitchTriggered".to_string(),
+            severity: Severity::Critical,
+            symbol: None,
+            reason: format!("No heartbeat for {elapsed_ms}ms"),
+            timestamp_ms,
+            elapsed_since_heartbeat_ms: Some(elapsed_ms),
+        }
+    }
+
+    pub fn emergency_close_failed(reason: impl Into<String>, timestamp_ms: u64) -> Self {
+        Self {
+            event: "EmergencyCloseFailed".to_string(),
+            severity: Severity::Critical,
+            symbol: None,
+            reason: reason.into(),
+            timestamp_ms,
+            elapsed_since_heartbeat_ms: None,
+        }
+    }
+
+    pub fn cortex_rejected(symbol: &str, reason: impl Into<String>, severity: Severity, timestamp_ms: u64) -> Self {
+        Self {
+            event: "CortexRejected".to_string(),
+            severity,
+            symbol: Some(symbol.to_string()),
+            reason: reason.into(),
+            timestamp_ms,
+            elapsed_since_heartbeat_ms: None,
+        }
+    }
+}
+
+/// Send `alert` to every URL in the `WEBHOOK_URLS` secret (comma-separated), if that
+/// secret is configured and `alert.severity` meets `ALERT_MIN_SEVERITY`'s threshold.
+/// Every failure (missing secret, unreachable endpoint, non-2xx response) is logged
+/// via `console_error!` and otherwise swallowed - see module docs.
+pub async fn send(env: &Env, alert: &Alert) {
+    if alert.severity < Severity::configured_minimum(env) {
+        return;
+    }
+
+    let urls = match env.secret("WEBHOOK_URLS") {
+        Ok(v) => v.to_string(),
+        Err(_) => {
+            console_error!("⚠️ Alert not delivered (no WEBHOOK_URLS configured): {}", alert.event);
+            return;
+        }
+    };
+
+    let client = reqwest::Client::new();
+    for url in urls.split(',').map(str::trim).filter(|u| !u.is_empty()) {
+        let result = with_retry(|| client.post(url).json(alert).send(), RetryPolicy::default()).await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                console_log!("📣 Alert delivered to {}: {}", url, alert.event);
+            }
+            Ok(response) => {
+                console_error!("❌ Alert webhook {} returned {}: {}", url, response.status(), alert.event);
+            }
+            Err(e) => {
+                console_error!("❌ Failed to deliver alert to {}: {}", url, e);
+            }
+        }
+    }
+}
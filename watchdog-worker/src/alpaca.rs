@@ -1,6 +1,7 @@
 use worker::*;
 use reqwest::{Client, Method};
-use serde_json::Value;
+use serde::Deserialize;
+use serde_json::{json, Value};
 
 pub struct AlpacaClient {
     base_url: String,
@@ -9,6 +10,217 @@ pub struct AlpacaClient {
     client: Client,
 }
 
+/// Side of a new order, for `submit_order`'s oracle price-band guard below.
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+}
+
+/// Entry order type for `submit_order`/`submit_bracket`.
+pub enum OrderType {
+    Market,
+    Limit,
+}
+
+impl OrderType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+        }
+    }
+}
+
+/// An order as returned by the Alpaca orders API. Numeric fields come back as JSON
+/// strings (matching the real API schema, the same way `coinbase-worker`'s
+/// `client::Product`/`Candle` keep Coinbase's string fields as-is) - use the `_f64`
+/// accessors below rather than parsing these directly.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Order {
+    pub id: String,
+    pub symbol: String,
+    pub side: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    /// e.g. "new", "partially_filled", "filled", "canceled", "rejected"
+    pub status: String,
+    pub qty: String,
+    pub filled_qty: String,
+    pub limit_price: Option<String>,
+    pub filled_avg_price: Option<String>,
+    /// Take-profit/stop-loss child orders of a bracket, absent on a plain order.
+    #[serde(default)]
+    pub legs: Option<Vec<Order>>,
+}
+
+impl Order {
+    pub fn qty_f64(&self) -> Result<f64> {
+        parse_field("qty", &self.qty)
+    }
+
+    pub fn filled_qty_f64(&self) -> Result<f64> {
+        parse_field("filled_qty", &self.filled_qty)
+    }
+
+    /// Whether the order has filled some but not all of its quantity (Alpaca's
+    /// `partially_filled` status).
+    pub fn is_partially_filled(&self) -> bool {
+        self.status == "partially_filled"
+    }
+}
+
+/// An open position as returned by the Alpaca positions API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Position {
+    pub symbol: String,
+    pub qty: String,
+    pub avg_entry_price: String,
+    pub market_value: String,
+    pub unrealized_pl: String,
+    pub current_price: String,
+}
+
+impl Position {
+    pub fn qty_f64(&self) -> Result<f64> {
+        parse_field("qty", &self.qty)
+    }
+
+    pub fn avg_entry_price_f64(&self) -> Result<f64> {
+        parse_field("avg_entry_price", &self.avg_entry_price)
+    }
+
+    pub fn unrealized_pl_f64(&self) -> Result<f64> {
+        parse_field("unrealized_pl", &self.unrealized_pl)
+    }
+}
+
+fn parse_field(name: &str, value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|e| Error::RustError(format!("Failed to parse {name} '{value}': {e}")))
+}
+
+/// Oracle price-band width (%) a new order's limit price must fall within, tighter
+/// for smaller accounts (less room to absorb a single bad print) and wider for
+/// larger ones (more tolerant of ordinary slippage on size). Mirrors
+/// `coinbase-worker`'s `CapitalTier` breakpoints so both bots treat account size
+/// the same way, without the two crates sharing a dependency.
+fn band_percent_for_portfolio(portfolio_value: f64) -> f64 {
+    if portfolio_value < 500.0 {
+        0.25 // Micro/Tiny
+    } else if portfolio_value < 2000.0 {
+        0.5 // Small
+    } else if portfolio_value < 5000.0 {
+        0.75 // Medium
+    } else if portfolio_value < 25000.0 {
+        1.0 // Standard
+    } else {
+        1.5 // Large
+    }
+}
+
+/// Minimum take-profit percent a bracket's TP leg may use, mirroring
+/// `coinbase-worker`'s `FeeTier::min_profitable_tp`: the round-trip fee percent
+/// (caller-supplied, from Alpaca's own fee schedule) plus a minimum net profit
+/// margin, so a bracket is never placed with a TP that wouldn't even cover fees.
+fn min_profitable_tp_percent(round_trip_fee_percent: f64, target_net_profit_percent: f64) -> f64 {
+    round_trip_fee_percent + target_net_profit_percent
+}
+
+/// Stop-loss distance (%) for a bracket's SL leg, mirroring `coinbase-worker`'s
+/// `CapitalTier::risk_per_trade_percent` breakpoints so smaller accounts risk less
+/// per trade than larger ones.
+fn risk_percent_for_portfolio(portfolio_value: f64) -> f64 {
+    if portfolio_value < 100.0 {
+        0.0 // Micro: trading disabled
+    } else if portfolio_value < 500.0 {
+        0.5 // Tiny
+    } else if portfolio_value < 2000.0 {
+        1.0 // Small
+    } else if portfolio_value < 5000.0 {
+        1.5 // Medium
+    } else {
+        2.0 // Standard/Large
+    }
+}
+
+/// Why `submit_order`/`submit_bracket` failed before a `Result<Order>` could be
+/// produced. Keeps the oracle-band rejection distinguishable from a genuine
+/// network/API failure, so a caller doing alerting or retry logic can tell "the
+/// reference price/limit price combination was rejected by design, don't just
+/// retry it" from "Alpaca is unreachable, a retry might succeed" - `Error::RustError`
+/// collapses both into the same opaque string.
+#[derive(Debug)]
+pub enum OrderError {
+    /// `limit_price` fell outside the oracle band around `reference_price`.
+    OracleBandRejected { symbol: String, limit_price: f64, band_percent: f64, reference_price: f64 },
+    /// Everything else - a network, HTTP, or JSON failure talking to Alpaca.
+    Alpaca(Error),
+}
+
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OracleBandRejected { symbol, limit_price, band_percent, reference_price } => write!(
+                f,
+                "{symbol}: limit price {limit_price} outside {band_percent}% oracle band of reference price {reference_price}"
+            ),
+            Self::Alpaca(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<Error> for OrderError {
+    fn from(e: Error) -> Self {
+        Self::Alpaca(e)
+    }
+}
+
+impl From<OrderError> for Error {
+    fn from(e: OrderError) -> Self {
+        match e {
+            OrderError::OracleBandRejected { .. } => Error::RustError(e.to_string()),
+            OrderError::Alpaca(err) => err,
+        }
+    }
+}
+
+/// Reject `limit_price` before any HTTP call if it falls outside an oracle price
+/// band around `reference_price` (a recent trade/quote supplied by the caller).
+/// Guards against sending an order derisory or runaway-priced relative to the
+/// market because of a stale or bad reference price upstream - the band widens
+/// for larger portfolios via `band_percent_for_portfolio`. A free function (no
+/// `AlpacaClient` state is involved) so it's unit-testable without an `Env`.
+fn check_oracle_band(symbol: &str, limit_price: f64, reference_price: f64, portfolio_value: f64) -> std::result::Result<(), OrderError> {
+    let band_percent = band_percent_for_portfolio(portfolio_value);
+    let lower_bound = reference_price * (1.0 - band_percent / 100.0);
+    let upper_bound = reference_price * (1.0 + band_percent / 100.0);
+
+    if limit_price < lower_bound || limit_price > upper_bound {
+        console_warn!(
+            "⚠️ REJECTED: {} limit price {} outside {}% oracle band [{}, {}] of reference {}",
+            symbol, limit_price, band_percent, lower_bound, upper_bound, reference_price
+        );
+        return Err(OrderError::OracleBandRejected {
+            symbol: symbol.to_string(),
+            limit_price,
+            band_percent,
+            reference_price,
+        });
+    }
+
+    Ok(())
+}
+
 impl AlpacaClient {
     pub fn new(env: &Env) -> Result<Self> {
         let key_id = env.secret("APCA_API_KEY_ID")?.to_string();
@@ -25,9 +237,131 @@ impl AlpacaClient {
 
     pub async fn close_all_positions(&self) -> Result<()> {
         let url = format!("{}/v2/positions", self.base_url);
-        
+
         console_log!("🚨 EMERGENCY: Attempting to FLATTEN all positions via {}", url);
 
+        let response = crate::retry::with_retry(
+            || {
+                self.client
+                    .request(Method::DELETE, &url)
+                    .header("APCA-API-KEY-ID", &self.key_id)
+                    .header("APCA-API-SECRET-KEY", &self.secret)
+                    .send()
+            },
+            crate::retry::RetryPolicy::default(),
+        )
+        .await
+        .map_err(|e| Error::RustError(format!("Reqwest error: {}", e)))?;
+
+        if response.status().is_success() {
+            console_log!("✅ EMERGENCY FLATTEN SUCCESSFUL");
+            Ok(())
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            console_error!("❌ FAILED TO FLATTEN: {} - {}", status, text);
+            Err(Error::RustError(format!("Alpaca API failed: {}", text)))
+        }
+    }
+
+    /// Submit a market or limit order. `limit_price` is required for `OrderType::Limit`
+    /// and is checked against `reference_price`'s oracle band before anything is sent;
+    /// it's ignored for `OrderType::Market`, which has no price to guard.
+    pub async fn submit_order(
+        &self,
+        symbol: &str,
+        qty: f64,
+        side: OrderSide,
+        order_type: OrderType,
+        limit_price: Option<f64>,
+        reference_price: f64,
+        portfolio_value: f64,
+    ) -> std::result::Result<Order, OrderError> {
+        let limit_price = if matches!(order_type, OrderType::Limit) { limit_price } else { None };
+        if let Some(price) = limit_price {
+            check_oracle_band(symbol, price, reference_price, portfolio_value)?;
+        }
+
+        let mut body = json!({
+            "symbol": symbol,
+            "qty": qty.to_string(),
+            "side": side.as_str(),
+            "type": order_type.as_str(),
+            "time_in_force": "day",
+        });
+        if let Some(price) = limit_price {
+            body["limit_price"] = json!(price.to_string());
+        }
+
+        console_log!("📤 Submitting {} {} order for {} {} via {}/v2/orders", side.as_str(), order_type.as_str(), qty, symbol, self.base_url);
+
+        Ok(self.send_order(body).await?)
+    }
+
+    /// Submit an entry order with take-profit and stop-loss legs as a single OCO
+    /// bracket. The take-profit leg is computed from `min_profitable_tp_percent` so
+    /// the bracket is never placed with a TP below the round-trip fee plus a minimum
+    /// net target; the stop-loss distance scales from `risk_percent_for_portfolio`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn submit_bracket(
+        &self,
+        symbol: &str,
+        qty: f64,
+        side: OrderSide,
+        order_type: OrderType,
+        limit_price: Option<f64>,
+        reference_price: f64,
+        round_trip_fee_percent: f64,
+        target_net_profit_percent: f64,
+        portfolio_value: f64,
+    ) -> std::result::Result<Order, OrderError> {
+        if let Some(price) = limit_price {
+            check_oracle_band(symbol, price, reference_price, portfolio_value)?;
+        }
+
+        let tp_percent = min_profitable_tp_percent(round_trip_fee_percent, target_net_profit_percent);
+        let risk_percent = risk_percent_for_portfolio(portfolio_value);
+        let entry_reference = limit_price.unwrap_or(reference_price);
+
+        let (take_profit_price, stop_loss_price) = match side {
+            OrderSide::Buy => (
+                entry_reference * (1.0 + tp_percent / 100.0),
+                entry_reference * (1.0 - risk_percent / 100.0),
+            ),
+            OrderSide::Sell => (
+                entry_reference * (1.0 - tp_percent / 100.0),
+                entry_reference * (1.0 + risk_percent / 100.0),
+            ),
+        };
+
+        let mut body = json!({
+            "symbol": symbol,
+            "qty": qty.to_string(),
+            "side": side.as_str(),
+            "type": order_type.as_str(),
+            "time_in_force": "day",
+            "order_class": "bracket",
+            "take_profit": { "limit_price": take_profit_price.to_string() },
+            "stop_loss": { "stop_price": stop_loss_price.to_string() },
+        });
+        if let Some(price) = limit_price {
+            body["limit_price"] = json!(price.to_string());
+        }
+
+        console_log!(
+            "📤 Submitting {} bracket order for {} {} (tp {}, sl {}) via {}/v2/orders",
+            side.as_str(), qty, symbol, take_profit_price, stop_loss_price, self.base_url
+        );
+
+        Ok(self.send_order(body).await?)
+    }
+
+    /// Cancel a resting order by id.
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let url = format!("{}/v2/orders/{}", self.base_url, order_id);
+
+        console_log!("🗑️ Cancelling order {} via {}", order_id, url);
+
         let response = self.client
             .request(Method::DELETE, &url)
             .header("APCA-API-KEY-ID", &self.key_id)
@@ -37,13 +371,132 @@ impl AlpacaClient {
             .map_err(|e| Error::RustError(format!("Reqwest error: {}", e)))?;
 
         if response.status().is_success() {
-            console_log!("✅ EMERGENCY FLATTEN SUCCESSFUL");
+            console_log!("✅ ORDER CANCELLED: {}", order_id);
             Ok(())
         } else {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            console_error!("❌ FAILED TO FLATTEN: {} - {}", status, text);
+            console_error!("❌ FAILED TO CANCEL ORDER {}: {} - {}", order_id, status, text);
             Err(Error::RustError(format!("Alpaca API failed: {}", text)))
         }
     }
+
+    /// Fetch the open position for `symbol`.
+    pub async fn get_position(&self, symbol: &str) -> Result<Position> {
+        let url = format!("{}/v2/positions/{}", self.base_url, symbol);
+
+        let response = self.client
+            .request(Method::GET, &url)
+            .header("APCA-API-KEY-ID", &self.key_id)
+            .header("APCA-API-SECRET-KEY", &self.secret)
+            .send()
+            .await
+            .map_err(|e| Error::RustError(format!("Reqwest error: {}", e)))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .await
+                .map_err(|e| Error::RustError(format!("Failed to parse position response: {}", e)))
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            Err(Error::RustError(format!("Alpaca API failed to get position for {}: {} - {}", symbol, status, text)))
+        }
+    }
+
+    /// POST an order request body to `/v2/orders` and parse the response, shared by
+    /// `submit_order` and `submit_bracket`.
+    async fn send_order(&self, body: Value) -> Result<Order> {
+        let url = format!("{}/v2/orders", self.base_url);
+
+        let response = self.client
+            .request(Method::POST, &url)
+            .header("APCA-API-KEY-ID", &self.key_id)
+            .header("APCA-API-SECRET-KEY", &self.secret)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::RustError(format!("Reqwest error: {}", e)))?;
+
+        if response.status().is_success() {
+            let order: Order = response
+                .json()
+                .await
+                .map_err(|e| Error::RustError(format!("Failed to parse order response: {}", e)))?;
+            console_log!("✅ ORDER SUBMITTED: {}", order.symbol);
+            Ok(order)
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            console_error!("❌ FAILED TO SUBMIT ORDER: {} - {}", status, text);
+            Err(Error::RustError(format!("Alpaca API failed: {}", text)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_oracle_band_accepts_a_limit_price_inside_the_band() {
+        assert!(check_oracle_band("AAPL", 100.4, 100.0, 1000.0).is_ok());
+    }
+
+    #[test]
+    fn test_check_oracle_band_rejects_a_limit_price_outside_the_band() {
+        let err = check_oracle_band("AAPL", 103.0, 100.0, 1000.0).unwrap_err();
+        match err {
+            OrderError::OracleBandRejected { symbol, limit_price, band_percent, reference_price } => {
+                assert_eq!(symbol, "AAPL");
+                assert_eq!(limit_price, 103.0);
+                assert_eq!(band_percent, 0.5);
+                assert_eq!(reference_price, 100.0);
+            }
+            OrderError::Alpaca(_) => panic!("expected OracleBandRejected"),
+        }
+    }
+
+    #[test]
+    fn test_check_oracle_band_widens_for_larger_portfolios() {
+        // 3% above reference is outside the 0.5% band for a $1,000 account...
+        assert!(check_oracle_band("AAPL", 103.0, 100.0, 1000.0).is_err());
+        // ...while 1% above is inside the 1.5% band for a $30,000 account.
+        assert!(check_oracle_band("AAPL", 101.0, 100.0, 30_000.0).is_ok());
+    }
+
+    #[test]
+    fn test_band_percent_for_portfolio_breakpoints() {
+        assert_eq!(band_percent_for_portfolio(0.0), 0.25);
+        assert_eq!(band_percent_for_portfolio(500.0), 0.5);
+        assert_eq!(band_percent_for_portfolio(2000.0), 0.75);
+        assert_eq!(band_percent_for_portfolio(5000.0), 1.0);
+        assert_eq!(band_percent_for_portfolio(25_000.0), 1.5);
+    }
+
+    #[test]
+    fn test_risk_percent_for_portfolio_breakpoints() {
+        assert_eq!(risk_percent_for_portfolio(50.0), 0.0);
+        assert_eq!(risk_percent_for_portfolio(100.0), 0.5);
+        assert_eq!(risk_percent_for_portfolio(500.0), 1.0);
+        assert_eq!(risk_percent_for_portfolio(2000.0), 1.5);
+        assert_eq!(risk_percent_for_portfolio(5000.0), 2.0);
+    }
+
+    #[test]
+    fn test_order_error_oracle_band_rejected_converts_to_a_rust_error_with_a_readable_message() {
+        let err = OrderError::OracleBandRejected {
+            symbol: "AAPL".to_string(),
+            limit_price: 103.0,
+            band_percent: 0.5,
+            reference_price: 100.0,
+        };
+        let message = err.to_string();
+        assert!(message.contains("AAPL"));
+        assert!(message.contains("oracle band"));
+
+        let as_worker_error: Error = err.into();
+        assert!(matches!(as_worker_error, Error::RustError(_)));
+    }
 }
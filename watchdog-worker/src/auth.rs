@@ -0,0 +1,208 @@
+//! HMAC-signed request authentication for `/heartbeat` and `/cortex`
+//!
+//! Without it, both endpoints would accept unauthenticated POSTs, so anyone who learns the
+//! worker URL can spoof heartbeats (suppressing the dead man's switch indefinitely)
+//! or feed fabricated market packets into the cortex filter. `verify_signature`
+//! recomputes an HMAC-SHA256 over the raw request body using the shared
+//! `HEARTBEAT_SECRET` and checks it against the `X-Signature` header with a
+//! constant-time comparison - accumulating XOR differences across the full length
+//! of both slices rather than returning on the first mismatch, so timing can't leak
+//! how many leading bytes matched. A required `X-Signature-Timestamp` header is
+//! folded into the signed material and rejected outside `MAX_SKEW_SECONDS` - missing
+//! entirely, not just stale - so a captured request can't be replayed indefinitely;
+//! making it optional would let any caller that doesn't bother setting it skip
+//! anti-replay protection altogether, which defeats the point of having it.
+
+use worker::{Env, Headers};
+use sha2::{Digest, Sha256};
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// Widest allowed gap between `X-Signature-Timestamp` and the worker's clock before
+/// a signed request is treated as a replay and rejected.
+const MAX_SKEW_SECONDS: i64 = 300;
+
+/// Why `verify_signature` rejected a request - typed so callers (status codes,
+/// metrics, logging) can match on the reason instead of an opaque `String`.
+/// `watchdog-worker` has no crate-wide error type like `coinbase-worker`'s
+/// `TradingError`, so this stays a small, local enum rather than reaching for one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// `HEARTBEAT_SECRET` binding isn't configured.
+    SecretMissing,
+    /// Request carried no `X-Signature` header.
+    MissingSignature,
+    /// Request carried no `X-Signature-Timestamp` header.
+    MissingTimestamp,
+    /// `X-Signature-Timestamp` wasn't a valid integer.
+    InvalidTimestamp,
+    /// `X-Signature-Timestamp` was outside `MAX_SKEW_SECONDS` of the worker's clock.
+    TimestampOutsideSkew,
+    /// Recomputed HMAC didn't match `X-Signature`.
+    SignatureMismatch,
+}
+
+impl AuthError {
+    /// Human-readable reason, for logging and the rejected-request response.
+    pub fn reason(self) -> &'static str {
+        match self {
+            Self::SecretMissing => "HEARTBEAT_SECRET not configured",
+            Self::MissingSignature => "Missing X-Signature header",
+            Self::MissingTimestamp => "Missing X-Signature-Timestamp header",
+            Self::InvalidTimestamp => "Invalid X-Signature-Timestamp header",
+            Self::TimestampOutsideSkew => "Signature timestamp outside allowed skew window",
+            Self::SignatureMismatch => "Signature mismatch",
+        }
+    }
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.reason())
+    }
+}
+
+/// Verify `headers["X-Signature"]` against `body`, using the `HEARTBEAT_SECRET`
+/// binding as the HMAC key. Returns `Ok(())` for a valid, fresh signature; `Err`
+/// with a typed reason otherwise - callers map any `Err` straight to a 401.
+pub fn verify_signature(env: &Env, headers: &Headers, body: &[u8]) -> Result<(), AuthError> {
+    let secret = env.secret("HEARTBEAT_SECRET").map_err(|_| AuthError::SecretMissing)?.to_string();
+
+    let signature = headers.get("X-Signature").ok().flatten().ok_or(AuthError::MissingSignature)?;
+
+    let timestamp = headers.get("X-Signature-Timestamp").ok().flatten();
+    let now = (worker::Date::now().as_millis() / 1000) as i64;
+    check_skew(timestamp.as_deref(), now)?;
+    let timestamp = timestamp.expect("check_skew already rejected a missing timestamp");
+
+    let mut signed_payload = Vec::with_capacity(body.len() + timestamp.len() + 1);
+    signed_payload.extend_from_slice(timestamp.as_bytes());
+    signed_payload.push(b'.');
+    signed_payload.extend_from_slice(body);
+
+    let expected = hmac_sha256_hex(secret.as_bytes(), &signed_payload);
+
+    if constant_time_eq(expected.as_bytes(), signature.as_bytes()) {
+        Ok(())
+    } else {
+        Err(AuthError::SignatureMismatch)
+    }
+}
+
+/// Parse `ts_header` and check it's within `MAX_SKEW_SECONDS` of `now` - pulled out
+/// of `verify_signature` as a pure function so the skew/missing/invalid paths are
+/// unit-testable without a `worker::Env`.
+fn check_skew(ts_header: Option<&str>, now: i64) -> Result<(), AuthError> {
+    let ts_header = ts_header.ok_or(AuthError::MissingTimestamp)?;
+    let ts: i64 = ts_header.parse().map_err(|_| AuthError::InvalidTimestamp)?;
+    if (now - ts).abs() > MAX_SKEW_SECONDS {
+        return Err(AuthError::TimestampOutsideSkew);
+    }
+    Ok(())
+}
+
+/// Compares two byte slices for equality without short-circuiting: every byte pair
+/// is XORed into a running accumulator and only the final result is checked, so the
+/// number of differing bytes can't be inferred from how long the comparison takes.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// HMAC-SHA256 per RFC 2104, rendered as lowercase hex.
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let mut block_key = [0u8; HMAC_BLOCK_SIZE];
+    if key.len() > HMAC_BLOCK_SIZE {
+        let hashed = Sha256::digest(key);
+        block_key[..hashed.len()].copy_from_slice(&hashed);
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for i in 0..HMAC_BLOCK_SIZE {
+        ipad[i] ^= block_key[i];
+        opad[i] ^= block_key[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(ipad);
+    inner.update(message);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(opad);
+    outer.update(inner_hash);
+
+    hex_encode(&outer.finalize())
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 4231 Test Case 1: 20-byte key, short ASCII data.
+    #[test]
+    fn test_hmac_sha256_matches_rfc4231_test_case_1() {
+        let key = [0x0bu8; 20];
+        let expected = "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7";
+        assert_eq!(hmac_sha256_hex(&key, b"Hi There"), expected);
+    }
+
+    /// RFC 4231 Test Case 2: key shorter than the block size, ASCII key.
+    #[test]
+    fn test_hmac_sha256_matches_rfc4231_test_case_2() {
+        let expected = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        assert_eq!(hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"), expected);
+    }
+
+    /// RFC 4231 Test Case 6: key longer than the block size, exercising the
+    /// key-hashing branch of `hmac_sha256_hex`.
+    #[test]
+    fn test_hmac_sha256_matches_rfc4231_test_case_6_long_key() {
+        let key = [0xaau8; 131];
+        let expected = "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54";
+        assert_eq!(hmac_sha256_hex(&key, b"Test Using Larger Than Block-Size Key - Hash Key First"), expected);
+    }
+
+    #[test]
+    fn test_constant_time_eq_round_trips_on_equal_and_unequal_input() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn test_check_skew_accepts_a_fresh_timestamp() {
+        assert_eq!(check_skew(Some("1000"), 1000), Ok(()));
+        assert_eq!(check_skew(Some("1000"), 1000 + MAX_SKEW_SECONDS), Ok(()));
+        assert_eq!(check_skew(Some("1000"), 1000 - MAX_SKEW_SECONDS), Ok(()));
+    }
+
+    #[test]
+    fn test_check_skew_rejects_outside_the_window() {
+        assert_eq!(check_skew(Some("1000"), 1000 + MAX_SKEW_SECONDS + 1), Err(AuthError::TimestampOutsideSkew));
+        assert_eq!(check_skew(Some("1000"), 1000 - MAX_SKEW_SECONDS - 1), Err(AuthError::TimestampOutsideSkew));
+    }
+
+    #[test]
+    fn test_check_skew_rejects_a_missing_timestamp() {
+        assert_eq!(check_skew(None, 1000), Err(AuthError::MissingTimestamp));
+    }
+
+    #[test]
+    fn test_check_skew_rejects_a_non_numeric_timestamp() {
+        assert_eq!(check_skew(Some("not-a-number"), 1000), Err(AuthError::InvalidTimestamp));
+    }
+}
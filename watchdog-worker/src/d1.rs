@@ -25,3 +25,134 @@ pub async fn save_heartbeat(env: &Env, timestamp: u64, component: &str) -> Resul
     statement.bind(&[component.into(), timestamp.into()])?.run().await?;
     Ok(())
 }
+
+/// Most recent heartbeat for one `component`, unlike `get_last_heartbeat` which ignores
+/// `source` entirely. Used by the per-component watchdog (see `watchdog::run_heartbeat_monitor`).
+pub async fn get_last_heartbeat_for(env: &Env, component: &str) -> Result<Option<u64>> {
+    let d1 = env.d1("DB")?;
+    let statement = d1
+        .prepare("SELECT timestamp FROM heartbeats WHERE source = ? ORDER BY timestamp DESC LIMIT 1")
+        .bind(&[component.into()])?;
+    let result = statement.first::<Heartbeat>(None).await?;
+
+    match result {
+        Some(heartbeat) => Ok(Some(heartbeat.timestamp)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ComponentAlertState {
+    state: String,
+}
+
+/// Last alert state recorded for `component` ("stale" or "alive"), so the watchdog can
+/// tell a genuine edge transition from "still stale, already alerted". `None` means no
+/// alert has ever fired for this component. Align with schema.sql:
+/// `component_alert_state (component TEXT PRIMARY KEY, state TEXT, updated_at INTEGER)`.
+pub async fn get_alert_state(env: &Env, component: &str) -> Result<Option<String>> {
+    let d1 = env.d1("DB")?;
+    let statement = d1
+        .prepare("SELECT state FROM component_alert_state WHERE component = ?")
+        .bind(&[component.into()])?;
+    let result = statement.first::<ComponentAlertState>(None).await?;
+    Ok(result.map(|row| row.state))
+}
+
+/// Record `component`'s new alert state after a transition.
+pub async fn save_alert_state(env: &Env, component: &str, state: &str, updated_at: u64) -> Result<()> {
+    let d1 = env.d1("DB")?;
+    let statement = d1.prepare(
+        "INSERT INTO component_alert_state (component, state, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT (component) DO UPDATE SET state = excluded.state, updated_at = excluded.updated_at",
+    );
+    statement
+        .bind(&[component.into(), state.into(), updated_at.into()])?
+        .run()
+        .await?;
+    Ok(())
+}
+
+/// A recorded Cortex ALLOW/REJECT decision, as returned by `get_recent_decisions`.
+#[derive(Debug, Serialize)]
+pub struct Decision {
+    pub symbol: String,
+    pub allow: bool,
+    pub reason: String,
+    pub score: u32,
+    pub core_analysis_summary: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Raw row shape from `decisions` - D1 stores `allow` as an INTEGER, so it's read back
+/// as `i64` here and narrowed to `bool` in `From<DecisionRow> for Decision`.
+#[derive(Debug, Deserialize)]
+struct DecisionRow {
+    symbol: String,
+    allow: i64,
+    reason: String,
+    score: i64,
+    core_analysis_summary: Option<String>,
+    timestamp: i64,
+}
+
+impl From<DecisionRow> for Decision {
+    fn from(row: DecisionRow) -> Self {
+        Decision {
+            symbol: row.symbol,
+            allow: row.allow != 0,
+            reason: row.reason,
+            score: row.score as u32,
+            core_analysis_summary: row.core_analysis_summary,
+            timestamp: row.timestamp as u64,
+        }
+    }
+}
+
+/// Record one Cortex ALLOW/REJECT decision. Align with schema.sql:
+/// `decisions (symbol TEXT, allow INTEGER, reason TEXT, score INTEGER,
+/// core_analysis_summary TEXT, timestamp INTEGER)`.
+#[allow(clippy::too_many_arguments)]
+pub async fn save_decision(
+    env: &Env,
+    packet: &crate::MarketPacket,
+    allow: bool,
+    reason: &str,
+    score: u32,
+    core_analysis_summary: Option<&str>,
+    ts: u64,
+) -> Result<()> {
+    let d1 = env.d1("DB")?;
+    let statement = d1.prepare(
+        "INSERT INTO decisions (symbol, allow, reason, score, core_analysis_summary, timestamp) VALUES (?, ?, ?, ?, ?, ?)",
+    );
+    statement
+        .bind(&[
+            packet.symbol.clone().into(),
+            (allow as i64).into(),
+            reason.into(),
+            score.into(),
+            core_analysis_summary.into(),
+            ts.into(),
+        ])?
+        .run()
+        .await?;
+    Ok(())
+}
+
+/// Most recent decisions, newest first, optionally narrowed to one `symbol` - backs
+/// `GET /decisions` so operators can see rejection rate and score distribution
+/// without scraping worker logs.
+pub async fn get_recent_decisions(env: &Env, limit: u32, symbol_filter: Option<&str>) -> Result<Vec<Decision>> {
+    let d1 = env.d1("DB")?;
+    let statement = match symbol_filter {
+        Some(symbol) => d1
+            .prepare("SELECT symbol, allow, reason, score, core_analysis_summary, timestamp FROM decisions WHERE symbol = ? ORDER BY timestamp DESC LIMIT ?")
+            .bind(&[symbol.into(), limit.into()])?,
+        None => d1
+            .prepare("SELECT symbol, allow, reason, score, core_analysis_summary, timestamp FROM decisions ORDER BY timestamp DESC LIMIT ?")
+            .bind(&[limit.into()])?,
+    };
+    let rows = statement.all().await?.results::<DecisionRow>()?;
+    Ok(rows.into_iter().map(Decision::from).collect())
+}
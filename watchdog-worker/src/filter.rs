@@ -0,0 +1,130 @@
+//! Configurable Cortex edge-filter rules
+//!
+//! The VIX/spread/volume thresholds used to be hardcoded directly in `main`'s
+//! `if/else`, so tightening risk posture after a bad session meant a redeploy.
+//! `FilterConfig` pulls those thresholds (and the score assigned to each rejection)
+//! out into data, loaded fresh on every request from a `filter_config` D1 row and
+//! editable via `POST /config/filter` without a deploy. `load_config` falls back to
+//! `FilterConfig::default()` - the same numbers that used to be hardcoded - if no
+//! row exists yet or the stored JSON fails to parse, so a bad write can never leave
+//! the worker serving requests with an empty ruleset.
+
+use serde::{Deserialize, Serialize};
+use worker::{console_error, Date, Env, Error, Result};
+
+use crate::{FilterResult, MarketPacket};
+
+/// Risk thresholds for the Cortex edge filter, evaluated in order (VIX, then
+/// spread, then volume) against an incoming `MarketPacket`. Each rule pairs a
+/// threshold with the score assigned on rejection; `pass_score` is returned when a
+/// packet clears every rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterConfig {
+    pub vix_max: f64,
+    pub vix_reject_score: u32,
+    pub spread_max_percent: f64,
+    pub spread_reject_score: u32,
+    pub min_volume: u64,
+    pub min_volume_reject_score: u32,
+    pub pass_score: u32,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            vix_max: 35.0,
+            vix_reject_score: 0,
+            spread_max_percent: 0.5,
+            spread_reject_score: 10,
+            min_volume: 0,
+            min_volume_reject_score: 5,
+            pass_score: 95,
+        }
+    }
+}
+
+impl FilterConfig {
+    /// Evaluate `packet` against this config's rules in order, returning the first
+    /// failing rule's reason/score, or a pass result at `pass_score` if every rule
+    /// clears.
+    pub fn evaluate(&self, packet: &MarketPacket) -> FilterResult {
+        if packet.vix_level > self.vix_max {
+            return FilterResult {
+                allow_execution: false,
+                reason: "VIX too high - Chaos Protected".to_string(),
+                score: self.vix_reject_score,
+            };
+        }
+        if packet.spread_percent > self.spread_max_percent {
+            return FilterResult {
+                allow_execution: false,
+                reason: "Spread too wide - Liquidity Protected".to_string(),
+                score: self.spread_reject_score,
+            };
+        }
+        if packet.volume < self.min_volume {
+            return FilterResult {
+                allow_execution: false,
+                reason: "Volume too thin - Liquidity Protected".to_string(),
+                score: self.min_volume_reject_score,
+            };
+        }
+        FilterResult {
+            allow_execution: true,
+            reason: "Signal Passed Edge Filter".to_string(),
+            score: self.pass_score,
+        }
+    }
+}
+
+/// Row shape for `filter_config` - one active config stored as a JSON blob rather
+/// than a column per field, so adding a rule later doesn't need a migration. Align
+/// with schema.sql: `filter_config (id INTEGER PRIMARY KEY, config_json TEXT,
+/// updated_at INTEGER)`.
+#[derive(Debug, Deserialize)]
+struct FilterConfigRow {
+    config_json: String,
+}
+
+/// Load the active `FilterConfig`, falling back to `FilterConfig::default()` if no
+/// row exists yet or the stored JSON fails to parse.
+pub async fn load_config(env: &Env) -> FilterConfig {
+    match try_load_config(env).await {
+        Ok(Some(config)) => config,
+        Ok(None) => FilterConfig::default(),
+        Err(e) => {
+            console_error!("⚠️ Failed to load filter config, using defaults: {}", e);
+            FilterConfig::default()
+        }
+    }
+}
+
+async fn try_load_config(env: &Env) -> Result<Option<FilterConfig>> {
+    let d1 = env.d1("DB")?;
+    let statement = d1.prepare("SELECT config_json FROM filter_config WHERE id = 1");
+    let row = statement.first::<FilterConfigRow>(None).await?;
+
+    match row {
+        Some(row) => {
+            let config = serde_json::from_str(&row.config_json)
+                .map_err(|e| Error::RustError(format!("Invalid filter_config JSON: {e}")))?;
+            Ok(Some(config))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Persist a new active `FilterConfig`, replacing whatever was previously stored.
+pub async fn save_config(env: &Env, config: &FilterConfig) -> Result<()> {
+    let d1 = env.d1("DB")?;
+    let config_json = serde_json::to_string(config)
+        .map_err(|e| Error::RustError(format!("Failed to serialize filter config: {e}")))?;
+    let updated_at = Date::now().as_millis();
+
+    let statement = d1.prepare(
+        "INSERT INTO filter_config (id, config_json, updated_at) VALUES (1, ?, ?)
+         ON CONFLICT (id) DO UPDATE SET config_json = excluded.config_json, updated_at = excluded.updated_at",
+    );
+    statement.bind(&[config_json.into(), updated_at.into()])?.run().await?;
+    Ok(())
+}
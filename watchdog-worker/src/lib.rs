@@ -2,8 +2,14 @@ use worker::*;
 use serde::{Deserialize, Serialize};
 
 mod utils;
+mod alerts;
+mod auth;
 mod d1;
 mod alpaca;
+mod filter;
+mod market_data;
+mod retry;
+mod watchdog;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MarketPacket {
@@ -70,6 +76,12 @@ pub async fn main(mut req: Request, env: Env, _ctx: worker::Context) -> Result<R
 
     // 1. Heartbeat Endpoint
     if path == "/heartbeat" && method == Method::Post {
+        let body = req.bytes().await?;
+        if let Err(reason) = auth::verify_signature(&env, &req.headers(), &body) {
+            console_warn!("🔒 Rejected unauthenticated heartbeat: {}", reason);
+            return Response::error("Unauthorized", 401);
+        }
+
         let timestamp = Date::now().as_millis() as u64;
         d1::save_heartbeat(&env, timestamp, "java-core").await?;
         return Response::ok("Heartbeat Captured ❤️");
@@ -77,17 +89,45 @@ pub async fn main(mut req: Request, env: Env, _ctx: worker::Context) -> Result<R
 
     // 2. Cortex Proxy Endpoint
     if path == "/cortex" && method == Method::Post {
-        let packet: MarketPacket = req.json().await?;
+        let body = req.bytes().await?;
+        if let Err(reason) = auth::verify_signature(&env, &req.headers(), &body) {
+            console_warn!("🔒 Rejected unauthenticated cortex packet: {}", reason);
+            return Response::error("Unauthorized", 401);
+        }
+
+        let packet: MarketPacket = serde_json::from_slice(&body)
+            .map_err(|e| Error::RustError(format!("Invalid packet JSON: {}", e)))?;
         console_log!("🧠 Cortex Received Packet: {:?}", packet);
 
-        // Edge Filtering (WASM Speed)
-        let (allow, reason, score) = if packet.vix_level > 35.0 {
-            (false, "VIX too high - Chaos Protected", 0)
-        } else if packet.spread_percent > 0.5 {
-            (false, "Spread too wide - Liquidity Protected", 10)
+        let now = Date::now().as_millis() as u64;
+
+        // Edge Filtering (WASM Speed), against the data-driven ruleset rather than
+        // hardcoded thresholds - see the `filter` module.
+        let filter_config = filter::load_config(&env).await;
+        let FilterResult { allow_execution, reason, score } = filter_config.evaluate(&packet);
+
+        // Independent market-data cross-check: only meaningful once the packet has
+        // cleared the edge filter - a rejected packet doesn't need a second reason.
+        // Checks price, vix_level, AND spread_percent against an independent quote
+        // (not just price) - those last two are what the edge filter actually gates
+        // on, so a sender that faked only them would otherwise bypass chaos/liquidity
+        // protection undetected. Degrades to trusting the packet's own values if the
+        // provider isn't configured or is unreachable (see `market_data::validate_packet`).
+        let (allow, reason, score) = if allow_execution {
+            match market_data::ExternalQuoteProvider::new(&env) {
+                Ok(provider) => match market_data::validate_packet(&provider, &packet).await {
+                    Some(validation_reason) => (false, validation_reason, 0),
+                    None => (true, reason, score),
+                },
+                Err(e) => {
+                    console_warn!("⚠️ Market data provider not configured, skipping price cross-check: {}", e);
+                    (true, reason, score)
+                }
+            }
         } else {
-            (true, "Signal Passed Edge Filter", 95)
+            (false, reason, score)
         };
+        let reason = reason.as_str();
 
         if allow {
             console_log!("✅ Cortex ALLOWED signal for {}. Score: {}", packet.symbol, score);
@@ -97,15 +137,24 @@ pub async fn main(mut req: Request, env: Env, _ctx: worker::Context) -> Result<R
             let url = format!("{}/analyze", core_url);
             
             let client = reqwest::Client::new();
-            let core_res = client.post(&url)
-                .json(&packet)
-                .send()
-                .await
-                .map_err(|e| Error::RustError(format!("Core Proxy Error: {}", e)))?;
+            let core_res = retry::with_retry(
+                || client.post(&url).json(&packet).send(),
+                retry::RetryPolicy::default(),
+            )
+            .await
+            .map_err(|e| Error::RustError(format!("Core Proxy Error: {}", e)))?;
+
+            if !core_res.status().is_success() {
+                let status = core_res.status();
+                let text = core_res.text().await.unwrap_or_default();
+                return Err(Error::RustError(format!("Core Proxy Error: {} - {}", status, text)));
+            }
 
             let core_json: serde_json::Value = core_res.json().await
                 .map_err(|e| Error::RustError(format!("Core JSON Error: {}", e)))?;
 
+            d1::save_decision(&env, &packet, true, reason, score, Some(&core_json.to_string()), now).await?;
+
             return Response::from_json(&serde_json::json!({
                 "decision": "ALLOWED",
                 "edge_score": score,
@@ -114,6 +163,13 @@ pub async fn main(mut req: Request, env: Env, _ctx: worker::Context) -> Result<R
             }));
         } else {
             console_warn!("🚫 Cortex REJECTED signal for {}. Reason: {}", packet.symbol, reason);
+
+            d1::save_decision(&env, &packet, false, reason, score, None, now).await?;
+
+            let severity = if score == 0 { alerts::Severity::Critical } else { alerts::Severity::Warning };
+            let alert = alerts::Alert::cortex_rejected(&packet.symbol, reason, severity, now);
+            alerts::send(&env, &alert).await;
+
             return Response::from_json(&serde_json::json!({
                 "decision": "REJECTED",
                 "edge_reason": reason,
@@ -122,6 +178,43 @@ pub async fn main(mut req: Request, env: Env, _ctx: worker::Context) -> Result<R
         }
     }
 
+    // 3. Decision History Endpoint
+    if path == "/decisions" && method == Method::Get {
+        let url = req.url()?;
+        let mut symbol_filter: Option<String> = None;
+        let mut since: Option<u64> = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "symbol" => symbol_filter = Some(value.to_string()),
+                "since" => since = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        let decisions = d1::get_recent_decisions(&env, 100, symbol_filter.as_deref()).await?;
+        let decisions: Vec<_> = match since {
+            Some(ts) => decisions.into_iter().filter(|d| d.timestamp >= ts).collect(),
+            None => decisions,
+        };
+
+        return Response::from_json(&decisions);
+    }
+
+    // 4. Filter Config Update Endpoint (hot-reload, no redeploy)
+    if path == "/config/filter" && method == Method::Post {
+        let body = req.bytes().await?;
+        if let Err(reason) = auth::verify_signature(&env, &req.headers(), &body) {
+            console_warn!("🔒 Rejected unauthenticated filter config update: {}", reason);
+            return Response::error("Unauthorized", 401);
+        }
+
+        let config: filter::FilterConfig = serde_json::from_slice(&body)
+            .map_err(|e| Error::RustError(format!("Invalid filter config JSON: {}", e)))?;
+        filter::save_config(&env, &config).await?;
+
+        return Response::from_json(&serde_json::json!({ "status": "updated", "config": config }));
+    }
+
     Response::ok("Alpaca Bot Cortex/Watchdog - Online")
 }
 
@@ -134,6 +227,11 @@ pub async fn cron(_event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
         Ok(_) => console_log!("✅ Health Check Passed"),
         Err(e) => console_error!("❌ Health Check Failed/Error: {}", e),
     }
+
+    match watchdog::run_heartbeat_monitor(&env, &["java-core"], &[180_000]).await {
+        Ok(_) => console_log!("✅ Heartbeat Monitor Passed"),
+        Err(e) => console_error!("❌ Heartbeat Monitor Failed/Error: {}", e),
+    }
 }
 
 async fn check_health(env: &Env) -> Result<()> {
@@ -149,11 +247,15 @@ async fn check_health(env: &Env) -> Result<()> {
             
             if elapsed > threshold_ms {
                 console_error!("💀 DEAD MAN'S SWITCH TRIGGERED! Last beat: {}ms ago", elapsed);
-                
+                alerts::send(env, &alerts::Alert::dead_mans_switch_triggered(elapsed, now)).await;
+
                 // 2. Trigger Emergency Protocol
                 let alpaca = alpaca::AlpacaClient::new(env)?;
-                alpaca.close_all_positions().await?;
-                
+                if let Err(e) = alpaca.close_all_positions().await {
+                    alerts::send(env, &alerts::Alert::emergency_close_failed(e.to_string(), now)).await;
+                    return Err(e);
+                }
+
             } else {
                 console_log!("❤️ System Alive. Last beat: {}ms ago", elapsed);
             }
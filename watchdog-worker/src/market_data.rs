@@ -0,0 +1,313 @@
+//! Independent market-data validation for inbound Cortex packets
+//!
+//! The `/cortex` handler used to trust `MarketPacket`'s `price` (and, by extension,
+//! everything derived from it) verbatim - a buggy or malicious sender could pass
+//! whatever numbers it wanted with nothing to cross-check them against. HMAC only
+//! authenticates who sent the packet, not that its fields are honest, so `vix_level`
+//! and `spread_percent` - the two fields `FilterConfig::evaluate` actually gates
+//! chaos/liquidity protection on - need the same treatment as `price`, or a sender
+//! can simply report `vix_level: 0.0` and sail through the edge filter.
+//! `MarketDataProvider` fetches an independent quote for the packet's symbol from an
+//! external quote API, and the handler rejects any packet whose self-reported price,
+//! `vix_level`, or `spread_percent` diverges from that quote's by more than its
+//! respective tolerance, or whose symbol the provider doesn't recognize at all.
+//! Quotes are cached in KV with a short TTL (`QUOTE_CACHE_TTL_SECONDS`) keyed by
+//! symbol so a burst of signals for the same symbol doesn't hammer the upstream API.
+//! A provider that's unreachable degrades gracefully - the packet-reported values
+//! are allowed through rather than blocking trading on a third-party outage.
+
+use serde::{Deserialize, Serialize};
+use worker::{console_warn, kv::KvStore, Env};
+
+use crate::retry::{with_retry, RetryPolicy};
+use crate::MarketPacket;
+
+/// Tolerance band (%) a packet's self-reported `price` may diverge from an
+/// independently fetched quote before the packet is rejected as suspect.
+pub const PRICE_TOLERANCE_PERCENT: f64 = 2.0;
+
+/// Tolerance band (absolute points) a packet's self-reported `vix_level` may
+/// diverge from an independently fetched quote's before the packet is rejected.
+/// VIX is already expressed as an index, not a price, so an absolute-point
+/// tolerance makes more sense here than a relative percentage.
+pub const VIX_TOLERANCE_ABSOLUTE: f64 = 5.0;
+
+/// Tolerance band (absolute percentage points) a packet's self-reported
+/// `spread_percent` may diverge from an independently fetched quote's before the
+/// packet is rejected.
+pub const SPREAD_TOLERANCE_ABSOLUTE_PERCENT: f64 = 0.25;
+
+/// How long a fetched quote is trusted before the next lookup re-fetches it.
+const QUOTE_CACHE_TTL_SECONDS: u64 = 30;
+
+/// An independently sourced quote for one symbol, including the chaos/liquidity
+/// signals (`vix_level`, `spread_percent`) the Cortex edge filter gates on - not
+/// just `price` - so all three can be cross-checked against what the packet itself
+/// reported.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Quote {
+    pub price: f64,
+    pub volume: u64,
+    pub vix_level: f64,
+    pub spread_percent: f64,
+}
+
+/// Source of independent market data. Abstracted behind a trait so the concrete
+/// HTTP-backed provider below can be swapped for a test double. Uses
+/// `async_trait(?Send)` since futures here wrap `worker`/`reqwest` types, neither of
+/// which is `Send` under Workers/WASM.
+#[async_trait::async_trait(?Send)]
+pub trait MarketDataProvider {
+    /// Fetch `symbol`'s current quote. `Ok(None)` means the symbol is unknown to the
+    /// provider, not an error.
+    async fn get_quote(&self, symbol: &str) -> worker::Result<Option<Quote>>;
+}
+
+/// `MarketDataProvider` backed by an external quote API, configured via the
+/// `QUOTE_API_URL`/`QUOTE_API_KEY` secrets. Quotes are cached in the `STATE` KV
+/// namespace so bursty signals for the same symbol don't hammer the upstream API.
+pub struct ExternalQuoteProvider {
+    base_url: String,
+    api_key: String,
+    kv: KvStore,
+}
+
+impl ExternalQuoteProvider {
+    pub fn new(env: &Env) -> worker::Result<Self> {
+        Ok(Self {
+            base_url: env.secret("QUOTE_API_URL")?.to_string(),
+            api_key: env.secret("QUOTE_API_KEY")?.to_string(),
+            kv: env.kv("STATE")?,
+        })
+    }
+
+    fn cache_key(symbol: &str) -> String {
+        format!("quote_cache_{symbol}")
+    }
+
+    async fn fetch_live(&self, symbol: &str) -> worker::Result<Option<Quote>> {
+        let url = format!("{}/quote?symbol={symbol}", self.base_url);
+        let client = reqwest::Client::new();
+
+        let response = with_retry(
+            || client.get(&url).header("Authorization", format!("Bearer {}", self.api_key)).send(),
+            RetryPolicy::default(),
+        )
+        .await
+        .map_err(|e| worker::Error::RustError(format!("Quote API error: {e}")))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(worker::Error::RustError(format!("Quote API returned {}", response.status())));
+        }
+
+        let quote: Quote = response
+            .json()
+            .await
+            .map_err(|e| worker::Error::RustError(format!("Quote API JSON error: {e}")))?;
+        Ok(Some(quote))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MarketDataProvider for ExternalQuoteProvider {
+    async fn get_quote(&self, symbol: &str) -> worker::Result<Option<Quote>> {
+        let key = Self::cache_key(symbol);
+
+        if let Ok(Some(cached)) = self.kv.get(&key).json::<Quote>().await {
+            return Ok(Some(cached));
+        }
+
+        let quote = self.fetch_live(symbol).await?;
+
+        if let Some(quote) = &quote {
+            if let Ok(builder) = self.kv.put(&key, quote) {
+                let _ = builder.expiration_ttl(QUOTE_CACHE_TTL_SECONDS).execute().await;
+            }
+        }
+
+        Ok(quote)
+    }
+}
+
+/// Whether `reported_price` is close enough to `quote_price` (within
+/// `PRICE_TOLERANCE_PERCENT`) to trust.
+fn within_tolerance(reported_price: f64, quote_price: f64) -> bool {
+    if quote_price <= 0.0 {
+        return false;
+    }
+    let divergence_percent = ((reported_price - quote_price).abs() / quote_price) * 100.0;
+    divergence_percent <= PRICE_TOLERANCE_PERCENT
+}
+
+/// Whether `reported` is within `tolerance` absolute units of `independent`.
+fn within_absolute_tolerance(reported: f64, independent: f64, tolerance: f64) -> bool {
+    (reported - independent).abs() <= tolerance
+}
+
+/// Cross-check `packet`'s self-reported `price`, `vix_level`, and `spread_percent`
+/// against `provider`'s independent quote for its symbol. Checking `price` alone
+/// isn't enough: the filter gates on `vix_level`/`spread_percent`, so a sender that
+/// only faked those two would otherwise sail straight through. Returns `None`
+/// when the packet should be trusted (every field confirmed, or the provider is
+/// unreachable and we degrade gracefully); `Some(reason)` when the packet
+/// should be rejected.
+pub async fn validate_packet<P: MarketDataProvider>(provider: &P, packet: &MarketPacket) -> Option<String> {
+    match provider.get_quote(&packet.symbol).await {
+        Ok(Some(quote)) => {
+            if !within_tolerance(packet.price, quote.price) {
+                return Some(format!(
+                    "Reported price {} diverges from independent quote {} beyond {PRICE_TOLERANCE_PERCENT}%",
+                    packet.price, quote.price
+                ));
+            }
+            if !within_absolute_tolerance(packet.vix_level, quote.vix_level, VIX_TOLERANCE_ABSOLUTE) {
+                return Some(format!(
+                    "Reported VIX {} diverges from independent quote {} beyond {VIX_TOLERANCE_ABSOLUTE} points",
+                    packet.vix_level, quote.vix_level
+                ));
+            }
+            if !within_absolute_tolerance(packet.spread_percent, quote.spread_percent, SPREAD_TOLERANCE_ABSOLUTE_PERCENT) {
+                return Some(format!(
+                    "Reported spread {}% diverges from independent quote {}% beyond {SPREAD_TOLERANCE_ABSOLUTE_PERCENT} points",
+                    packet.spread_percent, quote.spread_percent
+                ));
+            }
+            None
+        }
+        Ok(None) => Some(format!("Symbol {} unknown to market data provider", packet.symbol)),
+        Err(e) => {
+            console_warn!(
+                "⚠️ Market data provider unreachable for {}, allowing packet as-reported: {}",
+                packet.symbol, e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Polls `future` to completion without pulling in an async test runtime -
+    /// neither crate in this repo depends on `tokio`/`async-std`, and every future
+    /// exercised below (`FakeProvider::get_quote`) never actually awaits anything, so
+    /// it's always `Poll::Ready` on the first poll.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("test future did not resolve synchronously"),
+        }
+    }
+
+    /// Test double for `MarketDataProvider`, the one the trait's doc comment above
+    /// mentions. Returns a canned `Quote` for a known symbol, `Ok(None)` for an
+    /// unknown one, or `Err` to simulate the provider being unreachable - no
+    /// `worker::Env`/HTTP/KV involved.
+    struct FakeProvider {
+        quote: Option<Quote>,
+        unreachable: bool,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl MarketDataProvider for FakeProvider {
+        async fn get_quote(&self, _symbol: &str) -> worker::Result<Option<Quote>> {
+            if self.unreachable {
+                return Err(worker::Error::RustError("provider unreachable".to_string()));
+            }
+            Ok(self.quote)
+        }
+    }
+
+    fn packet(price: f64, vix_level: f64, spread_percent: f64) -> MarketPacket {
+        MarketPacket {
+            symbol: "BTC-USD".to_string(),
+            price,
+            volume: 1,
+            spread_percent,
+            vix_level,
+        }
+    }
+
+    fn quote(price: f64, vix_level: f64, spread_percent: f64) -> Quote {
+        Quote { price, volume: 1, vix_level, spread_percent }
+    }
+
+    #[test]
+    fn test_within_tolerance_accepts_a_close_price_and_rejects_a_divergent_one() {
+        assert!(within_tolerance(101.0, 100.0));
+        assert!(!within_tolerance(103.0, 100.0));
+    }
+
+    #[test]
+    fn test_within_tolerance_rejects_a_non_positive_quote_price() {
+        assert!(!within_tolerance(0.0, 0.0));
+        assert!(!within_tolerance(1.0, -5.0));
+    }
+
+    #[test]
+    fn test_within_absolute_tolerance_accepts_within_and_rejects_beyond_the_band() {
+        assert!(within_absolute_tolerance(20.0, 24.0, VIX_TOLERANCE_ABSOLUTE));
+        assert!(!within_absolute_tolerance(20.0, 26.0, VIX_TOLERANCE_ABSOLUTE));
+    }
+
+    #[test]
+    fn test_validate_packet_accepts_a_packet_matching_the_independent_quote() {
+        let provider = FakeProvider { quote: Some(quote(100.0, 20.0, 0.1)), unreachable: false };
+        let packet = packet(100.5, 20.2, 0.15);
+        assert_eq!(block_on(validate_packet(&provider, &packet)), None);
+    }
+
+    #[test]
+    fn test_validate_packet_rejects_a_divergent_price() {
+        let provider = FakeProvider { quote: Some(quote(100.0, 20.0, 0.1)), unreachable: false };
+        let packet = packet(110.0, 20.0, 0.1);
+        let reason = block_on(validate_packet(&provider, &packet)).expect("should reject");
+        assert!(reason.contains("price"));
+    }
+
+    #[test]
+    fn test_validate_packet_rejects_a_divergent_vix_level() {
+        let provider = FakeProvider { quote: Some(quote(100.0, 20.0, 0.1)), unreachable: false };
+        let packet = packet(100.0, 30.0, 0.1);
+        let reason = block_on(validate_packet(&provider, &packet)).expect("should reject");
+        assert!(reason.contains("VIX"));
+    }
+
+    #[test]
+    fn test_validate_packet_rejects_a_divergent_spread() {
+        let provider = FakeProvider { quote: Some(quote(100.0, 20.0, 0.1)), unreachable: false };
+        let packet = packet(100.0, 20.0, 1.0);
+        let reason = block_on(validate_packet(&provider, &packet)).expect("should reject");
+        assert!(reason.contains("spread"));
+    }
+
+    #[test]
+    fn test_validate_packet_rejects_a_symbol_unknown_to_the_provider() {
+        let provider = FakeProvider { quote: None, unreachable: false };
+        let packet = packet(100.0, 20.0, 0.1);
+        let reason = block_on(validate_packet(&provider, &packet)).expect("should reject");
+        assert!(reason.contains("unknown"));
+    }
+
+    #[test]
+    fn test_validate_packet_degrades_open_when_the_provider_is_unreachable() {
+        let provider = FakeProvider { quote: None, unreachable: true };
+        let packet = packet(100.0, 20.0, 0.1);
+        assert_eq!(block_on(validate_packet(&provider, &packet)), None);
+    }
+}
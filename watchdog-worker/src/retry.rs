@@ -0,0 +1,88 @@
+//! Retry helper for outbound HTTP calls
+//!
+//! Both the `/cortex` proxy to the Java Core and `AlpacaClient::close_all_positions`
+//! go through it: a single `reqwest` call with no retry would let a transient 429/503
+//! during the dead man's switch permanently leak open positions. `with_retry` wraps any `reqwest`
+//! call with full-jitter exponential backoff, retrying only on 429/5xx responses and
+//! transport-level errors - a 4xx (other than 429) is treated as terminal and handed
+//! straight back.
+
+use std::time::Duration;
+use worker::{console_warn, Delay};
+
+/// Backoff policy for `with_retry`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub cap_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, base_delay_ms: 250, cap_delay_ms: 8_000 }
+    }
+}
+
+/// Run `op` (a `reqwest` call), retrying on a 429/5xx response or a transport-level
+/// error up to `policy.max_attempts` times total. Full-jitter exponential backoff
+/// (`delay = rand(0, min(cap, base * 2^attempt))`) between attempts, except when a
+/// retried response carries a `Retry-After` header - that overrides the computed
+/// backoff with whatever the server asked for. A 2xx or a non-429 4xx is returned to
+/// the caller immediately (the former to use, the latter to report - not retryable).
+pub async fn with_retry<F, Fut>(op: F, policy: RetryPolicy) -> Result<reqwest::Response, reqwest::Error>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match op().await {
+            Ok(response) => {
+                let status = response.status();
+                let retryable = status.as_u16() == 429 || status.is_server_error();
+                if !retryable || attempt + 1 >= policy.max_attempts {
+                    return Ok(response);
+                }
+
+                let retry_after_ms = retry_after_delay_ms(&response);
+                attempt += 1;
+                let delay_ms = retry_after_ms.unwrap_or_else(|| full_jitter_delay_ms(attempt, &policy));
+                console_warn!(
+                    "Retrying after {}ms (attempt {}/{}), status {}",
+                    delay_ms, attempt, policy.max_attempts, status
+                );
+                Delay::from(Duration::from_millis(delay_ms)).await;
+            }
+            Err(e) => {
+                if attempt + 1 >= policy.max_attempts {
+                    return Err(e);
+                }
+                attempt += 1;
+                let delay_ms = full_jitter_delay_ms(attempt, &policy);
+                console_warn!(
+                    "Retrying after {}ms (attempt {}/{}), transport error: {}",
+                    delay_ms, attempt, policy.max_attempts, e
+                );
+                Delay::from(Duration::from_millis(delay_ms)).await;
+            }
+        }
+    }
+}
+
+/// Parse a `Retry-After` header (seconds) off a retryable response, if present.
+fn retry_after_delay_ms(response: &reqwest::Response) -> Option<u64> {
+    let seconds: u64 = response.headers().get("Retry-After")?.to_str().ok()?.parse().ok()?;
+    Some(seconds * 1000)
+}
+
+/// Full-jitter exponential backoff: a uniformly random delay in `[0, min(cap, base *
+/// 2^attempt)]`. Derives its randomness from a fresh UUID's low bits rather than
+/// pulling in `rand` for one fraction - the same trick `coinbase-worker`'s
+/// `CoinbaseClient::backoff_delay` uses for its own retry jitter.
+fn full_jitter_delay_ms(attempt: u32, policy: &RetryPolicy) -> u64 {
+    let max_delay = policy.base_delay_ms.saturating_mul(1u64 << attempt.min(32)).min(policy.cap_delay_ms);
+    let jitter_fraction = (uuid::Uuid::new_v4().as_u128() & 0xFFFF) as f64 / u16::MAX as f64;
+    (max_delay as f64 * jitter_fraction).round() as u64
+}
@@ -0,0 +1,74 @@
+use worker::*;
+
+use crate::d1;
+
+/// Per-component alert state, as persisted via `d1::get_alert_state`/`save_alert_state`
+const STATE_STALE: &str = "stale";
+const STATE_ALIVE: &str = "alive";
+
+/// Check every `(component, threshold_ms)` pair's most recent heartbeat against its own
+/// staleness threshold and fire a webhook alert on state transitions only - a component
+/// already known stale doesn't re-alert every poll, and recovering from stale back to
+/// alive gets its own alert. Modeled on `check_health`'s dead-man's-switch, generalized
+/// from the single hardcoded "java-core" component to an arbitrary set so each scanner/
+/// trading loop reporting its own heartbeats gets independent monitoring.
+pub async fn run_heartbeat_monitor(env: &Env, components: &[&str], thresholds: &[u64]) -> Result<()> {
+    if components.len() != thresholds.len() {
+        return Err(Error::RustError(
+            "run_heartbeat_monitor: components and thresholds must be the same length".into(),
+        ));
+    }
+
+    let now = Date::now().as_millis() as u64;
+
+    for (component, threshold_ms) in components.iter().zip(thresholds.iter()) {
+        let last_beat = d1::get_last_heartbeat_for(env, component).await?;
+        let current_state = match last_beat {
+            Some(timestamp) if now.saturating_sub(timestamp) <= *threshold_ms => STATE_ALIVE,
+            _ => STATE_STALE,
+        };
+
+        let previous_state = d1::get_alert_state(env, component).await?;
+        if previous_state.as_deref() == Some(current_state) {
+            // No transition - already alerted (or already known alive) for this state.
+            continue;
+        }
+
+        console_warn!(
+            "⚠️ Component '{}' transitioned {} -> {}",
+            component,
+            previous_state.as_deref().unwrap_or("unknown"),
+            current_state
+        );
+        send_alert(env, component, current_state, last_beat, now).await?;
+        d1::save_alert_state(env, component, current_state, now).await?;
+    }
+
+    Ok(())
+}
+
+/// POST a transition alert to `ALERT_WEBHOOK_URL`. Missing the secret is treated as
+/// "alerting isn't configured" rather than an error, so a watchdog without a webhook set
+/// up still tracks state transitions and logs them via `console_warn!`.
+async fn send_alert(env: &Env, component: &str, new_state: &str, last_beat: Option<u64>, now: u64) -> Result<()> {
+    let Ok(webhook_url) = env.secret("ALERT_WEBHOOK_URL") else {
+        return Ok(());
+    };
+
+    let payload = serde_json::json!({
+        "component": component,
+        "state": new_state,
+        "last_heartbeat_ms": last_beat,
+        "checked_at_ms": now,
+    });
+
+    let client = reqwest::Client::new();
+    client
+        .post(webhook_url.to_string())
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| Error::RustError(format!("Alert webhook failed for {component}: {e}")))?;
+
+    Ok(())
+}